@@ -2,11 +2,19 @@
 // Referenz: TODO.md Zeile 175-181
 // Verschoben: cancel_backup und run_backup_command von lib.rs hierher
 
-use crate::config::BackupJobConfig;
+use crate::config::{AppConfig, BackupJobConfig};
+use crate::error::RusticGuiError;
+use crate::rustic::backup::{BackupOptions, BackupProgress};
+use crate::scheduler::{JobPriority, QueueEntry};
 use crate::state::AppState;
-use crate::types::{BackupJobDto, RetentionPolicy};
+use crate::types::{
+    BackupJobDto, BackupJobUpdate, JobExecutionStatus, JobStage, JobStageResult, SnapshotDiffDto,
+    SnapshotDiffOptions,
+};
 use serde::Serialize;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tauri::Emitter;
 use uuid::Uuid;
 
@@ -15,7 +23,7 @@ use uuid::Uuid;
 struct BackupEvent {
     #[serde(rename = "type")]
     event_type: String, // "progress" | "completed" | "error"
-    progress: Option<crate::rustic::backup::BackupProgress>,
+    progress: Option<BackupProgress>,
     message: Option<String>,
     #[serde(rename = "jobId")]
     job_id: String,
@@ -34,6 +42,7 @@ struct BackupCancelEvent {
 /// Bricht ein laufendes Backup ab und sendet Cancellation-Event.
 ///
 /// Verwendet das Cancellation-Token-System um Backups sicher abzubrechen.
+/// Das Token bleibt registriert, bis das laufende Backup sauber beendet ist.
 /// Sendet ein `backup-cancelled` Event an das Frontend.
 ///
 /// # Arguments
@@ -62,8 +71,8 @@ pub async fn cancel_backup(
     job_id: String,
     state: tauri::State<'_, AppState>,
 ) -> std::result::Result<(), crate::types::ErrorDto> {
-    let mut tokens = state.cancellation_tokens.lock();
-    if let Some(token) = tokens.remove(&job_id) {
+    let tokens = state.cancellation_tokens.lock();
+    if let Some(token) = tokens.get(&job_id) {
         token.cancel();
         let event = BackupCancelEvent {
             event_type: "cancelled".to_string(),
@@ -73,6 +82,15 @@ pub async fn cancel_backup(
         let _ = app.emit("backup-cancelled", &event);
         tracing::info!(job = %job_id, "Backup-Abbruch ausgelöst");
         Ok(())
+    } else if state.job_queue.remove_queued(&job_id) {
+        let event = BackupCancelEvent {
+            event_type: "cancelled".to_string(),
+            job_id: job_id.clone(),
+            message: Some("Backup wurde aus der Warteschlange entfernt".to_string()),
+        };
+        let _ = app.emit("backup-cancelled", &event);
+        tracing::info!(job = %job_id, "Wartendes Backup aus der Warteschlange entfernt");
+        Ok(())
     } else {
         tracing::warn!(job = %job_id, "Kein laufender Backup-Job zum Abbrechen gefunden");
         Err(crate::types::ErrorDto {
@@ -91,13 +109,16 @@ pub async fn cancel_backup(
 ///
 /// # Arguments
 /// * `app` - Tauri AppHandle für Event-Emission
-/// * `options` - Backup-Optionen (Source-Pfade, Exclude-Patterns, Tags, etc.)
+/// * `options` - Backup-Optionen (Source-Pfade, Exclude-Patterns, Tags, etc.); ohne
+///   `job_id` erhält der Lauf eine eigene ID (siehe `jobId` der Events)
+/// * `state` - AppState mit Cancellation-Tokens
 ///
 /// # Returns
 /// `Result<(), ErrorDto>` - Ok bei erfolgreichem Backup, Fehler sonst
 ///
 /// # Errors
-/// - `BackupFailed`: Backup konnte nicht abgeschlossen werden
+/// - `BackupFailed`: Backup konnte nicht abgeschlossen werden oder läuft bereits
+/// - `BackupCancelled`: Backup wurde über `cancel_backup` abgebrochen
 /// - `RepositoryLocked`: Repository ist durch anderen Prozess gesperrt
 /// - `IoError`: Dateisystem-Fehler während Backup
 ///
@@ -135,16 +156,34 @@ pub async fn cancel_backup(
 #[tauri::command]
 pub async fn run_backup_command(
     app: tauri::AppHandle,
-    mut options: crate::rustic::backup::BackupOptions,
+    mut options: BackupOptions,
+    state: tauri::State<'_, AppState>,
 ) -> std::result::Result<(), crate::types::ErrorDto> {
     tracing::info!("run_backup_command aufgerufen");
-    let job_id = options.job_id.clone().unwrap_or_else(|| "default".to_string());
+    let job_id = options.job_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
     options.job_id = Some(job_id.clone());
 
+    // Auf freien Platz in der Job-Warteschlange warten
+    let repository_key = repository_queue_key(&state, &options.repository);
+    let _ticket =
+        state.job_queue.acquire(&job_id, &repository_key, JobPriority::High).await.map_err(
+            |e| crate::types::ErrorDto {
+                code: "BackupQueued".to_string(),
+                message: e.to_string(),
+                details: Some(format!("job_id: {}", job_id)),
+            },
+        )?;
+
+    let cancel_token =
+        state.register_cancellation_token(&job_id).map_err(|e| crate::types::ErrorDto::from(&e))?;
+    let recorder = ExecutionRecorder::start(&state, &job_id);
+
     // Closure für Progress-Events
     let app_progress = app.clone();
     let job_id_progress = job_id.clone();
-    let progress_callback = move |progress: crate::rustic::backup::BackupProgress| {
+    let record_progress = recorder.progress_sink();
+    let progress_callback = move |progress: BackupProgress| {
+        record_progress(progress.clone());
         let event = BackupEvent {
             event_type: "progress".to_string(),
             progress: Some(progress.clone()),
@@ -161,7 +200,13 @@ pub async fn run_backup_command(
     };
 
     // Backup ausführen und Events senden
-    match crate::rustic::backup::run_backup(app.clone(), options, progress_callback).await {
+    let result =
+        crate::rustic::backup::run_backup(app.clone(), options, cancel_token, progress_callback)
+            .await;
+    state.release_cancellation_token(&job_id);
+    recorder.finish(&state, result.as_deref());
+
+    match result {
         Ok(_) => {
             let event = BackupEvent {
                 event_type: "completed".to_string(),
@@ -172,6 +217,10 @@ pub async fn run_backup_command(
             let _ = app.emit("backup-completed", &event);
             Ok(())
         }
+        Err(e @ RusticGuiError::BackupCancelled) => {
+            // Das `backup-cancelled` Event wurde bereits von `cancel_backup` gesendet
+            Err(crate::types::ErrorDto::from(&e))
+        }
         Err(e) => {
             let event = BackupEvent {
                 event_type: "error".to_string(),
@@ -234,8 +283,14 @@ pub async fn create_backup_job(
         }
     }
 
+    // Validiere Retention-Policy (z.B. keep_within-Dauer)
+    if let Some(ref retention) = job.retention {
+        crate::commands::retention::rustic_retention_policy_to_keep_options(retention)?;
+    }
+
     // Erstelle Job-Konfiguration
     let job_id = Uuid::new_v4().to_string();
+    let schedule_since = job.schedule.as_ref().map(|_| chrono::Utc::now().to_rfc3339());
     let job_config = BackupJobConfig {
         id: job_id.clone(),
         name: job.name.trim().to_string(),
@@ -244,17 +299,11 @@ pub async fn create_backup_job(
         exclude_patterns: job.exclude_patterns.unwrap_or_default(),
         tags: job.tags, // Bereits Vec<String>, kein Option
         schedule: job.schedule,
-        retention: job
-            .retention
-            .map(|r| RetentionPolicy {
-                keep_last: r.keep_last,
-                keep_daily: r.keep_daily,
-                keep_weekly: r.keep_weekly,
-                keep_monthly: r.keep_monthly,
-                keep_yearly: r.keep_yearly,
-            })
-            .unwrap_or_default(),
+        retention: job.retention.unwrap_or_default(),
         enabled: true,
+        catch_up: job.catch_up.unwrap_or_default(),
+        schedule_since,
+        post_backup: job.post_backup.unwrap_or_default(),
         password: job.password, // Verwende Passwort aus DTO
     };
 
@@ -279,33 +328,39 @@ pub async fn create_backup_job(
 ///
 /// # Parameter
 /// - `job_id`: ID des zu aktualisierenden Jobs
-/// - `name`: Neuer Anzeigename (optional)
-/// - `source_paths`: Neue Quellpfade (optional)
-/// - `exclude_patterns`: Neue Ausschlussmuster (optional)
-/// - `tags`: Neue Tags (optional)
-/// - `schedule`: Neue Cron-Expression (optional)
-/// - `retention`: Neue Retention-Policy (optional)
-/// - `enabled`: Ob Job aktiviert ist (optional)
+/// - `update`: Zu ändernde Felder; nicht gesetzte Felder bleiben unverändert, ein leerer
+///   `schedule` entfernt den Zeitplan
+///
+/// Ändern sich Zeitplan oder Aktivierung, wird der Job im Scheduler sofort neu geplant.
+/// Schlägt das Speichern oder Planen fehl, bleibt der bisherige Job unverändert.
 #[tauri::command]
 pub async fn update_backup_job(
     job_id: String,
-    name: Option<String>,
-    source_paths: Option<Vec<String>>,
-    exclude_patterns: Option<Vec<String>>,
-    tags: Option<Vec<String>>,
-    schedule: Option<String>,
-    retention: Option<crate::types::RetentionPolicy>,
-    enabled: Option<bool>,
+    update: BackupJobUpdate,
     state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
+    let BackupJobUpdate {
+        name,
+        source_paths,
+        exclude_patterns,
+        tags,
+        schedule,
+        retention,
+        enabled,
+        catch_up,
+        post_backup,
+    } = update;
+
     // Hole existierenden Job
-    let mut job_config = {
+    let previous = {
         let config = state.config.lock();
         config
             .get_backup_job(&job_id)
             .ok_or_else(|| format!("Backup-Job '{}' nicht gefunden", job_id))?
             .clone()
     };
+    let mut job_config = previous.clone();
 
     // Validierung
     if let Some(ref name) = name {
@@ -342,33 +397,42 @@ pub async fn update_backup_job(
     }
 
     if let Some(schedule) = schedule {
-        job_config.schedule = Some(schedule);
+        if schedule.trim().is_empty() {
+            job_config.schedule = None;
+        } else {
+            validate_cron_expression(&schedule)?;
+            job_config.schedule = Some(schedule);
+        }
     }
 
     if let Some(retention) = retention {
-        job_config.retention = RetentionPolicy {
-            keep_last: retention.keep_last,
-            keep_daily: retention.keep_daily,
-            keep_weekly: retention.keep_weekly,
-            keep_monthly: retention.keep_monthly,
-            keep_yearly: retention.keep_yearly,
-        };
+        crate::commands::retention::rustic_retention_policy_to_keep_options(&retention)?;
+        job_config.retention = retention;
     }
 
     if let Some(enabled) = enabled {
         job_config.enabled = enabled;
     }
 
-    // Speichere aktualisierten Job
+    if let Some(catch_up) = catch_up {
+        job_config.catch_up = catch_up;
+    }
+
+    if let Some(post_backup) = post_backup {
+        job_config.post_backup = post_backup;
+    }
+
+    mark_schedule_change(&previous, &mut job_config);
+
+    // Speichere aktualisierten Job (Config, Disk und Scheduler transaktional)
     {
         let mut config = state.config.lock();
-        config.add_backup_job(job_config);
+        config.add_backup_job(job_config.clone());
     }
 
-    // Speichere Config auf Disk
-    state
-        .save_config()
-        .map_err(|e| format!("Job aktualisiert aber Config-Speicherung fehlgeschlagen: {}", e))?;
+    apply_job_update(&state, &app_handle, previous, job_config)
+        .await
+        .map_err(|e| format!("Job-Aktualisierung fehlgeschlagen: {}", e))?;
 
     tracing::info!("Backup-Job '{}' aktualisiert", job_id);
 
@@ -406,13 +470,23 @@ pub async fn delete_backup_job(
         .save_config()
         .map_err(|e| format!("Job gelöscht aber Config-Speicherung fehlgeschlagen: {}", e))?;
 
+    // Geplante Ausführungen des gelöschten Jobs entfernen
+    {
+        let mut scheduler_lock = state.scheduler.lock().await;
+        if let Some(scheduler) = scheduler_lock.as_mut().filter(|s| s.has_job(&job_id))
+            && let Err(e) = scheduler.remove_job(&job_id).await
+        {
+            tracing::warn!("Zeitplan von Job '{}' konnte nicht entfernt werden: {}", job_id, e);
+        }
+    }
+
     tracing::info!("Backup-Job '{}' gelöscht", job_id);
 
     Ok(())
 }
 
 /// Holt Details eines Backup-Jobs.
-/// TODO.md: Phase 1 Zeile 180 ✅ IMPLEMENTIERT (mit TODO für last_run/next_run)
+/// TODO.md: Phase 1 Zeile 180 ✅ IMPLEMENTIERT
 ///
 /// # Parameter
 /// - `job_id`: ID des Jobs
@@ -424,27 +498,14 @@ pub async fn get_backup_job(
     job_id: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<BackupJobDto, String> {
+    let next_runs = scheduled_next_runs(&state).await;
+
     let config = state.config.lock();
     let job = config
         .get_backup_job(&job_id)
         .ok_or_else(|| format!("Backup-Job '{}' nicht gefunden", job_id))?;
 
-    let dto = BackupJobDto {
-        id: job.id.clone(),
-        name: job.name.clone(),
-        repository_id: job.repository_id.clone(),
-        source_paths: job.source_paths.iter().map(|p| p.to_string_lossy().to_string()).collect(),
-        exclude_patterns: Some(job.exclude_patterns.clone()),
-        tags: job.tags.clone(),
-        schedule: job.schedule.clone(),
-        enabled: job.enabled,
-        last_run: None, // TODO: Implementieren wenn Job-History verfügbar
-        next_run: None, // TODO: Implementieren wenn Scheduler verfügbar
-        retention: Some(job.retention.clone().into()),
-        password: job.password.clone(), // Passwort mit-übertragen (bereits verschlüsselt in Config)
-    };
-
-    Ok(dto)
+    Ok(job_to_dto(job, &config, &next_runs))
 }
 
 /// Listet alle Backup-Jobs auf.
@@ -460,37 +521,54 @@ pub async fn list_backup_jobs(
     repository_id: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<BackupJobDto>, String> {
+    let next_runs = scheduled_next_runs(&state).await;
+
     let config = state.config.lock();
 
     let jobs = if let Some(repo_id) = repository_id {
-        config.get_backup_jobs_for_repository(&repo_id).into_iter().cloned().collect()
+        config.get_backup_jobs_for_repository(&repo_id)
     } else {
-        config.backup_jobs.clone()
+        config.backup_jobs.iter().collect()
     };
 
-    let dtos = jobs
-        .into_iter()
-        .map(|job| BackupJobDto {
-            id: job.id,
-            name: job.name,
-            repository_id: job.repository_id,
-            source_paths: job
-                .source_paths
-                .iter()
-                .map(|p| p.to_string_lossy().to_string())
-                .collect(),
-            exclude_patterns: Some(job.exclude_patterns),
-            tags: job.tags,
-            schedule: job.schedule,
-            enabled: job.enabled,
-            last_run: None, // TODO: Implementieren wenn Job-History verfügbar
-            next_run: None, // TODO: Implementieren wenn Scheduler verfügbar
-            retention: Some(job.retention.clone().into()),
-            password: job.password.clone(),
+    Ok(jobs.into_iter().map(|job| job_to_dto(job, &config, &next_runs)).collect())
+}
+
+/// Ermittelt die nächsten Ausführungszeitpunkte aller geplanten Jobs (Job-ID -> ISO 8601)
+async fn scheduled_next_runs(state: &AppState) -> HashMap<String, String> {
+    let scheduler_lock = state.scheduler.lock().await;
+    scheduler_lock
+        .as_ref()
+        .map(|scheduler| {
+            scheduler.next_runs().into_iter().map(|(id, next)| (id, next.to_rfc3339())).collect()
         })
-        .collect();
+        .unwrap_or_default()
+}
 
-    Ok(dtos)
+/// Konvertiert eine Job-Konfiguration in ein DTO.
+///
+/// `last_run` stammt aus der Job-History, `next_run` aus dem Scheduler.
+fn job_to_dto(
+    job: &BackupJobConfig,
+    config: &AppConfig,
+    next_runs: &HashMap<String, String>,
+) -> BackupJobDto {
+    BackupJobDto {
+        id: job.id.clone(),
+        name: job.name.clone(),
+        repository_id: job.repository_id.clone(),
+        source_paths: job.source_paths.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        exclude_patterns: Some(job.exclude_patterns.clone()),
+        tags: job.tags.clone(),
+        schedule: job.schedule.clone(),
+        enabled: job.enabled,
+        last_run: config.last_job_execution(&job.id).map(|e| e.started_at.clone()),
+        next_run: next_runs.get(&job.id).cloned(),
+        retention: Some(job.retention.clone()),
+        catch_up: Some(job.catch_up),
+        post_backup: Some(job.post_backup),
+        password: job.password.clone(), // Passwort mit-übertragen (bereits verschlüsselt in Config)
+    }
 }
 
 #[cfg(test)]
@@ -525,27 +603,102 @@ mod tests {
 
 // ===== M2: Backup-Ausführung =====
 
-/// Interne Backup-Logik (für scheduled Backups und Tauri Command)
-async fn run_backup_internal(
-    job_id: String,
-    password: Option<String>,
-    state: &AppState,
-    app_handle: tauri::AppHandle,
-) -> Result<String, String> {
-    tracing::info!(job_id = %job_id, "Backup wird ausgeführt");
+/// Protokolliert einen Backup-Lauf in der Job-History.
+///
+/// Legt beim Start einen `Running`-Eintrag an und aktualisiert ihn nach Ende des Laufs
+/// mit Endstatus, Snapshot-ID, verarbeiteten Dateien/Bytes, Laufzeit und Fehlermeldung.
+struct ExecutionRecorder {
+    execution: crate::types::JobExecution,
+    started: std::time::Instant,
+    last_progress: Arc<parking_lot::Mutex<Option<BackupProgress>>>,
+}
 
-    // 1. Job-Config laden
-    let job = {
-        let config = state.config.lock();
-        config
-            .backup_jobs
-            .iter()
-            .find(|j| j.id == job_id)
-            .ok_or_else(|| format!("Backup-Job '{}' nicht gefunden", job_id))?
-            .clone()
-    };
+impl ExecutionRecorder {
+    /// Legt einen `Running`-Eintrag für den Job an und speichert die History
+    fn start(state: &AppState, job_id: &str) -> Self {
+        let execution = crate::types::JobExecution {
+            job_id: job_id.to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+            status: crate::types::JobExecutionStatus::Running,
+            snapshot_id: None,
+            files_processed: 0,
+            bytes_processed: 0,
+            duration_seconds: None,
+            error_message: None,
+            stages: Vec::new(),
+        };
+        state.config.lock().add_job_execution(execution.clone());
+        persist_job_history(state, job_id);
+
+        Self {
+            execution,
+            started: std::time::Instant::now(),
+            last_progress: Arc::new(parking_lot::Mutex::new(None)),
+        }
+    }
 
-    // 2. Repository laden
+    /// Liefert einen Callback, der den zuletzt gemeldeten Fortschritt festhält
+    fn progress_sink(&self) -> impl Fn(BackupProgress) + Send + Sync + 'static {
+        let last_progress = self.last_progress.clone();
+        move |progress| *last_progress.lock() = Some(progress)
+    }
+
+    /// Schließt den Eintrag mit dem Ergebnis des Backups ab und speichert die History
+    ///
+    /// # Returns
+    /// Den abgeschlossenen Eintrag (für nachfolgende Wartungsschritte)
+    fn finish(
+        mut self,
+        state: &AppState,
+        result: Result<&str, &RusticGuiError>,
+    ) -> crate::types::JobExecution {
+        if let Some(progress) = self.last_progress.lock().take() {
+            self.execution.files_processed = progress.files_processed;
+            self.execution.bytes_processed = progress.bytes_uploaded;
+        }
+        self.execution.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        self.execution.duration_seconds = Some(self.started.elapsed().as_secs());
+        match result {
+            Ok(snapshot_id) => {
+                self.execution.status = JobExecutionStatus::Completed;
+                self.execution.snapshot_id = Some(snapshot_id.to_string());
+            }
+            Err(RusticGuiError::BackupCancelled) => {
+                self.execution.status = JobExecutionStatus::Cancelled;
+                self.execution.error_message = Some(RusticGuiError::BackupCancelled.to_string());
+            }
+            Err(e) => {
+                self.execution.status = JobExecutionStatus::Failed;
+                self.execution.error_message = Some(e.to_string());
+            }
+        }
+
+        let job_id = self.execution.job_id.clone();
+        tracing::info!(job_id = %job_id, status = ?self.execution.status, "Job-Ausführung protokolliert");
+        state.config.lock().update_job_execution(self.execution.clone());
+        persist_job_history(state, &job_id);
+        self.execution
+    }
+}
+
+/// Speichert die Konfiguration inkl. Job-History (Fehler werden nur protokolliert)
+fn persist_job_history(state: &AppState, job_id: &str) {
+    if let Err(e) = state.save_config() {
+        tracing::warn!(job_id = %job_id, "Job-History konnte nicht gespeichert werden: {}", e);
+    }
+}
+
+/// Erstellt die Backup-Optionen für einen Job
+///
+/// # Errors
+/// Gibt einen Fehler zurück wenn das Repository nicht existiert oder kein Passwort vorliegt
+fn backup_options_for_job(
+    job: &BackupJobConfig,
+    password: Option<String>,
+    state: &AppState,
+) -> Result<BackupOptions, String> {
+    // Repository laden
     let repo = {
         let config = state.config.lock();
         config
@@ -556,37 +709,261 @@ async fn run_backup_internal(
             .clone()
     };
 
-    // 3. Passwort bestimmen: Job-Config > Parameter > Fehler
-    let effective_password = job.password.or(password).ok_or_else(|| {
+    // Passwort bestimmen: Job-Config > Parameter > Fehler
+    let effective_password = job.password.clone().or(password).ok_or_else(|| {
         "Passwort erforderlich: Weder im Job gespeichert noch übergeben".to_string()
     })?;
 
-    // 4. Backup-Optionen erstellen
-    let backup_opts = crate::rustic::backup::BackupOptions {
+    Ok(BackupOptions {
         repository: repo.path.clone(),
         source_paths: job.source_paths.iter().map(|p| p.to_string_lossy().to_string()).collect(),
         password: Some(effective_password),
         tags: Some(job.tags.clone()),
         exclude: Some(job.exclude_patterns.clone()),
         compression: None,
-        job_id: Some(job_id.clone()),
+        job_id: Some(job.id.clone()),
+    })
+}
+
+/// Schlüssel eines Repositories für die Job-Warteschlange (Repository-ID, sonst Pfad)
+fn repository_queue_key(state: &AppState, repository_path: &str) -> String {
+    let config = state.config.lock();
+    config
+        .repositories
+        .iter()
+        .find(|r| r.path == repository_path)
+        .map(|r| r.id.clone())
+        .unwrap_or_else(|| repository_path.to_string())
+}
+
+/// Interne Backup-Logik (für scheduled Backups und Tauri Command)
+///
+/// Der Lauf wartet zunächst auf einen freien Platz in der Job-Warteschlange
+/// (`AppSettings::max_concurrent_backups`, Limit pro Repository). Jeder gestartete Lauf
+/// wird in der Job-History protokolliert und kann über `cancel_backup` abgebrochen werden.
+async fn run_backup_internal(
+    job_id: String,
+    password: Option<String>,
+    state: &AppState,
+    app_handle: tauri::AppHandle,
+    priority: JobPriority,
+) -> Result<String, String> {
+    tracing::info!(job_id = %job_id, "Backup wird ausgeführt");
+
+    // 1. Job-Config laden
+    let job = {
+        let config = state.config.lock();
+        config
+            .backup_jobs
+            .iter()
+            .find(|j| j.id == job_id)
+            .ok_or_else(|| format!("Backup-Job '{}' nicht gefunden", job_id))?
+            .clone()
     };
 
-    // 5. Backup ausführen mit Progress-Callback
-    let progress_callback = |_progress: crate::rustic::backup::BackupProgress| {
-        // Progress-Events werden bereits von rustic::backup::run_backup emitted
+    // 2. Auf freien Platz in der Job-Warteschlange warten
+    let _ticket = state
+        .job_queue
+        .acquire(&job_id, &job.repository_id, priority)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 3. Lauf registrieren (abbrechbar) und in der History anlegen
+    let cancel_token = state.register_cancellation_token(&job_id).map_err(|e| e.to_string())?;
+    let recorder = ExecutionRecorder::start(state, &job_id);
+
+    // 4. Backup-Optionen erstellen (Repository + Passwort)
+    let backup_opts = match backup_options_for_job(&job, password, state) {
+        Ok(opts) => opts,
+        Err(reason) => {
+            state.release_cancellation_token(&job_id);
+            recorder.finish(state, Err(&RusticGuiError::BackupFailed { reason: reason.clone() }));
+            return Err(reason);
+        }
     };
 
-    let snapshot_id =
-        crate::rustic::backup::run_backup(app_handle.clone(), backup_opts, progress_callback)
-            .await
-            .map_err(|e| format!("Backup fehlgeschlagen: {:?}", e))?;
+    // 5. Backup ausführen (Progress-Events werden von rustic::backup::run_backup emitted)
+    let repository = backup_opts.repository.clone();
+    let repo_password = backup_opts.password.clone().unwrap_or_default();
+    let result = crate::rustic::backup::run_backup(
+        app_handle.clone(),
+        backup_opts,
+        cancel_token,
+        recorder.progress_sink(),
+    )
+    .await;
+    state.release_cancellation_token(&job_id);
+    let mut execution = recorder.finish(state, result.as_deref());
+
+    let snapshot_id = match result {
+        Ok(snapshot_id) => snapshot_id,
+        Err(e @ RusticGuiError::BackupCancelled) => return Err(e.to_string()),
+        Err(e) => return Err(format!("Backup fehlgeschlagen: {:?}", e)),
+    };
 
     tracing::info!(job_id = %job_id, snapshot_id = %snapshot_id, "Backup erfolgreich abgeschlossen");
 
+    // 6. Wartungsschritte (Retention, Prune) ausführen und in der History vermerken
+    execution.stages =
+        run_post_backup_stages(state, &job, repository, repo_password, &snapshot_id).await;
+    if !execution.stages.is_empty() {
+        let _ = app_handle.emit("job-stages-completed", &execution);
+        state.config.lock().update_job_execution(execution);
+        persist_job_history(state, &job_id);
+    }
+
     Ok(snapshot_id)
 }
 
+/// Führt die Wartungsschritte nach einem erfolgreichen Backup aus
+///
+/// Die Retention-Policy wird nur auf die Snapshots dieses Jobs angewendet, Prune läuft
+/// höchstens im konfigurierten Intervall. Nach fehlgeschlagener Retention wird kein Prune
+/// ausgeführt.
+///
+/// # Returns
+/// Ergebnisse der ausgeführten Schritte (leer, wenn keine Schritte konfiguriert sind)
+async fn run_post_backup_stages(
+    state: &AppState,
+    job: &BackupJobConfig,
+    repository: String,
+    password: String,
+    snapshot_id: &str,
+) -> Vec<JobStageResult> {
+    let apply_retention = job.post_backup.apply_retention;
+    let prune = job.post_backup.prune_interval_days.is_some_and(|days| {
+        let last_prune = state
+            .config
+            .lock()
+            .last_completed_stage(&job.id, JobStage::Prune)
+            .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.to_utc());
+        crate::rustic::maintenance::prune_due(last_prune, days, chrono::Utc::now())
+    });
+    if !apply_retention && !prune {
+        return Vec::new();
+    }
+
+    let keep = crate::commands::retention::rustic_retention_policy_to_keep_options(&job.retention);
+    let group_by = crate::commands::retention::retention_group_criterion(&job.retention);
+    let job_tags = job.tags.clone();
+    let snapshot_id = snapshot_id.to_string();
+
+    let stages = tokio::task::spawn_blocking(move || {
+        use crate::rustic::maintenance;
+
+        let mut stages = Vec::new();
+        let repo = match maintenance::open_repository(&repository, &password) {
+            Ok(repo) => repo,
+            Err(e) => {
+                let stage = if apply_retention { JobStage::Retention } else { JobStage::Prune };
+                stages.push(StageRecorder::start(stage).failed(e.to_string()));
+                return stages;
+            }
+        };
+
+        if apply_retention {
+            let recorder = StageRecorder::start(JobStage::Retention);
+            // Eine ungültige Policy ist ein Konfigurationsfehler, das Backup bleibt erfolgreich
+            let result = keep
+                .map_err(|e| {
+                    tracing::warn!("Retention-Policy ungültig: {}", e);
+                    RusticGuiError::InvalidConfig { field: "retention".into() }
+                })
+                .and_then(|keep| {
+                    maintenance::apply_job_retention(
+                        &repo,
+                        &snapshot_id,
+                        &job_tags,
+                        &keep,
+                        group_by,
+                    )
+                });
+            match result {
+                Ok(removed) => stages.push(recorder.completed(removed, None)),
+                Err(e) => {
+                    stages.push(recorder.failed(e.to_string()));
+                    return stages;
+                }
+            }
+        }
+
+        if prune {
+            let recorder = StageRecorder::start(JobStage::Prune);
+            match maintenance::prune(&repo) {
+                Ok(result) => stages.push(recorder.completed(Vec::new(), Some(result))),
+                Err(e) => stages.push(recorder.failed(e.to_string())),
+            }
+        }
+
+        stages
+    })
+    .await;
+
+    match stages {
+        Ok(stages) => {
+            for stage in &stages {
+                tracing::info!(job_id = %job.id, stage = ?stage.stage, status = ?stage.status, "Wartungsschritt abgeschlossen");
+            }
+            stages
+        }
+        Err(e) => {
+            tracing::error!(job_id = %job.id, "Wartungsschritte abgebrochen: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Misst Start und Dauer eines Wartungsschritts
+struct StageRecorder {
+    stage: JobStage,
+    started_at: String,
+    started: std::time::Instant,
+}
+
+impl StageRecorder {
+    fn start(stage: JobStage) -> Self {
+        Self {
+            stage,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            started: std::time::Instant::now(),
+        }
+    }
+
+    fn result(self, status: JobExecutionStatus) -> JobStageResult {
+        JobStageResult {
+            stage: self.stage,
+            status,
+            started_at: self.started_at,
+            duration_seconds: Some(self.started.elapsed().as_secs()),
+            snapshots_removed: Vec::new(),
+            packs_removed: None,
+            bytes_freed: None,
+            error_message: None,
+        }
+    }
+
+    fn completed(
+        self,
+        snapshots_removed: Vec<String>,
+        prune: Option<crate::types::PruneResultDto>,
+    ) -> JobStageResult {
+        JobStageResult {
+            snapshots_removed,
+            packs_removed: prune.as_ref().map(|p| p.packs_removed),
+            bytes_freed: prune.as_ref().map(|p| p.size_removed),
+            ..self.result(JobExecutionStatus::Completed)
+        }
+    }
+
+    fn failed(self, error_message: String) -> JobStageResult {
+        JobStageResult {
+            error_message: Some(error_message),
+            ..self.result(JobExecutionStatus::Failed)
+        }
+    }
+}
+
 /// Führt einen Backup-Job aus
 ///
 /// # Arguments
@@ -614,7 +991,7 @@ pub async fn run_backup(
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<String, String> {
-    run_backup_internal(job_id, password, &state, app_handle).await
+    run_backup_internal(job_id, password, &state, app_handle, JobPriority::High).await
 }
 
 // ===== M3: Job-Scheduler Commands =====
@@ -675,78 +1052,284 @@ pub async fn schedule_backup(
     // Validiere Cron-Expression
     validate_cron_expression(&cron_expression)?;
 
-    // Prüfe ob Job existiert
-    let job_exists = {
-        let config = state.config.lock();
-        config.backup_jobs.iter().any(|j| j.id == job_id)
+    set_job_schedule(&state, &app_handle, &job_id, Some(cron_expression.clone())).await?;
+
+    tracing::info!("Backup-Job '{}' geplant mit Cron-Expression: {}", job_id, cron_expression);
+
+    Ok(())
+}
+
+/// Setzt den Zeitplan eines Jobs transaktional.
+///
+/// Aktualisiert `BackupJobConfig::schedule`, speichert die Konfiguration und gleicht den
+/// Scheduler ab. Schlägt ein Schritt fehl, werden Konfiguration und Scheduler auf den
+/// vorherigen Stand zurückgesetzt. Deaktivierte Jobs werden nur in der Konfiguration
+/// geplant, aber nicht im Scheduler registriert.
+///
+/// # Arguments
+/// * `state` - AppState mit Scheduler und Konfiguration
+/// * `app_handle` - App-Handle für Events
+/// * `job_id` - ID des Jobs
+/// * `schedule` - Neue Cron-Expression oder None zum Entplanen
+///
+/// # Errors
+/// Gibt einen Fehler zurück wenn der Job nicht existiert, die Konfiguration nicht
+/// gespeichert werden kann oder das (Ent-)Planen im Scheduler fehlschlägt
+async fn set_job_schedule(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    schedule: Option<String>,
+) -> Result<(), String> {
+    let (previous, updated) = {
+        let mut config = state.config.lock();
+        let job = config
+            .backup_jobs
+            .iter_mut()
+            .find(|j| j.id == job_id)
+            .ok_or_else(|| format!("Backup-Job '{}' nicht gefunden", job_id))?;
+        let previous = job.clone();
+        job.schedule = schedule;
+        mark_schedule_change(&previous, job);
+        (previous, job.clone())
+    };
+
+    apply_job_update(state, app_handle, previous, updated).await
+}
+
+/// Übernimmt eine bereits in der Konfiguration eingetragene Job-Änderung transaktional.
+///
+/// Speichert die Konfiguration und gleicht den Scheduler an den neuen Zeitplan an. Bei
+/// einem Fehler wird `previous` wiederhergestellt.
+async fn apply_job_update(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    previous: BackupJobConfig,
+    updated: BackupJobConfig,
+) -> Result<(), String> {
+    let job_id = updated.id.clone();
+
+    // 1. Konfiguration speichern
+    if let Err(e) = state.save_config() {
+        state.config.lock().add_backup_job(previous);
+        return Err(format!("Config-Speicherung fehlgeschlagen: {}", e));
+    }
+
+    // 2. Scheduler abgleichen
+    let old_schedule = effective_schedule(&previous);
+    let new_schedule = effective_schedule(&updated);
+    if old_schedule == new_schedule {
+        return Ok(());
+    }
+
+    if let Err(e) = sync_scheduler(state, app_handle, &job_id, old_schedule, new_schedule).await {
+        tracing::warn!(job_id = %job_id, "Scheduling fehlgeschlagen, setze Konfiguration zurück");
+        state.config.lock().add_backup_job(previous);
+        if let Err(save_err) = state.save_config() {
+            tracing::error!(job_id = %job_id, "Rollback der Konfiguration fehlgeschlagen: {}", save_err);
+        }
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Zeitplan, mit dem ein Job im Scheduler registriert sein soll
+fn effective_schedule(job: &BackupJobConfig) -> Option<&str> {
+    job.schedule.as_deref().filter(|_| job.enabled)
+}
+
+/// Setzt `schedule_since` neu, wenn sich der wirksame Zeitplan ändert
+fn mark_schedule_change(previous: &BackupJobConfig, updated: &mut BackupJobConfig) {
+    if effective_schedule(previous) != effective_schedule(updated) {
+        updated.schedule_since =
+            effective_schedule(updated).map(|_| chrono::Utc::now().to_rfc3339());
+    }
+}
+
+/// Ersetzt die Scheduler-Registrierung eines Jobs (mit Rollback auf `old_schedule`)
+async fn sync_scheduler(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    old_schedule: Option<&str>,
+    new_schedule: Option<&str>,
+) -> Result<(), String> {
+    {
+        let mut scheduler_lock = state.scheduler.lock().await;
+        let scheduler =
+            scheduler_lock.as_mut().ok_or_else(|| "Scheduler nicht initialisiert".to_string())?;
+        if scheduler.has_job(job_id) {
+            scheduler
+                .remove_job(job_id)
+                .await
+                .map_err(|e| format!("Job entfernen fehlgeschlagen: {}", e))?;
+        }
+    }
+
+    let Some(new_schedule) = new_schedule else {
+        return Ok(());
     };
 
-    if !job_exists {
-        return Err(format!("Backup-Job '{}' nicht gefunden", job_id));
+    if let Err(e) = register_scheduled_backup(state, app_handle, job_id, new_schedule).await {
+        if let Some(old_schedule) = old_schedule
+            && let Err(restore_err) =
+                register_scheduled_backup(state, app_handle, job_id, old_schedule).await
+        {
+            tracing::error!(job_id = %job_id, "Alter Zeitplan konnte nicht wiederhergestellt werden: {}", restore_err);
+        }
+        return Err(e);
     }
 
-    // Hole Scheduler
+    Ok(())
+}
+
+/// Registriert einen Backup-Job im Scheduler.
+///
+/// Gemeinsamer Pfad für `schedule_backup` und die Wiederherstellung geplanter Jobs beim
+/// App-Start. Der Callback führt das Backup über `run_backup_internal` aus und sendet
+/// `scheduled-backup-started`, `scheduled-backup-completed` bzw. `scheduled-backup-failed`.
+///
+/// # Arguments
+/// * `state` - AppState mit Scheduler und Konfiguration
+/// * `app_handle` - App-Handle für Events
+/// * `job_id` - ID des zu planenden Jobs
+/// * `cron_expression` - Cron-Expression (6 Felder)
+///
+/// # Errors
+/// Gibt einen Fehler zurück wenn der Scheduler nicht initialisiert ist oder das
+/// Registrieren fehlschlägt
+pub(crate) async fn register_scheduled_backup(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    cron_expression: &str,
+) -> Result<(), String> {
     let mut scheduler_lock = state.scheduler.lock().await;
     let scheduler =
         scheduler_lock.as_mut().ok_or_else(|| "Scheduler nicht initialisiert".to_string())?;
 
-    // Erstelle Callback für geplanten Backup
-    let job_id_clone = job_id.clone();
+    let job_id_clone = job_id.to_string();
     let app_handle_clone = app_handle.clone();
-    let state_clone = state.inner().clone();
+    let state_clone = state.clone();
 
     scheduler
-        .schedule_job(job_id.clone(), &cron_expression, move || {
-            let job_id = job_id_clone.clone();
-            let app_handle = app_handle_clone.clone();
-            let state = state_clone.clone();
-
-            Box::pin(async move {
-                tracing::info!("Scheduled backup gestartet: {}", job_id);
-
-                // Event: Backup gestartet
-                let _ = app_handle.emit(
-                    "scheduled-backup-started",
-                    serde_json::json!({
-                        "job_id": job_id,
-                        "time": chrono::Utc::now().to_rfc3339(),
-                    }),
-                );
-
-                // Backup ausführen mit run_backup_internal
-                // Passwort wird aus Job-Config geladen (muss dort gespeichert sein für Scheduled Backups)
-                match run_backup_internal(job_id.clone(), None, &state, app_handle.clone()).await {
-                    Ok(snapshot_id) => {
-                        let _ = app_handle.emit(
-                            "scheduled-backup-completed",
-                            serde_json::json!({
-                                "job_id": job_id,
-                                "snapshot_id": snapshot_id,
-                                "time": chrono::Utc::now().to_rfc3339(),
-                            }),
-                        );
-                        tracing::info!(job_id = %job_id, snapshot_id = %snapshot_id, "Scheduled backup erfolgreich");
-                    }
-                    Err(e) => {
-                        let _ = app_handle.emit(
-                            "scheduled-backup-failed",
-                            serde_json::json!({
-                                "job_id": job_id,
-                                "error": e.to_string(),
-                                "time": chrono::Utc::now().to_rfc3339(),
-                            }),
-                        );
-                        tracing::error!(job_id = %job_id, error = %e, "Scheduled backup fehlgeschlagen");
-                    }
-                }
-            })
+        .schedule_job(job_id.to_string(), cron_expression, move || {
+            Box::pin(run_scheduled_backup(
+                job_id_clone.clone(),
+                state_clone.clone(),
+                app_handle_clone.clone(),
+                JobPriority::Normal,
+            ))
         })
         .await
-        .map_err(|e| format!("Scheduling fehlgeschlagen: {}", e))?;
+        .map_err(|e| format!("Scheduling fehlgeschlagen: {}", e))
+}
 
-    tracing::info!("Backup-Job '{}' geplant mit Cron-Expression: {}", job_id, cron_expression);
+/// Führt einen geplanten Backup-Lauf aus und meldet Start und Ergebnis per Event
+///
+/// Wartet der Job bereits in der Warteschlange, wird der erneute Auslöser verworfen.
+async fn run_scheduled_backup(
+    job_id: String,
+    state: AppState,
+    app_handle: tauri::AppHandle,
+    priority: JobPriority,
+) {
+    if state.job_queue.is_queued(&job_id) {
+        tracing::info!("Scheduled backup übersprungen, Job wartet bereits: {}", job_id);
+        return;
+    }
 
-    Ok(())
+    tracing::info!("Scheduled backup gestartet: {}", job_id);
+
+    // Event: Backup gestartet
+    let _ = app_handle.emit(
+        "scheduled-backup-started",
+        serde_json::json!({
+            "job_id": job_id,
+            "time": chrono::Utc::now().to_rfc3339(),
+        }),
+    );
+
+    // Backup ausführen mit run_backup_internal
+    // Passwort wird aus Job-Config geladen (muss dort gespeichert sein für Scheduled Backups)
+    match run_backup_internal(job_id.clone(), None, &state, app_handle.clone(), priority).await {
+        Ok(snapshot_id) => {
+            let _ = app_handle.emit(
+                "scheduled-backup-completed",
+                serde_json::json!({
+                    "job_id": job_id,
+                    "snapshot_id": snapshot_id,
+                    "time": chrono::Utc::now().to_rfc3339(),
+                }),
+            );
+            tracing::info!(job_id = %job_id, snapshot_id = %snapshot_id, "Scheduled backup erfolgreich");
+        }
+        Err(e) => {
+            let _ = app_handle.emit(
+                "scheduled-backup-failed",
+                serde_json::json!({
+                    "job_id": job_id,
+                    "error": e.to_string(),
+                    "time": chrono::Utc::now().to_rfc3339(),
+                }),
+            );
+            tracing::error!(job_id = %job_id, error = %e, "Scheduled backup fehlgeschlagen");
+        }
+    }
+}
+
+/// Holt verpasste geplante Läufe nach (nach App-Start oder Aufwachen aus dem Ruhezustand)
+///
+/// Vergleicht für jeden aktiven, geplanten Job den letzten Cron-Termin mit dem letzten
+/// Eintrag der Job-History und startet verpasste Läufe gemäß `BackupJobConfig::catch_up`.
+/// Die Läufe werden nacheinander in einem Hintergrund-Task ausgeführt.
+///
+/// # Arguments
+/// * `state` - AppState mit Konfiguration und Job-History
+/// * `app_handle` - App-Handle für Events
+///
+/// # Returns
+/// Anzahl der nachzuholenden Läufe
+pub(crate) fn catch_up_missed_runs(state: &AppState, app_handle: &tauri::AppHandle) -> usize {
+    let now = chrono::Utc::now();
+    let due: Vec<String> = {
+        let config = state.config.lock();
+        config
+            .backup_jobs
+            .iter()
+            .filter_map(|job| {
+                let schedule = effective_schedule(job)?;
+                let parse = |t: &str| chrono::DateTime::parse_from_rfc3339(t).ok().map(|t| t.to_utc());
+                let last_run =
+                    config.last_job_execution(&job.id).and_then(|e| parse(&e.started_at));
+                let schedule_since = job.schedule_since.as_deref().and_then(parse);
+                let missed = crate::scheduler::missed_run(
+                    schedule,
+                    last_run,
+                    schedule_since,
+                    now,
+                    job.catch_up,
+                )?;
+                tracing::info!(job_id = %job.id, missed = %missed, "Verpasster Lauf wird nachgeholt");
+                Some(job.id.clone())
+            })
+            .collect()
+    };
+
+    let count = due.len();
+    if count > 0 {
+        let state = state.clone();
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            for job_id in due {
+                run_scheduled_backup(job_id, state.clone(), app_handle.clone(), JobPriority::Low)
+                    .await;
+            }
+        });
+    }
+
+    count
 }
 
 /// Entfernt die Planung eines Backup-Jobs
@@ -765,15 +1348,21 @@ pub async fn schedule_backup(
 pub async fn unschedule_backup(
     job_id: String,
     state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    let mut scheduler_lock = state.scheduler.lock().await;
-    let scheduler =
-        scheduler_lock.as_mut().ok_or_else(|| "Scheduler nicht initialisiert".to_string())?;
+    let is_scheduled = {
+        let config = state.config.lock();
+        config.get_backup_job(&job_id).is_some_and(|j| j.schedule.is_some())
+    };
+    let is_registered = {
+        let scheduler_lock = state.scheduler.lock().await;
+        scheduler_lock.as_ref().is_some_and(|s| s.has_job(&job_id))
+    };
+    if !is_scheduled && !is_registered {
+        return Err(format!("Backup-Job '{}' ist nicht geplant", job_id));
+    }
 
-    scheduler
-        .remove_job(&job_id)
-        .await
-        .map_err(|e| format!("Job entfernen fehlgeschlagen: {}", e))?;
+    set_job_schedule(&state, &app_handle, &job_id, None).await?;
 
     tracing::info!("Backup-Job '{}' entplant", job_id);
 
@@ -821,9 +1410,60 @@ pub async fn list_job_history(
     Ok(executions)
 }
 
+/// Listet laufende und wartende Backup-Läufe der Job-Warteschlange
+///
+/// Änderungen werden zusätzlich als `job-queue-changed`-Event gesendet.
+///
+/// # Returns
+/// Laufende Einträge zuerst, danach wartende in Startreihenfolge
+#[tauri::command]
+pub async fn list_job_queue(state: tauri::State<'_, AppState>) -> Result<Vec<QueueEntry>, String> {
+    Ok(state.job_queue.entries())
+}
+
+/// Zeigt, was ein neues Backup eines Jobs aufnehmen würde
+///
+/// Vergleicht den letzten Snapshot des Jobs mit dem aktuellen Stand seiner Quellpfade
+/// im Dateisystem (gleiche Exclude- und .gitignore-Filter wie das Backup).
+///
+/// # Arguments
+/// * `job_id` - ID des Backup-Jobs
+/// * `password` - Repository-Passwort, falls nicht im Job gespeichert
+/// * `options` - Präfix-Filter, Paging und Rollup-Tiefe
+///
+/// # Returns
+/// Neue (`added`), geänderte (`changed`) und gelöschte (`removed`) Einträge mit Größen
+#[tauri::command]
+pub async fn diff_backup_source(
+    job_id: String,
+    password: Option<String>,
+    options: Option<SnapshotDiffOptions>,
+    state: tauri::State<'_, AppState>,
+) -> Result<SnapshotDiffDto, String> {
+    let job = {
+        let config = state.config.lock();
+        config
+            .backup_jobs
+            .iter()
+            .find(|j| j.id == job_id)
+            .ok_or_else(|| format!("Backup-Job '{}' nicht gefunden", job_id))?
+            .clone()
+    };
+    let backup_options = backup_options_for_job(&job, password, &state)?;
+    let options = options.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || {
+        crate::rustic::backup::diff_source_against_snapshot(&backup_options, &options)
+    })
+    .await
+    .map_err(|e| format!("Vergleichs-Task fehlgeschlagen: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod scheduler_tests {
     use super::*;
+    use crate::types::{CatchUpPolicy, PostBackupOptions, RetentionPolicy};
 
     #[test]
     fn test_validate_cron_expression() {
@@ -837,4 +1477,69 @@ mod scheduler_tests {
         assert!(validate_cron_expression("0 0 2 * *").is_err()); // Zu wenig Felder
         assert!(validate_cron_expression("0 0 2 * * * *").is_err()); // Zu viele Felder
     }
+
+    #[test]
+    fn test_effective_schedule_requires_enabled_job() {
+        let mut job = BackupJobConfig {
+            id: "job-1".to_string(),
+            name: "Job".to_string(),
+            repository_id: "repo-1".to_string(),
+            source_paths: vec![PathBuf::from("/home")],
+            exclude_patterns: vec![],
+            tags: vec![],
+            schedule: Some("0 0 2 * * *".to_string()),
+            retention: RetentionPolicy::default(),
+            enabled: true,
+            catch_up: CatchUpPolicy::default(),
+            schedule_since: None,
+            post_backup: PostBackupOptions::default(),
+            password: None,
+        };
+        assert_eq!(effective_schedule(&job), Some("0 0 2 * * *"));
+
+        job.enabled = false;
+        assert_eq!(effective_schedule(&job), None);
+
+        job.enabled = true;
+        job.schedule = None;
+        assert_eq!(effective_schedule(&job), None);
+    }
+
+    #[test]
+    fn test_mark_schedule_change_sets_reference_time() {
+        let previous = BackupJobConfig {
+            id: "job-1".to_string(),
+            name: "Job".to_string(),
+            repository_id: "repo-1".to_string(),
+            source_paths: vec![PathBuf::from("/home")],
+            exclude_patterns: vec![],
+            tags: vec![],
+            schedule: None,
+            retention: RetentionPolicy::default(),
+            enabled: true,
+            catch_up: CatchUpPolicy::default(),
+            schedule_since: None,
+            post_backup: PostBackupOptions::default(),
+            password: None,
+        };
+
+        // Neuer Zeitplan: Referenzzeitpunkt wird gesetzt
+        let mut updated = previous.clone();
+        updated.schedule = Some("0 0 2 * * *".to_string());
+        mark_schedule_change(&previous, &mut updated);
+        assert!(updated.schedule_since.is_some());
+
+        // Andere Änderungen lassen ihn unverändert
+        let mut renamed = updated.clone();
+        renamed.name = "Neu".to_string();
+        renamed.schedule_since = Some("2025-01-01T00:00:00+00:00".to_string());
+        mark_schedule_change(&updated, &mut renamed);
+        assert_eq!(renamed.schedule_since.as_deref(), Some("2025-01-01T00:00:00+00:00"));
+
+        // Deaktiviert: kein wirksamer Zeitplan mehr
+        let mut disabled = renamed.clone();
+        disabled.enabled = false;
+        mark_schedule_change(&renamed, &mut disabled);
+        assert_eq!(disabled.schedule_since, None);
+    }
 }
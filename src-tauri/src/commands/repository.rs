@@ -4,7 +4,6 @@
 
 use crate::state::AppState;
 use crate::types::RepositoryDto;
-use rustic_core::CheckOptions;
 use tauri::Emitter;
 
 /// Initialisiert ein neues Repository und speichert es in der Konfiguration.
@@ -247,32 +246,36 @@ pub fn get_repository_info(
 
 /// Prüft ein Repository auf Fehler oder Inkonsistenzen.
 ///
-/// Verwendet rustic_core's check() Methode mit CheckOptions.
-/// Referenz: https://github.com/rustic-rs/rustic/blob/main/src/commands/check.rs
+/// Die Prüfung läuft in `rustic::check` und liefert Befunde nach Kategorie
+/// (fehlende/unreferenzierte Packs, Index-Abweichungen, Snapshot- und Tree-Fehler,
+/// beschädigte Daten). Fortschritt wird über `check-progress`-Events gemeldet. Kann die
+/// Prüfung nicht durchgeführt werden (z.B. Repository nicht lesbar), wird ein Ergebnis mit
+/// `is_ok = false` und dem Fehler in `errors` geliefert.
 ///
 /// # Arguments
 /// * `repository_id` - ID des zu prüfenden Repositories
 /// * `trust_cache` - Cache vertrauen (schneller, weniger sicher)
 /// * `read_data` - Pack-Dateien lesen und verifizieren (langsamer, gründlicher)
-/// * `state` - AppState mit Repository-Cache
+/// * `read_data_subset` - Optional: Nur einen Teil der Packs lesen (Prozent oder Bytes)
+/// * `state` - AppState mit Repository-Konfiguration
 /// * `app_handle` - Tauri AppHandle für Progress-Events
 ///
 /// # Returns
-/// `Result<CheckResult, String>` - Check-Ergebnisse oder Fehler
+/// `Result<CheckResult, String>` - Check-Ergebnisse; Fehler nur bei unbekanntem
+/// Repository oder ungültiger Teilmenge
 #[tauri::command]
 pub async fn check_repository(
     repository_id: String,
     trust_cache: bool,
     read_data: bool,
+    read_data_subset: Option<crate::types::ReadDataSubset>,
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<crate::types::CheckResultDto, String> {
-    // Repository aus Cache holen (NICHT async!)
-    let repo = state
-        .get_repository(&repository_id)
+    let (repo_config, password) = state
+        .repository_credentials(&repository_id)
         .map_err(|e| format!("Repository nicht gefunden: {}", e))?;
 
-    // Progress-Events senden
     app_handle
         .emit(
             "check-started",
@@ -282,35 +285,47 @@ pub async fn check_repository(
         )
         .ok();
 
-    // CheckOptions erstellen (wie rustic CLI)
-    let opts = CheckOptions::default().trust_cache(trust_cache).read_data(read_data);
+    let progress_handle = app_handle.clone();
+    let progress_repo_id = repository_id.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        crate::rustic::check::check_repository(
+            &repo_config.path,
+            &password,
+            trust_cache,
+            read_data,
+            &read_data_subset.unwrap_or_default(),
+            |progress| {
+                progress_handle
+                    .emit(
+                        "check-progress",
+                        serde_json::json!({
+                            "repository_id": progress_repo_id,
+                            "progress": progress,
+                        }),
+                    )
+                    .ok();
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("Task-Fehler: {}", e))?;
 
-    // Repository prüfen (check() lädt automatisch alle Snapshots und ihre Trees!)
-    // WICHTIG: check() gibt Result<()> zurück, keine CheckResults!
-    // Fehler werden intern über tracing geloggt.
-    match repo.check(opts) {
-        Ok(_) => {
-            // Check erfolgreich
+    match result {
+        Ok(result) => {
             app_handle
                 .emit(
                     "check-completed",
                     serde_json::json!({
                         "repository_id": repository_id,
-                        "success": true,
+                        "success": result.is_ok,
+                        "summary": result.summary,
                     }),
                 )
                 .ok();
-
-            Ok(crate::types::CheckResultDto {
-                errors: Vec::new(),
-                warnings: Vec::new(),
-                is_ok: true,
-            })
+            Ok(result)
         }
         Err(e) => {
-            // Check fehlgeschlagen
-            let error_msg = format!("{}", e);
-
+            let error_msg = e.to_string();
             app_handle
                 .emit(
                     "check-completed",
@@ -321,16 +336,208 @@ pub async fn check_repository(
                     }),
                 )
                 .ok();
-
+            if matches!(e, crate::error::RusticGuiError::InvalidConfig { .. }) {
+                return Err(error_msg);
+            }
             Ok(crate::types::CheckResultDto {
                 errors: vec![error_msg],
-                warnings: Vec::new(),
                 is_ok: false,
+                ..Default::default()
             })
         }
     }
 }
 
+/// Repariert den Index eines Repositories (z.B. nach einem Stromausfall).
+///
+/// Ablauf in zwei Schritten: Ein Dry-Run liefert den Plan mit `plan_id`; ausgeführt wird
+/// erst, wenn diese `plan_id` bestätigt wird und der Plan noch aktuell ist.
+/// Fortschritt wird über `repair-progress`-Events gemeldet.
+///
+/// # Arguments
+/// * `repository_id` - ID des Repositories
+/// * `read_all` - Alle Pack-Header lesen (Index komplett neu aufbauen)
+/// * `dry_run` - Nur planen, keine Änderungen
+/// * `plan_id` - Bestätigte Plan-ID aus dem Dry-Run (Pflicht, wenn `dry_run` false ist)
+/// * `state` - AppState mit Repository-Konfiguration
+/// * `app_handle` - Tauri AppHandle für Progress-Events
+///
+/// # Returns
+/// `Result<RepairIndexPlanDto, String>` - Plan bzw. ausgeführter Plan
+#[tauri::command]
+pub async fn repair_index(
+    repository_id: String,
+    read_all: bool,
+    dry_run: bool,
+    plan_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<crate::types::RepairIndexPlanDto, String> {
+    let confirm = repair_confirmation(dry_run, plan_id)?;
+    let (repo_config, password) = state
+        .repository_credentials(&repository_id)
+        .map_err(|e| format!("Repository nicht gefunden: {}", e))?;
+
+    emit_repair_event(&app_handle, "repair-started", &repository_id, "index", dry_run, None);
+    let progress_handle = app_handle.clone();
+    let progress_repo_id = repository_id.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        crate::rustic::repair::repair_index(
+            &repo_config.path,
+            &password,
+            read_all,
+            confirm.as_deref(),
+            move |progress| {
+                emit_repair_progress(&progress_handle, &progress_repo_id, "index", progress)
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("Task-Fehler: {}", e))?;
+
+    finish_repair(&state, &app_handle, &repository_id, "index", dry_run, result)
+}
+
+/// Repariert beschädigte Snapshots (fehlende Trees oder Datei-Inhalte).
+///
+/// Ablauf wie bei `repair_index`: erst Dry-Run mit Plan, dann Ausführung mit bestätigter
+/// `plan_id`. Reparierte Snapshots werden neu gespeichert; Originale werden nur mit
+/// `options.delete` entfernt.
+///
+/// # Arguments
+/// * `repository_id` - ID des Repositories
+/// * `options` - Auswahl der Snapshots, Suffix, Tags, Originale löschen
+/// * `dry_run` - Nur planen, keine Änderungen
+/// * `plan_id` - Bestätigte Plan-ID aus dem Dry-Run (Pflicht, wenn `dry_run` false ist)
+/// * `state` - AppState mit Repository-Konfiguration
+/// * `app_handle` - Tauri AppHandle für Progress-Events
+///
+/// # Returns
+/// `Result<RepairSnapshotsPlanDto, String>` - Plan bzw. ausgeführter Plan
+#[tauri::command]
+pub async fn repair_snapshots(
+    repository_id: String,
+    options: Option<crate::types::RepairSnapshotsOptionsDto>,
+    dry_run: bool,
+    plan_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<crate::types::RepairSnapshotsPlanDto, String> {
+    let confirm = repair_confirmation(dry_run, plan_id)?;
+    let (repo_config, password) = state
+        .repository_credentials(&repository_id)
+        .map_err(|e| format!("Repository nicht gefunden: {}", e))?;
+
+    emit_repair_event(&app_handle, "repair-started", &repository_id, "snapshots", dry_run, None);
+    let progress_handle = app_handle.clone();
+    let progress_repo_id = repository_id.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        crate::rustic::repair::repair_snapshots(
+            &repo_config.path,
+            &password,
+            &options.unwrap_or_default(),
+            confirm.as_deref(),
+            move |progress| {
+                emit_repair_progress(&progress_handle, &progress_repo_id, "snapshots", progress)
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("Task-Fehler: {}", e))?;
+
+    finish_repair(&state, &app_handle, &repository_id, "snapshots", dry_run, result)
+}
+
+/// Ohne Dry-Run muss eine Plan-ID bestätigt werden
+fn repair_confirmation(dry_run: bool, plan_id: Option<String>) -> Result<Option<String>, String> {
+    match (dry_run, plan_id) {
+        (true, _) => Ok(None),
+        (false, Some(plan_id)) => Ok(Some(plan_id)),
+        (false, None) => {
+            Err("Reparatur muss mit der plan_id eines Dry-Runs bestätigt werden".to_string())
+        }
+    }
+}
+
+fn emit_repair_event(
+    app_handle: &tauri::AppHandle,
+    event: &str,
+    repository_id: &str,
+    operation: &str,
+    dry_run: bool,
+    error: Option<&str>,
+) {
+    app_handle
+        .emit(
+            event,
+            serde_json::json!({
+                "repository_id": repository_id,
+                "operation": operation,
+                "dry_run": dry_run,
+                "success": error.is_none(),
+                "error": error,
+            }),
+        )
+        .ok();
+}
+
+fn emit_repair_progress(
+    app_handle: &tauri::AppHandle,
+    repository_id: &str,
+    operation: &str,
+    progress: crate::types::RepairProgress,
+) {
+    app_handle
+        .emit(
+            "repair-progress",
+            serde_json::json!({
+                "repository_id": repository_id,
+                "operation": operation,
+                "progress": progress,
+            }),
+        )
+        .ok();
+}
+
+/// Meldet das Ende einer Reparatur und verwirft das gecachte Repository nach Änderungen
+fn finish_repair<T>(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    repository_id: &str,
+    operation: &str,
+    dry_run: bool,
+    result: crate::error::Result<T>,
+) -> Result<T, String> {
+    match result {
+        Ok(plan) => {
+            if !dry_run {
+                state.invalidate_repository_cache(repository_id);
+            }
+            emit_repair_event(
+                app_handle,
+                "repair-completed",
+                repository_id,
+                operation,
+                dry_run,
+                None,
+            );
+            Ok(plan)
+        }
+        Err(e) => {
+            let error_msg = e.to_string();
+            emit_repair_event(
+                app_handle,
+                "repair-completed",
+                repository_id,
+                operation,
+                dry_run,
+                Some(&error_msg),
+            );
+            Err(error_msg)
+        }
+    }
+}
+
 /// Prune-Operation: Entfernt unnötige Pack-Dateien aus dem Repository.
 ///
 /// Führt eine 2-stufige Prune-Operation durch:
@@ -586,7 +793,12 @@ pub fn switch_repository(
             crate::config::BackendType::Rest => crate::types::RepositoryType::Rest,
             crate::config::BackendType::Rclone => crate::types::RepositoryType::Rclone,
         },
-        status: crate::types::RepositoryStatus::Healthy,
+        // Lock-Dateien (z.B. von restic) werden als `Locked` angezeigt
+        status: if crate::rustic::locks::has_locks(&path) {
+            crate::types::RepositoryStatus::Locked
+        } else {
+            crate::types::RepositoryStatus::Healthy
+        },
         snapshot_count,
         total_size: 0,
         last_accessed: Some(chrono::Utc::now().to_rfc3339()),
@@ -977,9 +1189,13 @@ pub async fn delete_favorite_location(
 
 /// Holt detaillierte Statistiken für ein Repository
 /// M4.3: Repository-Statistiken Command
+///
+/// Die Statistiken werden pro Repository gecacht und nur neu berechnet, wenn sich
+/// Index- oder Snapshot-Dateien geändert haben oder `refresh` gesetzt ist.
 #[tauri::command]
 pub async fn get_repository_stats(
     id: String,
+    refresh: Option<bool>,
     state: tauri::State<'_, AppState>,
 ) -> Result<crate::types::RepositoryStatsDto, String> {
     tracing::debug!("Lade Statistiken für Repository '{}'", id);
@@ -989,9 +1205,28 @@ pub async fn get_repository_stats(
         .get_repository(&id)
         .map_err(|e| format!("Repository öffnen fehlgeschlagen: {}", e))?;
 
-    // Hole Statistiken mit rustic_core API
-    let stats = crate::rustic::repository::get_repository_stats(&*repo)
-        .map_err(|e| format!("Statistiken sammeln fehlgeschlagen: {}", e))?;
+    let fingerprint_repo = repo.clone();
+    let fingerprint = tokio::task::spawn_blocking(move || {
+        crate::rustic::repository::repository_stats_fingerprint(&fingerprint_repo)
+    })
+    .await
+    .map_err(|e| format!("Statistik-Task fehlgeschlagen: {e}"))?
+    .map_err(|e| format!("Statistiken sammeln fehlgeschlagen: {}", e))?;
+
+    if !refresh.unwrap_or(false)
+        && let Some(stats) = state.cached_repository_stats(&id, fingerprint)
+    {
+        tracing::debug!("Statistiken für Repository '{}' aus Cache geladen", id);
+        return Ok(stats);
+    }
+
+    // Hole Statistiken mit rustic_core API (liest alle Index-Dateien)
+    let stats =
+        tokio::task::spawn_blocking(move || crate::rustic::repository::get_repository_stats(&repo))
+            .await
+            .map_err(|e| format!("Statistik-Task fehlgeschlagen: {e}"))?
+            .map_err(|e| format!("Statistiken sammeln fehlgeschlagen: {}", e))?;
+    state.cache_repository_stats(&id, fingerprint, stats.clone());
 
     tracing::info!(
         "Statistiken für Repository '{}' geladen: {} Snapshots",
@@ -1001,3 +1236,51 @@ pub async fn get_repository_stats(
 
     Ok(stats)
 }
+
+/// Ermittelt die Speicherbelegung pro Snapshot, Host, Tag und Backup-Job
+///
+/// Liest den vollständigen Index und alle Trees des Repositories; kann bei großen
+/// Repositories einige Zeit dauern. Jobs werden über den Repository-Pfad zugeordnet.
+///
+/// # Returns
+/// Eigene und geteilte Belegung jeder Gruppe
+#[tauri::command]
+pub async fn get_storage_attribution(
+    repository_path: String,
+    password: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<crate::types::StorageAttributionDto, String> {
+    tracing::debug!("Berechne Speicherbelegung für Repository '{}'", repository_path);
+
+    let jobs: Vec<crate::rustic::attribution::AttributionJob> = {
+        let config = state.config.lock();
+        let repository_ids: Vec<&str> = config
+            .repositories
+            .iter()
+            .filter(|r| r.path == repository_path)
+            .map(|r| r.id.as_str())
+            .collect();
+        config
+            .backup_jobs
+            .iter()
+            .filter(|job| repository_ids.contains(&job.repository_id.as_str()))
+            .map(|job| crate::rustic::attribution::AttributionJob {
+                id: job.id.clone(),
+                name: job.name.clone(),
+                source_paths: job
+                    .source_paths
+                    .iter()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .collect(),
+                tags: job.tags.clone(),
+            })
+            .collect()
+    };
+
+    tokio::task::spawn_blocking(move || {
+        crate::rustic::attribution::storage_attribution(&repository_path, &password, &jobs)
+    })
+    .await
+    .map_err(|e| format!("Speicherbelegungs-Task fehlgeschlagen: {e}"))?
+    .map_err(|e| format!("Speicherbelegung berechnen fehlgeschlagen: {}", e))
+}
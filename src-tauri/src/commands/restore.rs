@@ -4,21 +4,31 @@
 //
 // Verwendung in Frontend: src/lib/api/restore.ts, RestoreDialog.svelte
 
-use crate::types::{FileTreeNode, RestoreOptionsDto, RestoreProgress};
+use crate::error::RusticGuiError;
+use crate::state::AppState;
+use crate::types::{
+    ArchiveFormat, ArchiveResultDto, ExtractedFileDto, FilePreviewDto, FileTreeNode,
+    RestoreOptionsDto, RestorePlanDto, RestoreProgress,
+};
 use serde::Serialize;
 use tauri::Emitter;
+use uuid::Uuid;
 
 /// Event-Format für Restore-Progress
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 struct RestoreEvent {
     #[serde(rename = "type")]
-    event_type: String, // "progress" | "completed" | "error"
+    event_type: String, // "started" | "progress" | "completed" | "cancelled" | "error"
     progress: Option<RestoreProgress>,
+    /// Ausgeführter Restore-Plan (nur bei "completed")
+    plan: Option<RestorePlanDto>,
     message: Option<String>,
     #[serde(rename = "snapshotId")]
     snapshot_id: String,
     #[serde(rename = "targetPath")]
     target_path: String,
+    #[serde(rename = "restoreId")]
+    restore_id: String,
 }
 
 /// Holt den Dateibaum eines Snapshots für File-Browser
@@ -40,95 +50,268 @@ pub async fn get_file_tree_command(
 }
 
 /// Restore-Operation mit Progress-Events
+///
+/// Der Restore läuft in einem Blocking-Thread und kann über `cancel_restore` mit der
+/// `restore_id` abgebrochen werden. Bei `options.dry_run` wird nur der Plan erstellt.
+///
+/// # Arguments
+/// * `restore_id` - Optionale ID für Events und Abbruch (wird sonst generiert)
+///
+/// # Returns
+/// Restore-Plan (ausgeführt bzw. bei Dry-Run geplant)
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn restore_files_v1(
     app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
     repository_path: String,
     password: String,
     snapshot_id: String,
     files: Vec<String>,
     target_path: String,
     options: RestoreOptionsDto,
-) -> std::result::Result<(), String> {
+    restore_id: Option<String>,
+) -> std::result::Result<RestorePlanDto, String> {
     tracing::info!("restore_files_command aufgerufen");
 
-    // Sende initialen Progress-Event
-    let total = files.len().max(1) as u64;
-    let initial_progress = RestoreProgress {
-        base: crate::types::ProgressInfo {
-            current: 0,
-            total,
-            message: Some("Starte Restore...".to_string()),
-            percentage: Some(0.0),
-        },
-        files_restored: 0,
-        bytes_restored: 0,
-        current_file: None,
+    let restore_id = restore_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let cancel_token = state.register_cancellation_token(&restore_id).map_err(|e| e.to_string())?;
+
+    let event = |event_type: &str, progress: Option<RestoreProgress>, message: Option<String>| {
+        RestoreEvent {
+            event_type: event_type.to_string(),
+            progress,
+            plan: None,
+            message,
+            snapshot_id: snapshot_id.clone(),
+            target_path: target_path.clone(),
+            restore_id: restore_id.clone(),
+        }
     };
-    let event = RestoreEvent {
-        event_type: "progress".to_string(),
-        progress: Some(initial_progress),
-        message: None,
-        snapshot_id: snapshot_id.clone(),
-        target_path: target_path.clone(),
+    let _ = app.emit("restore-started", &event("started", None, None));
+
+    // Progress-Events aus rustic_core an das Frontend weiterleiten
+    let progress_app = app.clone();
+    let progress_event = event("progress", None, None);
+    let on_progress = move |progress: RestoreProgress| {
+        let event = RestoreEvent { progress: Some(progress), ..progress_event.clone() };
+        let _ = progress_app.emit("restore-progress", &event);
     };
-    let _ = app.emit("restore-progress", &event);
 
     // Führe echten Restore aus
-    match crate::rustic::restore::restore_files(
-        &repository_path,
-        &password,
-        &snapshot_id,
-        files.clone(),
-        &target_path,
-        &options,
-        Some(app.clone()),
-    )
-    .await
-    {
-        Ok(_) => {
-            // Sende finalen Progress-Event
-            let final_progress = RestoreProgress {
-                base: crate::types::ProgressInfo {
-                    current: total,
-                    total,
-                    message: None,
-                    percentage: Some(100.0),
-                },
-                files_restored: total,
-                bytes_restored: total * 1024, // Placeholder
-                current_file: None,
-            };
-            let event = RestoreEvent {
-                event_type: "progress".to_string(),
-                progress: Some(final_progress),
-                message: None,
-                snapshot_id: snapshot_id.clone(),
-                target_path: target_path.clone(),
-            };
-            let _ = app.emit("restore-progress", &event);
-
-            // Sende Completed-Event
-            let event = RestoreEvent {
-                event_type: "completed".to_string(),
-                progress: None,
-                message: Some("Restore erfolgreich abgeschlossen".to_string()),
-                snapshot_id: snapshot_id,
-                target_path: target_path,
-            };
-            let _ = app.emit("restore-completed", &event);
-            Ok(())
+    let dry_run = options.dry_run;
+    let result = {
+        let (snapshot_id, target_path) = (snapshot_id.clone(), target_path.clone());
+        tokio::task::spawn_blocking(move || {
+            crate::rustic::restore::restore_files(
+                &repository_path,
+                &password,
+                &snapshot_id,
+                files,
+                &target_path,
+                &options,
+                cancel_token,
+                on_progress,
+            )
+        })
+        .await
+        .unwrap_or_else(|e| {
+            Err(RusticGuiError::Internal(format!("Restore-Task fehlgeschlagen: {e}")))
+        })
+    };
+    state.release_cancellation_token(&restore_id);
+
+    match result {
+        Ok(plan) if dry_run => Ok(plan),
+        Ok(plan) => {
+            let message = Some("Restore erfolgreich abgeschlossen".to_string());
+            let completed =
+                RestoreEvent { plan: Some(plan.clone()), ..event("completed", None, message) };
+            let _ = app.emit("restore-completed", &completed);
+            Ok(plan)
+        }
+        Err(RusticGuiError::RestoreCancelled) => {
+            let message = Some("Restore wurde abgebrochen".to_string());
+            let _ = app.emit("restore-cancelled", &event("cancelled", None, message));
+            Err(RusticGuiError::RestoreCancelled.to_string())
         }
         Err(e) => {
-            let event = RestoreEvent {
-                event_type: "error".to_string(),
-                progress: None,
-                message: Some(format!("Restore fehlgeschlagen: {}", e)),
-                snapshot_id: snapshot_id,
-                target_path: target_path,
-            };
-            let _ = app.emit("restore-failed", &event);
+            let message = Some(format!("Restore fehlgeschlagen: {}", e));
+            let _ = app.emit("restore-failed", &event("error", None, message));
             Err(format!("Restore fehlgeschlagen: {}", e))
         }
     }
 }
+
+/// Bricht einen laufenden Restore ab.
+///
+/// Der Restore beendet sich daraufhin mit dem Event `restore-cancelled`. Werden gerade
+/// Dateiinhalte geschrieben, greift der Abbruch nach dem laufenden Abschnitt.
+///
+/// # Arguments
+/// * `restore_id` - ID des Restores (siehe `restore_files_v1`)
+///
+/// # Errors
+/// Wenn kein Restore mit dieser ID läuft
+#[tauri::command]
+pub async fn cancel_restore(
+    restore_id: String,
+    state: tauri::State<'_, AppState>,
+) -> std::result::Result<(), String> {
+    match state.cancellation_tokens.lock().get(&restore_id) {
+        Some(token) => {
+            token.cancel();
+            tracing::info!(restore = %restore_id, "Restore-Abbruch ausgelöst");
+            Ok(())
+        }
+        None => {
+            tracing::warn!(restore = %restore_id, "Kein laufender Restore zum Abbrechen gefunden");
+            Err(format!("Kein laufender Restore mit ID '{}' gefunden", restore_id))
+        }
+    }
+}
+
+/// Restore eines Snapshot-Teilbaums in eine Archivdatei (tar, tar.gz, tar.zst, zip)
+///
+/// Sendet dieselben Events wie `restore_files_v1` (`targetPath` = Archivdatei) und kann
+/// ebenfalls über `cancel_restore` abgebrochen werden.
+///
+/// # Arguments
+/// * `path` - Pfad im Snapshot (None = kompletter Snapshot)
+/// * `output_path` - Zu erstellende Archivdatei
+/// * `format` - Archivformat
+/// * `restore_id` - Optionale ID für Events und Abbruch (wird sonst generiert)
+///
+/// # Returns
+/// Zusammenfassung des erstellten Archivs
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn restore_to_archive(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    repository_path: String,
+    password: String,
+    snapshot_id: String,
+    path: Option<String>,
+    output_path: String,
+    format: ArchiveFormat,
+    restore_id: Option<String>,
+) -> std::result::Result<ArchiveResultDto, String> {
+    tracing::info!("restore_to_archive aufgerufen");
+
+    let restore_id = restore_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let cancel_token = state.register_cancellation_token(&restore_id).map_err(|e| e.to_string())?;
+
+    let event = |event_type: &str, progress: Option<RestoreProgress>, message: Option<String>| {
+        RestoreEvent {
+            event_type: event_type.to_string(),
+            progress,
+            plan: None,
+            message,
+            snapshot_id: snapshot_id.clone(),
+            target_path: output_path.clone(),
+            restore_id: restore_id.clone(),
+        }
+    };
+    let _ = app.emit("restore-started", &event("started", None, None));
+
+    let progress_app = app.clone();
+    let progress_event = event("progress", None, None);
+    let on_progress = move |progress: RestoreProgress| {
+        let event = RestoreEvent { progress: Some(progress), ..progress_event.clone() };
+        let _ = progress_app.emit("restore-progress", &event);
+    };
+
+    let result = {
+        let (snapshot_id, output_path) = (snapshot_id.clone(), output_path.clone());
+        tokio::task::spawn_blocking(move || {
+            crate::rustic::archive::restore_to_archive(
+                &repository_path,
+                &password,
+                &snapshot_id,
+                path.as_deref(),
+                &output_path,
+                format,
+                cancel_token,
+                on_progress,
+            )
+        })
+        .await
+        .unwrap_or_else(|e| {
+            Err(RusticGuiError::Internal(format!("Archiv-Task fehlgeschlagen: {e}")))
+        })
+    };
+    state.release_cancellation_token(&restore_id);
+
+    match result {
+        Ok(archive) => {
+            let message = Some(format!("Archiv erstellt: {}", archive.output_path));
+            let _ = app.emit("restore-completed", &event("completed", None, message));
+            Ok(archive)
+        }
+        Err(RusticGuiError::RestoreCancelled) => {
+            let message = Some("Restore wurde abgebrochen".to_string());
+            let _ = app.emit("restore-cancelled", &event("cancelled", None, message));
+            Err(RusticGuiError::RestoreCancelled.to_string())
+        }
+        Err(e) => {
+            let message = Some(format!("Archiv-Restore fehlgeschlagen: {}", e));
+            let _ = app.emit("restore-failed", &event("error", None, message));
+            Err(format!("Archiv-Restore fehlgeschlagen: {}", e))
+        }
+    }
+}
+
+/// Lädt eine Vorschau (die ersten Bytes) einer Datei aus einem Snapshot
+///
+/// # Arguments
+/// * `path` - Pfad der Datei im Snapshot
+/// * `max_bytes` - Maximal zu lesende Bytes (Standard 64 KiB)
+#[tauri::command]
+pub async fn preview_snapshot_file(
+    repository_path: String,
+    password: String,
+    snapshot_id: String,
+    path: String,
+    max_bytes: Option<u64>,
+) -> std::result::Result<FilePreviewDto, String> {
+    tokio::task::spawn_blocking(move || {
+        crate::rustic::preview::read_file_preview(
+            &repository_path,
+            &password,
+            &snapshot_id,
+            &path,
+            max_bytes,
+        )
+    })
+    .await
+    .map_err(|e| format!("Vorschau-Task fehlgeschlagen: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// Speichert eine einzelne Datei aus einem Snapshot, ohne Restore-Plan
+///
+/// # Arguments
+/// * `path` - Pfad der Datei im Snapshot
+/// * `output_path` - Zieldatei; None = temporäre Datei (z.B. zum Öffnen)
+#[tauri::command]
+pub async fn extract_snapshot_file(
+    repository_path: String,
+    password: String,
+    snapshot_id: String,
+    path: String,
+    output_path: Option<String>,
+) -> std::result::Result<ExtractedFileDto, String> {
+    tokio::task::spawn_blocking(move || {
+        crate::rustic::preview::extract_file(
+            &repository_path,
+            &password,
+            &snapshot_id,
+            &path,
+            output_path.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Datei-Task fehlgeschlagen: {e}"))?
+    .map_err(|e| e.to_string())
+}
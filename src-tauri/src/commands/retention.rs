@@ -4,7 +4,10 @@
 /// Referenz: rustic CLI forget.rs (Lines 107-177)
 use crate::state::AppState;
 use crate::types::RetentionPolicy;
-use rustic_core::{KeepOptions, repofile::SnapshotId};
+use rustic_core::{
+    KeepOptions, SnapshotGroupCriterion,
+    repofile::{SnapshotId, StringList},
+};
 
 /// Wendet eine Retention-Policy auf alle Snapshots an und gibt zurück welche gelöscht würden.
 ///
@@ -20,6 +23,7 @@ use rustic_core::{KeepOptions, repofile::SnapshotId};
 /// # Returns
 /// * `snapshots_to_keep` - IDs der Snapshots die behalten werden
 /// * `snapshots_to_delete` - IDs der Snapshots die gelöscht würden
+/// * `groups` - Aufschlüsselung pro Snapshot-Gruppe (`RetentionPolicy::group_by`)
 #[tauri::command]
 pub async fn preview_retention_policy(
     policy: RetentionPolicy,
@@ -33,41 +37,48 @@ pub async fn preview_retention_policy(
         .get_repository(&repo_id)
         .map_err(|e| format!("Repository öffnen fehlgeschlagen: {}", e))?;
 
-    // Alle Snapshots laden
-    let all_snapshots =
-        repo.get_all_snapshots().map_err(|e| format!("Snapshots laden fehlgeschlagen: {}", e))?;
-
-    tracing::debug!("Geladene Snapshots: {}", all_snapshots.len());
-
     // Konvertiere RetentionPolicy -> rustic_core::KeepOptions
-    let keep_opts = rustic_retention_policy_to_keep_options(&policy);
+    let keep_opts = rustic_retention_policy_to_keep_options(&policy)?;
 
-    // Wende Retention-Policy an (mit aktuellem Zeitpunkt)
-    let now = chrono::Local::now();
-    let forget_result = keep_opts
-        .apply(all_snapshots.clone(), now)
+    // Wende Retention-Policy pro Gruppe an (mit aktuellem Zeitpunkt)
+    let forget_groups = repo
+        .get_forget_snapshots(&keep_opts, retention_group_criterion(&policy), |_| true)
         .map_err(|e| format!("Retention-Policy fehlgeschlagen: {}", e))?;
 
     // Ermittle zu behaltende und zu löschende Snapshots
     let mut snapshots_to_keep: Vec<String> = Vec::new();
     let mut snapshots_to_delete: Vec<String> = Vec::new();
+    let mut groups: Vec<RetentionGroupPreview> = Vec::new();
 
-    for snap_result in forget_result {
-        let snap_id = snap_result.snapshot.id.to_string();
-        if snap_result.keep {
-            snapshots_to_keep.push(snap_id);
-        } else {
-            snapshots_to_delete.push(snap_id);
+    for forget_group in forget_groups.0 {
+        let mut group = RetentionGroupPreview {
+            hostname: forget_group.group.hostname.clone(),
+            paths: forget_group.group.paths.as_ref().map(ToString::to_string),
+            tags: forget_group.group.tags.as_ref().map(ToString::to_string),
+            snapshots_to_keep: Vec::new(),
+            snapshots_to_delete: Vec::new(),
+        };
+        for snap_result in forget_group.snapshots {
+            let snap_id = snap_result.snapshot.id.to_string();
+            if snap_result.keep {
+                group.snapshots_to_keep.push(snap_id);
+            } else {
+                group.snapshots_to_delete.push(snap_id);
+            }
         }
+        snapshots_to_keep.extend(group.snapshots_to_keep.iter().cloned());
+        snapshots_to_delete.extend(group.snapshots_to_delete.iter().cloned());
+        groups.push(group);
     }
 
     tracing::info!(
-        "Retention-Policy Preview: {} behalten, {} löschen",
+        "Retention-Policy Preview: {} behalten, {} löschen ({} Gruppen)",
         snapshots_to_keep.len(),
-        snapshots_to_delete.len()
+        snapshots_to_delete.len(),
+        groups.len()
     );
 
-    Ok(RetentionPolicyPreview { snapshots_to_keep, snapshots_to_delete })
+    Ok(RetentionPolicyPreview { snapshots_to_keep, snapshots_to_delete, groups })
 }
 
 /// Wendet eine Retention-Policy an und löscht Snapshots gemäß Policy.
@@ -96,29 +107,17 @@ pub async fn apply_retention_policy(
         .get_repository(&repo_id)
         .map_err(|e| format!("Repository öffnen fehlgeschlagen: {}", e))?;
 
-    // Alle Snapshots laden
-    let all_snapshots =
-        repo.get_all_snapshots().map_err(|e| format!("Snapshots laden fehlgeschlagen: {}", e))?;
-
     // Konvertiere RetentionPolicy -> rustic_core::KeepOptions
-    let keep_opts = rustic_retention_policy_to_keep_options(&policy);
+    let keep_opts = rustic_retention_policy_to_keep_options(&policy)?;
 
-    // Wende Retention-Policy an
-    let now = chrono::Local::now();
-    let forget_result = keep_opts
-        .apply(all_snapshots, now)
+    // Wende Retention-Policy pro Gruppe an
+    let forget_groups = repo
+        .get_forget_snapshots(&keep_opts, retention_group_criterion(&policy), |_| true)
         .map_err(|e| format!("Retention-Policy fehlgeschlagen: {}", e))?;
 
     // Sammle zu löschende Snapshot-IDs
-    let mut snapshots_to_delete: Vec<SnapshotId> = Vec::new();
-    let mut deleted_ids: Vec<String> = Vec::new();
-
-    for snap_result in forget_result {
-        if !snap_result.keep {
-            deleted_ids.push(snap_result.snapshot.id.to_string());
-            snapshots_to_delete.push(snap_result.snapshot.id);
-        }
-    }
+    let snapshots_to_delete: Vec<SnapshotId> = forget_groups.into_forget_ids();
+    let deleted_ids: Vec<String> = snapshots_to_delete.iter().map(ToString::to_string).collect();
 
     if snapshots_to_delete.is_empty() {
         tracing::info!("Keine Snapshots zum Löschen gemäß Retention-Policy");
@@ -139,17 +138,68 @@ pub async fn apply_retention_policy(
 /// Hilfsfunktion: Konvertiert unsere RetentionPolicy in rustic_core::KeepOptions
 ///
 /// Referenz: rustic CLI forget.rs Lines 62-88
-fn rustic_retention_policy_to_keep_options(policy: &RetentionPolicy) -> KeepOptions {
+///
+/// # Errors
+/// Ungültige `keep_within`-Dauer oder Tag-Liste, oder eine Policy ohne keep-Regel
+pub(crate) fn rustic_retention_policy_to_keep_options(
+    policy: &RetentionPolicy,
+) -> Result<KeepOptions, String> {
     let mut opts = KeepOptions::default();
 
     // Mappe unsere Policy-Felder auf rustic_core KeepOptions (i32)
     opts.keep_last = policy.keep_last.map(|n| n as i32);
+    opts.keep_hourly = policy.keep_hourly.map(|n| n as i32);
     opts.keep_daily = policy.keep_daily.map(|n| n as i32);
     opts.keep_weekly = policy.keep_weekly.map(|n| n as i32);
     opts.keep_monthly = policy.keep_monthly.map(|n| n as i32);
     opts.keep_yearly = policy.keep_yearly.map(|n| n as i32);
 
-    opts
+    opts.keep_within = policy
+        .keep_within
+        .as_deref()
+        .filter(|within| !within.trim().is_empty())
+        .map(|within| {
+            within
+                .trim()
+                .parse::<humantime::Duration>()
+                .map_err(|e| format!("Ungültige keep_within-Dauer '{}': {}", within, e))
+        })
+        .transpose()?;
+
+    opts.keep_tags = policy
+        .keep_tags
+        .iter()
+        .filter(|tags| !tags.trim().is_empty())
+        .map(|tags| {
+            tags.parse::<StringList>()
+                .map_err(|e| format!("Ungültige Tag-Liste '{}': {:?}", tags, e))
+        })
+        .collect::<Result<_, _>>()?;
+
+    opts.keep_ids = policy.keep_ids.iter().filter(|id| !id.trim().is_empty()).cloned().collect();
+
+    let has_rule = opts.keep_last.is_some()
+        || opts.keep_hourly.is_some()
+        || opts.keep_daily.is_some()
+        || opts.keep_weekly.is_some()
+        || opts.keep_monthly.is_some()
+        || opts.keep_yearly.is_some()
+        || opts.keep_within.is_some()
+        || !opts.keep_tags.is_empty()
+        || !opts.keep_ids.is_empty();
+    if !has_rule {
+        return Err("Retention-Policy muss mindestens eine keep-Regel enthalten".to_string());
+    }
+
+    Ok(opts)
+}
+
+/// Hilfsfunktion: Gruppierungskriterien der Policy für rustic_core
+pub(crate) fn retention_group_criterion(policy: &RetentionPolicy) -> SnapshotGroupCriterion {
+    SnapshotGroupCriterion::new()
+        .hostname(policy.group_by.hostname)
+        .paths(policy.group_by.paths)
+        .tags(policy.group_by.tags)
 }
 
 /// DTO für Retention-Policy Preview
@@ -159,4 +209,64 @@ pub struct RetentionPolicyPreview {
     pub snapshots_to_keep: Vec<String>,
     /// Snapshot-IDs die gelöscht würden
     pub snapshots_to_delete: Vec<String>,
+    /// Aufschlüsselung pro Snapshot-Gruppe
+    pub groups: Vec<RetentionGroupPreview>,
+}
+
+/// Preview einer einzelnen Snapshot-Gruppe
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RetentionGroupPreview {
+    /// Hostname der Gruppe (falls nach Host gruppiert)
+    pub hostname: Option<String>,
+    /// Pfade der Gruppe, kommagetrennt (falls nach Pfaden gruppiert)
+    pub paths: Option<String>,
+    /// Tags der Gruppe, kommagetrennt (falls nach Tags gruppiert)
+    pub tags: Option<String>,
+    /// Snapshot-IDs die behalten werden
+    pub snapshots_to_keep: Vec<String>,
+    /// Snapshot-IDs die gelöscht würden
+    pub snapshots_to_delete: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keep_options_mapping() {
+        let policy = RetentionPolicy {
+            keep_hourly: Some(24),
+            keep_within: Some("7d".to_string()),
+            keep_tags: vec!["important".to_string(), "a,b".to_string()],
+            keep_ids: vec!["abc123".to_string()],
+            ..Default::default()
+        };
+
+        let opts = rustic_retention_policy_to_keep_options(&policy).unwrap();
+        assert_eq!(opts.keep_hourly, Some(24));
+        assert_eq!(opts.keep_last, Some(10));
+        assert_eq!(
+            opts.keep_within.map(std::time::Duration::from),
+            Some(std::time::Duration::from_secs(7 * 24 * 3600))
+        );
+        assert_eq!(opts.keep_tags.len(), 2);
+        assert_eq!(opts.keep_ids, vec!["abc123".to_string()]);
+    }
+
+    #[test]
+    fn test_keep_options_rejects_invalid_policy() {
+        let invalid_within =
+            RetentionPolicy { keep_within: Some("sieben Tage".to_string()), ..Default::default() };
+        assert!(rustic_retention_policy_to_keep_options(&invalid_within).is_err());
+
+        let empty = RetentionPolicy {
+            keep_last: None,
+            keep_daily: None,
+            keep_weekly: None,
+            keep_monthly: None,
+            keep_yearly: None,
+            ..Default::default()
+        };
+        assert!(rustic_retention_policy_to_keep_options(&empty).is_err());
+    }
 }
@@ -31,6 +31,12 @@ pub async fn save_settings(
     // Persistiere Config
     state.save_config().map_err(|e| format!("Fehler beim Speichern der Config: {}", e))?;
 
+    // Neue Limits sofort für die Job-Warteschlange übernehmen
+    state.job_queue.set_limits(
+        settings.max_concurrent_backups,
+        settings.max_concurrent_backups_per_repository,
+    );
+
     tracing::info!("Einstellungen erfolgreich gespeichert");
 
     Ok(())
@@ -47,6 +53,7 @@ pub async fn reset_settings(state: tauri::State<'_, AppState>) -> Result<AppSett
         log_level: "info".to_string(),
         check_updates: true,
         max_concurrent_backups: 1,
+        max_concurrent_backups_per_repository: 1,
         notifications_enabled: true,
         language: "de".to_string(),
         password_storage: "system_keychain".to_string(),
@@ -62,6 +69,11 @@ pub async fn reset_settings(state: tauri::State<'_, AppState>) -> Result<AppSett
     // Persistiere Config
     state.save_config().map_err(|e| format!("Fehler beim Speichern der Config: {}", e))?;
 
+    state.job_queue.set_limits(
+        default_settings.max_concurrent_backups,
+        default_settings.max_concurrent_backups_per_repository,
+    );
+
     Ok(default_settings)
 }
 
@@ -3,7 +3,10 @@
 // Referenz: TODO.md Zeile 182-187
 
 use crate::state::AppState;
-use crate::types::{DiffResultDto, DiffStats, SnapshotDto};
+use crate::types::{
+    DiffEntryKind, DiffResultDto, DiffStats, FileHistoryDto, FindQuery, FindResultDto,
+    SnapshotDiffDto, SnapshotDiffOptions, SnapshotDto, SnapshotPageDto, SnapshotQuery,
+};
 
 /// Listet alle Snapshots eines Repositories
 #[tauri::command]
@@ -28,6 +31,85 @@ pub async fn list_snapshots_filtered_command(
         .map_err(|e| e.to_string())
 }
 
+/// Filtert, sortiert und paginiert Snapshots serverseitig
+///
+/// # Arguments
+/// * `filter` - Optionaler Snapshot-Filter
+/// * `query` - Sortierung und Paging
+///
+/// # Returns
+/// Seite der Snapshots mit Gesamtanzahl der Treffer
+#[tauri::command]
+pub async fn query_snapshots_command(
+    repository_path: String,
+    password: String,
+    filter: Option<crate::rustic::snapshot::SnapshotFilter>,
+    query: SnapshotQuery,
+) -> std::result::Result<SnapshotPageDto, String> {
+    tokio::task::spawn_blocking(move || {
+        crate::rustic::snapshot::query_snapshots(
+            &repository_path,
+            &password,
+            filter.as_ref(),
+            &query,
+        )
+    })
+    .await
+    .map_err(|e| format!("Snapshot-Abfrage fehlgeschlagen: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// Sucht Dateien über alle (bzw. gefilterten) Snapshots
+///
+/// # Arguments
+/// * `query` - Muster (Glob/Regex) sowie Größen- und Zeitfilter
+/// * `filter` - Optionaler Snapshot-Filter
+///
+/// # Returns
+/// Jede Snapshot-Version, in der eine passende Datei vorkommt
+#[tauri::command]
+pub async fn find_in_snapshots(
+    repository_path: String,
+    password: String,
+    query: FindQuery,
+    filter: Option<crate::rustic::snapshot::SnapshotFilter>,
+) -> std::result::Result<FindResultDto, String> {
+    tokio::task::spawn_blocking(move || {
+        crate::rustic::snapshot::find_in_snapshots(
+            &repository_path,
+            &password,
+            &query,
+            filter.as_ref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Such-Task fehlgeschlagen: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// Lädt die Versionsgeschichte einer Datei über alle (bzw. gefilterten) Snapshots
+///
+/// # Arguments
+/// * `path` - Pfad der Datei im Snapshot
+/// * `filter` - Optionaler Snapshot-Filter
+///
+/// # Returns
+/// Unterschiedliche Versionen mit erstem/letztem Snapshot, neueste zuerst
+#[tauri::command]
+pub async fn get_file_history(
+    repository_path: String,
+    password: String,
+    path: String,
+    filter: Option<crate::rustic::snapshot::SnapshotFilter>,
+) -> std::result::Result<FileHistoryDto, String> {
+    tokio::task::spawn_blocking(move || {
+        crate::rustic::snapshot::file_history(&repository_path, &password, &path, filter.as_ref())
+    })
+    .await
+    .map_err(|e| format!("Versions-Task fehlgeschlagen: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
 /// Holt Details zu einem Snapshot
 #[tauri::command]
 pub async fn get_snapshot_command(
@@ -54,8 +136,8 @@ pub async fn delete_snapshot_command(
 
 /// Vergleicht zwei Snapshots mittels Tree-basiertem Diff
 ///
-/// Basierend auf rustic CLI: https://github.com/rustic-rs/rustic/blob/main/src/commands/diff.rs
-/// Verwendet Repository::ls() für vollständigen File-Tree-Vergleich
+/// Kompakte Variante von `diff_snapshots`: liefert alle hinzugefügten, entfernten und
+/// inhaltlich geänderten Pfade (reine Metadaten-Änderungen werden ignoriert).
 #[tauri::command]
 pub async fn compare_snapshots(
     snapshot_id_a: String,
@@ -63,13 +145,69 @@ pub async fn compare_snapshots(
     password: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<DiffResultDto, String> {
-    use rustic_backend::BackendOptions;
-    use rustic_core::{LsOptions, NoProgressBars, Repository, RepositoryOptions};
-    use std::collections::HashMap;
-    use tracing::{error, info};
+    tracing::info!("compare_snapshots: {} vs {}", snapshot_id_a, snapshot_id_b);
 
-    info!("compare_snapshots: {} vs {}", snapshot_id_a, snapshot_id_b);
+    let options = SnapshotDiffOptions {
+        limit: Some(u64::MAX),
+        ignore_metadata: true,
+        rollup_depth: Some(0),
+        ..Default::default()
+    };
+    let diff = run_snapshot_diff(&state, snapshot_id_a, snapshot_id_b, password, options).await?;
+
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let mut modified = Vec::new();
+    for entry in diff.entries {
+        match entry.kind {
+            DiffEntryKind::Added => added.push(entry.path),
+            DiffEntryKind::Removed => removed.push(entry.path),
+            DiffEntryKind::Changed => modified.push(entry.path),
+        }
+    }
+
+    Ok(DiffResultDto {
+        stats: DiffStats {
+            added_count: added.len() as u64,
+            removed_count: removed.len() as u64,
+            modified_count: modified.len() as u64,
+            total_size_change: diff.stats.size_delta,
+        },
+        added,
+        removed,
+        modified,
+    })
+}
+
+/// Detaillierter Snapshot-Diff mit Änderungsklassen, Verzeichnis-Rollups und Paging
+///
+/// Vergleicht die Snapshots des aktuell ausgewählten Repositories per Merge-Join
+/// (siehe `rustic::snapshot::diff_snapshots`).
+///
+/// # Arguments
+/// * `snapshot_id_a` - Älterer Snapshot
+/// * `snapshot_id_b` - Neuerer Snapshot
+/// * `options` - Präfix-Filter, Paging, Rollup-Tiefe (None = erste Seite)
+#[tauri::command]
+pub async fn diff_snapshots(
+    snapshot_id_a: String,
+    snapshot_id_b: String,
+    password: String,
+    options: Option<SnapshotDiffOptions>,
+    state: tauri::State<'_, AppState>,
+) -> Result<SnapshotDiffDto, String> {
+    run_snapshot_diff(&state, snapshot_id_a, snapshot_id_b, password, options.unwrap_or_default())
+        .await
+}
 
+/// Führt den Diff für das aktuell ausgewählte Repository in einem Blocking-Thread aus
+async fn run_snapshot_diff(
+    state: &AppState,
+    snapshot_id_a: String,
+    snapshot_id_b: String,
+    password: String,
+    options: SnapshotDiffOptions,
+) -> Result<SnapshotDiffDto, String> {
     // Repository-Pfad holen (parking_lot::Mutex hat kein map_err)
     let repo_id = state.get_current_repository_id().ok_or("Kein Repository ausgewählt")?;
     let repository_path = {
@@ -83,133 +221,18 @@ pub async fn compare_snapshots(
             .clone()
     };
 
-    // Repository NEU öffnen und indexieren (wie restore.rs)
-    // Grund: Arc<Repository> kann nicht mit to_indexed() konsumiert werden
-    let mut repo_opts = RepositoryOptions::default();
-    repo_opts.password = Some(password.to_string());
-
-    let mut backend_opts = BackendOptions::default();
-    backend_opts.repository = Some(repository_path.clone());
-
-    let backends = backend_opts.to_backends().map_err(|e| {
-        error!(?e, "Backend erstellen fehlgeschlagen");
-        format!("Backend erstellen fehlgeschlagen: {}", e)
-    })?;
-
-    let repo = Repository::<NoProgressBars, _>::new(&repo_opts, &backends)
-        .map_err(|e| {
-            error!(?e, "Repository initialisieren fehlgeschlagen");
-            format!("Repository initialisieren fehlgeschlagen: {}", e)
-        })?
-        .open()
-        .map_err(|e| {
-            error!(?e, "Repository öffnen fehlgeschlagen");
-            format!("Repository öffnen fehlgeschlagen: {}", e)
-        })?
-        .to_indexed()
-        .map_err(|e| {
-            error!(?e, "Repository indexieren fehlgeschlagen");
-            format!("Repository indexieren fehlgeschlagen: {}", e)
-        })?;
-
-    // Beide Snapshots laden
-    let snaps =
-        repo.get_snapshots(&[snapshot_id_a.as_str(), snapshot_id_b.as_str()]).map_err(|e| {
-            error!(?e, "Snapshots laden fehlgeschlagen");
-            format!("Snapshots laden fehlgeschlagen: {}", e)
-        })?;
-
-    if snaps.len() != 2 {
-        return Err("Nicht alle Snapshots gefunden".into());
-    }
-
-    let snapshot_a = &snaps[0];
-    let snapshot_b = &snaps[1];
-
-    // Root-Nodes in Snapshots laden (siehe rustic CLI diff.rs#L110-112)
-    let node_a = repo
-        .node_from_snapshot_and_path(snapshot_a, "")
-        .map_err(|e| format!("Node A laden fehlgeschlagen: {}", e))?;
-
-    let node_b = repo
-        .node_from_snapshot_and_path(snapshot_b, "")
-        .map_err(|e| format!("Node B laden fehlgeschlagen: {}", e))?;
-
-    // File-Trees mit ls() laden (siehe rustic CLI diff.rs#L113-115)
-    let ls_opts = LsOptions::default();
-    let tree_a =
-        repo.ls(&node_a, &ls_opts).map_err(|e| format!("Tree A listen fehlgeschlagen: {}", e))?;
-
-    let tree_b =
-        repo.ls(&node_b, &ls_opts).map_err(|e| format!("Tree B listen fehlgeschlagen: {}", e))?;
-
-    // Dateien aus beiden Trees in HashMaps sammeln
-    let mut files_a: HashMap<String, rustic_core::repofile::Node> = HashMap::new();
-    let mut files_b: HashMap<String, rustic_core::repofile::Node> = HashMap::new();
-
-    for item in tree_a {
-        let (path, node) = item.map_err(|e| format!("Tree A Item fehlgeschlagen: {}", e))?;
-        files_a.insert(path.to_string_lossy().to_string(), node);
-    }
-
-    for item in tree_b {
-        let (path, node) = item.map_err(|e| format!("Tree B Item fehlgeschlagen: {}", e))?;
-        files_b.insert(path.to_string_lossy().to_string(), node);
-    }
-
-    // Unterschiede berechnen (siehe rustic CLI diff.rs#L477-505)
-    let mut added = Vec::new();
-    let mut removed = Vec::new();
-    let mut modified = Vec::new();
-    let mut total_size_change: i64 = 0;
-
-    // In B, aber nicht in A → hinzugefügt
-    for (path, node_b) in &files_b {
-        if !files_a.contains_key(path) {
-            added.push(path.clone());
-            total_size_change += node_b.meta.size as i64;
-        }
-    }
-
-    // In A, aber nicht in B → entfernt
-    for (path, node_a) in &files_a {
-        if !files_b.contains_key(path) {
-            removed.push(path.clone());
-            total_size_change -= node_a.meta.size as i64;
-        }
-    }
-
-    // In beiden, aber unterschiedlich → geändert (siehe rustic CLI diff.rs#L294-321)
-    for (path, node_a) in &files_a {
-        if let Some(node_b) = files_b.get(path) {
-            let content_changed = node_a.content != node_b.content;
-            let size_changed = node_a.meta.size != node_b.meta.size;
-
-            if content_changed || size_changed {
-                modified.push(path.clone());
-                total_size_change += (node_b.meta.size as i64) - (node_a.meta.size as i64);
-            }
-        }
-    }
-
-    let added_count = added.len() as u64;
-    let removed_count = removed.len() as u64;
-    let modified_count = modified.len() as u64;
-
-    tracing::info!(
-        "Diff: +{} -{} ~{} (size_change: {})",
-        added_count,
-        removed_count,
-        modified_count,
-        total_size_change
-    );
-
-    Ok(DiffResultDto {
-        added,
-        removed,
-        modified,
-        stats: DiffStats { added_count, removed_count, modified_count, total_size_change },
+    tokio::task::spawn_blocking(move || {
+        crate::rustic::snapshot::diff_snapshots(
+            &repository_path,
+            &password,
+            &snapshot_id_a,
+            &snapshot_id_b,
+            &options,
+        )
     })
+    .await
+    .map_err(|e| format!("Diff-Task fehlgeschlagen: {e}"))?
+    .map_err(|e| e.to_string())
 }
 
 /// Löscht einzelnen Snapshot
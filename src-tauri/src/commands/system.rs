@@ -1,9 +1,11 @@
 // TODO.md: Phase 1 - System Commands ✅ TEILWEISE IMPLEMENTIERT
 // Status: prepare_shutdown verschoben von lib.rs
+// Repository-Locks: check_repository_health, list_repository_locks, force_unlock_repository
 // Referenz: TODO.md Integration-Zusammenfassung Zeile 338
 
 use crate::state::AppState;
 use crate::storage::PortableStoreStatus;
+use crate::types::{RepositoryHealthDto, RepositoryLocksDto, RepositoryStatus, UnlockResultDto};
 
 /// Bereitet sauberen Shutdown vor (prüft laufende Operationen)
 #[tauri::command]
@@ -35,25 +37,123 @@ pub fn get_portable_status(state: tauri::State<'_, AppState>) -> PortableStoreSt
 }
 
 /// Health-Check für ein Repository
+///
+/// Leitet den Schlüssel aus dem Passwort ab und liest die Lock-Dateien. Liegen Locks vor (von restic oder einem
+/// abgebrochenen Prozess), ist der Status `Locked`. Können die Locks des Backends nicht gelesen
+/// werden (nicht-lokale Repositories), ist der Status `Warning` mit einem Hinweis.
+///
+/// # Arguments
+/// * `repository_id` - ID des Repositories
+/// * `state` - AppState mit Repository-Konfiguration
+///
+/// # Returns
+/// `Result<RepositoryHealthDto, String>` - Status, Locks und ggf. Fehlermeldung
 #[tauri::command]
 pub async fn check_repository_health(
-    _repository_id: String,
-    _state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    // TODO: Implementieren mit rustic_core
-    // TODO.md: lib.rs:424 (auskommentiert, noch nicht registriert)
-    // Wird benötigt für erweiterte Repository-Diagnostik
-    Err("check_repository_health: Noch nicht implementiert".into())
+    repository_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<RepositoryHealthDto, String> {
+    let (repo_config, password) = state
+        .repository_credentials(&repository_id)
+        .map_err(|e| format!("Repository nicht gefunden: {}", e))?;
+
+    tokio::task::spawn_blocking(move || {
+        match crate::rustic::locks::list_locks(&repo_config.path, &password) {
+            Ok(locks) if !locks.supported => RepositoryHealthDto {
+                status: RepositoryStatus::Warning,
+                locks,
+                message: Some(
+                    "Locks können nur bei lokalen Repositories geprüft werden".to_string(),
+                ),
+            },
+            Ok(locks) => {
+                let status = if locks.locks.is_empty() {
+                    RepositoryStatus::Healthy
+                } else {
+                    RepositoryStatus::Locked
+                };
+                RepositoryHealthDto { status, locks, message: None }
+            }
+            Err(e) => {
+                tracing::warn!("Health-Check für {} fehlgeschlagen: {}", repository_id, e);
+                RepositoryHealthDto {
+                    status: RepositoryStatus::Unavailable,
+                    locks: Default::default(),
+                    message: Some(e.to_string()),
+                }
+            }
+        }
+    })
+    .await
+    .map_err(|e| format!("Task-Fehler: {}", e))
+}
+
+/// Listet die Lock-Dateien eines Repositories mit Host, PID und Alter auf
+///
+/// # Arguments
+/// * `repository_id` - ID des Repositories
+/// * `state` - AppState mit Repository-Konfiguration
+///
+/// # Returns
+/// `Result<RepositoryLocksDto, String>` - Locks (nur bei lokalen Repositories lesbar)
+#[tauri::command]
+pub async fn list_repository_locks(
+    repository_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<RepositoryLocksDto, String> {
+    let (repo_config, password) = state
+        .repository_credentials(&repository_id)
+        .map_err(|e| format!("Repository nicht gefunden: {}", e))?;
+
+    tokio::task::spawn_blocking(move || {
+        crate::rustic::locks::list_locks(&repo_config.path, &password).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task-Fehler: {}", e))?
 }
 
-/// Erzwingt das Entsperren eines Repositories
+/// Entfernt veraltete Locks eines Repositories
+///
+/// Entfernt werden nur Locks, die älter als `min_age_secs` sind (Standard: 30 Minuten) und
+/// nicht von einem noch laufenden Prozess dieses Rechners gehalten werden. Nicht lesbare
+/// Lock-Dateien werden nur entfernt, wenn sie in `lock_ids` angegeben sind.
+///
+/// # Arguments
+/// * `repository_id` - ID des Repositories
+/// * `min_age_secs` - Optional: Mindestalter der zu entfernenden Locks in Sekunden
+/// * `lock_ids` - Optional: Nur diese Locks entfernen (sonst alle veralteten)
+/// * `state` - AppState mit Repository-Konfiguration
+///
+/// # Returns
+/// `Result<UnlockResultDto, String>` - Entfernte und übersprungene Locks
 #[tauri::command]
 pub async fn force_unlock_repository(
-    _repository_id: String,
-    _state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    // TODO: Implementieren mit rustic_core
-    // TODO.md: lib.rs:425 (auskommentiert, noch nicht registriert)
-    // Wird benötigt wenn Repository-Locks manuell entfernt werden müssen
-    Err("force_unlock_repository: Noch nicht implementiert".into())
+    repository_id: String,
+    min_age_secs: Option<u64>,
+    lock_ids: Option<Vec<String>>,
+    state: tauri::State<'_, AppState>,
+) -> Result<UnlockResultDto, String> {
+    let (repo_config, password) = state
+        .repository_credentials(&repository_id)
+        .map_err(|e| format!("Repository nicht gefunden: {}", e))?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        crate::rustic::locks::remove_stale_locks(
+            &repo_config.path,
+            &password,
+            min_age_secs.unwrap_or(crate::rustic::locks::DEFAULT_STALE_LOCK_AGE_SECS),
+            &lock_ids.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task-Fehler: {}", e))?
+    .map_err(|e| e.to_string())?;
+
+    tracing::info!(
+        "Repository {}: {} Locks entfernt, {} übersprungen",
+        repository_id,
+        result.removed.len(),
+        result.skipped.len()
+    );
+    Ok(result)
 }
@@ -58,6 +58,16 @@ pub struct BackupJobConfig {
     pub retention: RetentionPolicy,
     /// Ob Job aktiviert ist
     pub enabled: bool,
+    /// Verhalten bei verpassten geplanten Läufen
+    #[serde(default)]
+    pub catch_up: CatchUpPolicy,
+    /// Zeitpunkt (RFC 3339), seit dem der aktuelle Zeitplan gilt. Referenz für das
+    /// Nachholen, solange der Job noch nie lief.
+    #[serde(default)]
+    pub schedule_since: Option<String>,
+    /// Wartungsschritte nach erfolgreichem Backup (Retention, Prune)
+    #[serde(default)]
+    pub post_backup: PostBackupOptions,
     /// Gespeichertes Passwort für geplante Jobs (wird mit Config verschlüsselt)
     pub password: Option<String>,
 }
@@ -73,6 +83,9 @@ pub struct AppSettings {
     pub check_updates: bool,
     /// Maximale Anzahl gleichzeitiger Backups
     pub max_concurrent_backups: usize,
+    /// Maximale Anzahl gleichzeitiger Backups pro Repository
+    #[serde(default = "default_max_concurrent_per_repository")]
+    pub max_concurrent_backups_per_repository: usize,
     /// Desktop-Benachrichtigungen aktiviert (M4.4)
     #[serde(default = "default_true")]
     pub notifications_enabled: bool,
@@ -91,6 +104,10 @@ fn default_true() -> bool {
     true
 }
 
+fn default_max_concurrent_per_repository() -> usize {
+    1
+}
+
 fn default_language() -> String {
     "de".to_string()
 }
@@ -124,6 +141,7 @@ impl Default for AppConfig {
                 log_level: "info".to_string(),
                 check_updates: true,
                 max_concurrent_backups: 1,
+                max_concurrent_backups_per_repository: 1,
                 notifications_enabled: true,
                 language: "de".to_string(),
                 password_storage: "system_keychain".to_string(),
@@ -282,6 +300,59 @@ impl AppConfig {
         }
     }
 
+    /// Aktualisiert einen bestehenden Job-Execution-Eintrag
+    ///
+    /// Der Eintrag wird über Job-ID und Startzeitpunkt identifiziert. Existiert er nicht
+    /// (z.B. weil er aus der History verdrängt wurde), wird er neu hinzugefügt.
+    pub fn update_job_execution(&mut self, execution: JobExecution) {
+        match self
+            .job_executions
+            .iter_mut()
+            .rev()
+            .find(|e| e.job_id == execution.job_id && e.started_at == execution.started_at)
+        {
+            Some(existing) => *existing = execution,
+            None => self.add_job_execution(execution),
+        }
+    }
+
+    /// Markiert Job-Executions, die noch als laufend gespeichert sind, als fehlgeschlagen.
+    ///
+    /// Beim App-Start kann kein Job mehr laufen; solche Einträge stammen aus einem Absturz
+    /// oder einem harten Beenden der App und würden sonst `last_job_execution` und das
+    /// Nachholen verpasster Läufe verfälschen.
+    ///
+    /// # Returns
+    /// Anzahl der markierten Einträge
+    pub fn fail_interrupted_executions(&mut self) -> usize {
+        let mut count = 0;
+        for execution in
+            self.job_executions.iter_mut().filter(|e| e.status == JobExecutionStatus::Running)
+        {
+            execution.status = JobExecutionStatus::Failed;
+            execution.error_message =
+                Some("Unterbrochen: Die App wurde während des Laufs beendet".to_string());
+            count += 1;
+        }
+        count
+    }
+
+    /// Gibt die zuletzt gestartete Job-Execution für einen Job zurück
+    pub fn last_job_execution(&self, job_id: &str) -> Option<&JobExecution> {
+        self.job_executions.iter().rev().find(|e| e.job_id == job_id)
+    }
+
+    /// Gibt den Startzeitpunkt des letzten erfolgreichen Wartungsschritts eines Jobs zurück
+    pub fn last_completed_stage(&self, job_id: &str, stage: JobStage) -> Option<&str> {
+        self.job_executions
+            .iter()
+            .rev()
+            .filter(|e| e.job_id == job_id)
+            .flat_map(|e| e.stages.iter().rev())
+            .find(|s| s.stage == stage && s.status == JobExecutionStatus::Completed)
+            .map(|s| s.started_at.as_str())
+    }
+
     /// Gibt die letzten N Job-Executions für einen Job zurück
     pub fn get_job_executions(&self, job_id: &str, limit: usize) -> Vec<&JobExecution> {
         self.job_executions.iter().filter(|e| e.job_id == job_id).rev().take(limit).collect()
@@ -358,8 +429,12 @@ mod tests {
                 keep_weekly: Some(4),
                 keep_monthly: Some(12),
                 keep_yearly: Some(2),
+                ..Default::default()
             },
             enabled: true,
+            catch_up: CatchUpPolicy::default(),
+            schedule_since: None,
+            post_backup: PostBackupOptions::default(),
             password: None,
         });
 
@@ -442,6 +517,9 @@ mod tests {
             schedule: Some("0 2 * * *".to_string()),
             retention: RetentionPolicy::default(),
             enabled: true,
+            catch_up: CatchUpPolicy::default(),
+            schedule_since: None,
+            post_backup: PostBackupOptions::default(),
             password: None,
         };
 
@@ -462,6 +540,69 @@ mod tests {
         assert_eq!(config.backup_jobs.len(), 0);
     }
 
+    #[test]
+    fn test_job_execution_update() {
+        let mut config = AppConfig::default();
+        let running = JobExecution {
+            job_id: "job1".to_string(),
+            started_at: "2025-01-01T02:00:00+00:00".to_string(),
+            finished_at: None,
+            status: JobExecutionStatus::Running,
+            snapshot_id: None,
+            files_processed: 0,
+            bytes_processed: 0,
+            duration_seconds: None,
+            error_message: None,
+            stages: Vec::new(),
+        };
+        config.add_job_execution(running.clone());
+
+        let completed = JobExecution {
+            finished_at: Some("2025-01-01T02:05:00+00:00".to_string()),
+            status: JobExecutionStatus::Completed,
+            snapshot_id: Some("abc123".to_string()),
+            duration_seconds: Some(300),
+            ..running
+        };
+        config.update_job_execution(completed);
+
+        assert_eq!(config.job_executions.len(), 1);
+        let last = config.last_job_execution("job1").unwrap();
+        assert_eq!(last.status, JobExecutionStatus::Completed);
+        assert_eq!(last.snapshot_id.as_deref(), Some("abc123"));
+        assert!(config.last_job_execution("job2").is_none());
+    }
+
+    #[test]
+    fn test_fail_interrupted_executions() {
+        let mut config = AppConfig::default();
+        let running = JobExecution {
+            job_id: "job1".to_string(),
+            started_at: "2025-01-01T02:00:00+00:00".to_string(),
+            finished_at: None,
+            status: JobExecutionStatus::Running,
+            snapshot_id: None,
+            files_processed: 0,
+            bytes_processed: 0,
+            duration_seconds: None,
+            error_message: None,
+            stages: Vec::new(),
+        };
+        config.add_job_execution(JobExecution {
+            started_at: "2024-12-31T02:00:00+00:00".to_string(),
+            status: JobExecutionStatus::Completed,
+            ..running.clone()
+        });
+        config.add_job_execution(running);
+
+        assert_eq!(config.fail_interrupted_executions(), 1);
+        let last = config.last_job_execution("job1").unwrap();
+        assert_eq!(last.status, JobExecutionStatus::Failed);
+        assert!(last.error_message.as_deref().unwrap().starts_with("Unterbrochen"));
+        assert_eq!(config.job_executions[0].status, JobExecutionStatus::Completed);
+        assert_eq!(config.fail_interrupted_executions(), 0);
+    }
+
     #[test]
     fn test_config_path() {
         let path = AppConfig::config_path().unwrap();
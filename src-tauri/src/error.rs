@@ -18,8 +18,16 @@ impl From<&RusticGuiError> for ErrorDto {
             SnapshotNotFound { id } => {
                 ("SnapshotNotFound", error.to_string(), Some(format!("id: {}", id)))
             }
+            PathNotFound { path } => {
+                ("PathNotFound", error.to_string(), Some(format!("path: {}", path)))
+            }
             BackupFailed { reason } => ("BackupFailed", error.to_string(), Some(reason.clone())),
+            BackupCancelled => ("BackupCancelled", error.to_string(), None),
             RestoreFailed { reason } => ("RestoreFailed", error.to_string(), Some(reason.clone())),
+            RestoreCancelled => ("RestoreCancelled", error.to_string(), None),
+            RepairPlanOutdated { plan_id } => {
+                ("RepairPlanOutdated", error.to_string(), Some(format!("plan_id: {}", plan_id)))
+            }
             InvalidConfig { field } => {
                 ("InvalidConfig", error.to_string(), Some(format!("field: {}", field)))
             }
@@ -72,12 +80,24 @@ pub enum RusticGuiError {
     #[error("Snapshot nicht gefunden: {id}")]
     SnapshotNotFound { id: String },
 
+    #[error("Pfad nicht im Snapshot gefunden: {path}")]
+    PathNotFound { path: String },
+
     #[error("Backup fehlgeschlagen: {reason}")]
     BackupFailed { reason: String },
 
+    #[error("Backup wurde abgebrochen")]
+    BackupCancelled,
+
     #[error("Restore fehlgeschlagen: {reason}")]
     RestoreFailed { reason: String },
 
+    #[error("Restore wurde abgebrochen")]
+    RestoreCancelled,
+
+    #[error("Reparaturplan ist veraltet, bitte erneut einen Dry-Run ausführen")]
+    RepairPlanOutdated { plan_id: String },
+
     #[error("Konfiguration ungültig: {field}")]
     InvalidConfig { field: String },
 
@@ -168,18 +188,51 @@ mod tests {
         assert_eq!(error.to_string(), "Snapshot nicht gefunden: snapshot-123");
     }
 
+    #[test]
+    fn test_path_not_found_error() {
+        let error = RusticGuiError::PathNotFound { path: "/home/user/file.txt".into() };
+        assert_eq!(error.to_string(), "Pfad nicht im Snapshot gefunden: /home/user/file.txt");
+        assert_eq!(ErrorDto::from(&error).code, "PathNotFound");
+    }
+
     #[test]
     fn test_backup_failed_error() {
         let error = RusticGuiError::BackupFailed { reason: "Disk full".into() };
         assert_eq!(error.to_string(), "Backup fehlgeschlagen: Disk full");
     }
 
+    #[test]
+    fn test_backup_cancelled_error() {
+        let error = RusticGuiError::BackupCancelled;
+        assert_eq!(error.to_string(), "Backup wurde abgebrochen");
+        assert_eq!(ErrorDto::from(&error).code, "BackupCancelled");
+    }
+
     #[test]
     fn test_restore_failed_error() {
         let error = RusticGuiError::RestoreFailed { reason: "Permission denied".into() };
         assert_eq!(error.to_string(), "Restore fehlgeschlagen: Permission denied");
     }
 
+    #[test]
+    fn test_restore_cancelled_error() {
+        let error = RusticGuiError::RestoreCancelled;
+        assert_eq!(error.to_string(), "Restore wurde abgebrochen");
+        assert_eq!(ErrorDto::from(&error).code, "RestoreCancelled");
+    }
+
+    #[test]
+    fn test_repair_plan_outdated_error() {
+        let error = RusticGuiError::RepairPlanOutdated { plan_id: "abc".into() };
+        assert_eq!(
+            error.to_string(),
+            "Reparaturplan ist veraltet, bitte erneut einen Dry-Run ausführen"
+        );
+        let dto = ErrorDto::from(&error);
+        assert_eq!(dto.code, "RepairPlanOutdated");
+        assert_eq!(dto.details.as_deref(), Some("plan_id: abc"));
+    }
+
     #[test]
     fn test_invalid_config_error() {
         let error = RusticGuiError::InvalidConfig { field: "repository.path".into() };
@@ -10,28 +10,38 @@
 // ✅ Repository-Management (TODO.md Zeile 164-174): REGISTRIERT (teilweise Stubs)
 //    - list_repositories, delete_repository: IMPLEMENTIERT
 //    - init_repository, open_repository: SIMULIERT
-//    - check_repository, prune_repository, change_password: STUBS
+//    - prune_repository, change_password: STUBS
+//    - check_repository: ✅ IMPLEMENTIERT (Befunde nach Kategorie, Fortschritt, read-data-Teilmenge)
+//    - repair_index, repair_snapshots: ✅ IMPLEMENTIERT (Dry-Run-Plan, Bestätigung per plan_id)
+//    - check_repository_health, list_repository_locks, force_unlock_repository: ✅ IMPLEMENTIERT
+//      (restic-Locks lokaler Repositories, Entfernen nur veralteter Locks)
+//    - get_repository_stats: ✅ IMPLEMENTIERT (aus Index und Datei-Listing, gecacht)
+//    - get_storage_attribution: ✅ IMPLEMENTIERT (Belegung pro Snapshot/Host/Tag/Job)
 //
 // ✅ Backup-Job-Management (TODO.md Zeile 175-181): VOLLSTÄNDIG IMPLEMENTIERT
 //    - Alle 5 Commands (list, create, update, delete, get) in commands/backup.rs
 //
 // ✅ Snapshot-Management (TODO.md Zeile 182-187): VOLLSTÄNDIG IMPLEMENTIERT
 //    - list_snapshots, get_snapshot, delete_snapshot, forget_snapshots: IMPLEMENTIERT
-//    - compare_snapshots: ✅ IMPLEMENTIERT (Tree-basierter Diff mit rustic CLI Pattern)
+//    - compare_snapshots / diff_snapshots: ✅ IMPLEMENTIERT (Merge-Join-Diff mit Änderungsklassen)
+//    - find_in_snapshots: ✅ IMPLEMENTIERT (Dateisuche über alle Snapshots)
+//    - get_file_history: ✅ IMPLEMENTIERT (Versionen einer Datei über alle Snapshots)
+//    - query_snapshots_command: ✅ IMPLEMENTIERT (Snapshot-Filter, Sortierung und Paging)
 //
 // ✅ Prozess-Steuerung (TODO.md Zeile 189-198): SIMULIERT mit Events
-//    - run_backup (Zeile 121): Simuliert mit BackupEvent
+//    - run_backup: Echte rustic_core-Integration (manuell und geplant)
 //    - cancel_backup (Zeile 37): Implementiert mit CancellationToken
-//    - restore_files_v1 (Zeile 324): Simuliert mit RestoreEvent
-//    - get_file_tree_command (Zeile 312): Stub
+//    - diff_backup_source: Letzter Snapshot eines Jobs vs. aktuelles Dateisystem
+//    - restore_files_v1: Echter Fortschritt aus rustic_core, abbrechbar via cancel_restore
+//    - restore_to_archive: Teilbaum als tar/tar.gz/tar.zst/zip exportieren
+//    - preview_snapshot_file / extract_snapshot_file: Einzelne Datei ohne Restore-Plan lesen
+//    - get_file_tree_command: Lazy-Loading aus den Snapshot-Trees
 //
 // ⏳ Weitere Integration benötigt:
 //    - Echte rustic_core Integration statt Simulationen
 //    - Job-Scheduler für zeitgesteuerte Backups
 //    - Vollständige Error-DTOs (types.rs:45-51)
 
-use tauri::Emitter;
-
 pub mod commands;
 pub mod config;
 pub mod error;
@@ -75,55 +85,20 @@ async fn restore_scheduled_jobs(
     for (job_id, cron_expression) in jobs_to_schedule {
         tracing::info!("Stelle geplanten Job wieder her: {} ({})", job_id, cron_expression);
 
-        let job_id_clone = job_id.clone();
-        let app_handle_clone = app_handle.clone();
-        let state_clone = state.clone();
-
-        let mut scheduler_lock = state.scheduler.lock().await;
-        if let Some(scheduler) = scheduler_lock.as_mut() {
-            match scheduler
-                .schedule_job(job_id.clone(), &cron_expression, move || {
-                    let job_id = job_id_clone.clone();
-                    let app_handle = app_handle_clone.clone();
-                    let _state = state_clone.clone();
-
-                    Box::pin(async move {
-                        tracing::info!("Scheduled backup gestartet: {}", job_id);
-
-                        // Event: Backup gestartet
-                        let _ = app_handle.emit(
-                            "scheduled-backup-started",
-                            serde_json::json!({
-                                "job_id": job_id,
-                                "time": chrono::Utc::now().to_rfc3339(),
-                            }),
-                        );
-
-                        // TODO M3.3: Integriere run_backup hier
-                        // Vorläufig: Simuliere Backup-Ausführung
-                        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-
-                        // Event: Backup abgeschlossen
-                        let _ = app_handle.emit(
-                            "scheduled-backup-completed",
-                            serde_json::json!({
-                                "job_id": job_id,
-                                "time": chrono::Utc::now().to_rfc3339(),
-                            }),
-                        );
-
-                        tracing::info!("Scheduled backup abgeschlossen: {}", job_id);
-                    })
-                })
-                .await
-            {
-                Ok(_) => {
-                    restored_count += 1;
-                    tracing::info!("Job '{}' erfolgreich wiederhergestellt", job_id);
-                }
-                Err(e) => {
-                    tracing::error!("Fehler beim Wiederherstellen von Job '{}': {}", job_id, e);
-                }
+        match commands::backup::register_scheduled_backup(
+            state,
+            app_handle,
+            &job_id,
+            &cron_expression,
+        )
+        .await
+        {
+            Ok(()) => {
+                restored_count += 1;
+                tracing::info!("Job '{}' erfolgreich wiederhergestellt", job_id);
+            }
+            Err(e) => {
+                tracing::error!("Fehler beim Wiederherstellen von Job '{}': {}", job_id, e);
             }
         }
     }
@@ -148,6 +123,18 @@ pub fn run() {
         .setup(move |app| {
             let app_handle = app.handle().clone();
 
+            // Beim letzten Beenden noch laufende Jobs als unterbrochen markieren
+            let interrupted = app_state_clone.config.lock().fail_interrupted_executions();
+            if interrupted > 0 {
+                tracing::warn!(
+                    "{} unterbrochene Job-Ausführungen als fehlgeschlagen markiert",
+                    interrupted
+                );
+                if let Err(e) = app_state_clone.save_config() {
+                    tracing::error!("Config-Speicherung fehlgeschlagen: {}", e);
+                }
+            }
+
             // Scheduler async initialisieren
             tauri::async_runtime::block_on(async {
                 app_state_clone
@@ -161,6 +148,25 @@ pub fn run() {
                     .expect("Wiederherstellung geplanter Jobs fehlgeschlagen");
             });
 
+            // Änderungen der Job-Warteschlange an das Frontend senden
+            let queue_handle = app_handle.clone();
+            app_state_clone.job_queue.set_listener(move |entries| {
+                use tauri::Emitter;
+                let _ = queue_handle.emit("job-queue-changed", entries);
+            });
+
+            // Verpasste Läufe beim Start und nach dem Aufwachen aus dem Ruhezustand nachholen
+            commands::backup::catch_up_missed_runs(&app_state_clone, &app_handle);
+            let wake_state = app_state_clone.clone();
+            let wake_handle = app_handle.clone();
+            tauri::async_runtime::spawn(scheduler::watch_for_wake(
+                std::time::Duration::from_secs(60),
+                move || {
+                    commands::backup::catch_up_missed_runs(&wake_state, &wake_handle);
+                    std::future::ready(())
+                },
+            ));
+
             // Status des portablen Speichers an das Frontend senden
             app_state_clone.emit_portable_status_event(&app_handle);
 
@@ -171,6 +177,9 @@ pub fn run() {
             greet,
             commands::system::prepare_shutdown,
             commands::system::get_portable_status,
+            commands::system::check_repository_health,
+            commands::system::list_repository_locks,
+            commands::system::force_unlock_repository,
             // --- Repository Management ---
             commands::repository::init_repository,
             commands::repository::open_repository,
@@ -183,6 +192,8 @@ pub fn run() {
             commands::repository::delete_repository,
             commands::repository::remove_repository_from_config,
             commands::repository::check_repository,
+            commands::repository::repair_index,
+            commands::repository::repair_snapshots,
             commands::repository::prune_repository_v1,
             commands::repository::change_password_v1,
             commands::repository::prune_repository,
@@ -193,6 +204,7 @@ pub fn run() {
             commands::repository::update_favorite_last_used,
             commands::repository::delete_favorite_location,
             commands::repository::get_repository_stats,
+            commands::repository::get_storage_attribution,
             // --- Backup-Jobs ---
             commands::backup::run_backup,
             commands::backup::run_backup_command,
@@ -206,13 +218,19 @@ pub fn run() {
             commands::backup::unschedule_backup,
             commands::backup::list_scheduled_backups,
             commands::backup::list_job_history,
+            commands::backup::list_job_queue,
+            commands::backup::diff_backup_source,
             // --- Snapshot Management ---
             commands::snapshot::list_snapshots_command,
             commands::snapshot::list_snapshots_filtered_command,
+            commands::snapshot::query_snapshots_command,
             commands::snapshot::get_snapshot_command,
             commands::snapshot::delete_snapshot_command,
             commands::snapshot::forget_snapshots,
             commands::snapshot::compare_snapshots,
+            commands::snapshot::diff_snapshots,
+            commands::snapshot::find_in_snapshots,
+            commands::snapshot::get_file_history,
             commands::snapshot::add_snapshot_tags,
             commands::snapshot::remove_snapshot_tags,
             // --- Retention Policy ---
@@ -221,6 +239,10 @@ pub fn run() {
             // --- Restore ---
             commands::restore::get_file_tree_command,
             commands::restore::restore_files_v1,
+            commands::restore::cancel_restore,
+            commands::restore::restore_to_archive,
+            commands::restore::preview_snapshot_file,
+            commands::restore::extract_snapshot_file,
             // --- Settings ---
             commands::settings::get_settings,
             commands::settings::save_settings,
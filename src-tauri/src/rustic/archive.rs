@@ -0,0 +1,472 @@
+//! Restore in eine Archivdatei (tar, tar.gz, tar.zst, zip)
+//!
+//! Schreibt einen Teilbaum eines Snapshots als Archiv, ähnlich `rustic dump`. Die
+//! Dateiinhalte werden blockweise aus dem Repository gelesen und direkt in das Archiv
+//! gestreamt; tar-Formate funktionieren mit jedem `Write`, zip benötigt zusätzlich `Seek`.
+
+use crate::error::RusticGuiError;
+use crate::types::{ArchiveFormat, ArchiveResultDto, ProgressInfo, RestoreProgress};
+use parking_lot::Mutex;
+use rustic_backend::BackendOptions;
+use rustic_core::{
+    IndexedFull, LsOptions, Repository, RepositoryOptions, RusticResult,
+    repofile::{Node, NodeType},
+    vfs::OpenFile,
+};
+use std::io::{self, BufReader, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// Puffergröße beim Lesen der Dateiinhalte aus dem Repository
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Mindestabstand zwischen zwei Progress-Events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Zählt den Fortschritt beim Schreiben des Archivs und meldet ihn gedrosselt
+struct ArchiveProgress<F> {
+    on_progress: F,
+    total_files: u64,
+    total_bytes: u64,
+    files: u64,
+    bytes: u64,
+    last_update: Option<Instant>,
+}
+
+impl<F> ArchiveProgress<F>
+where
+    F: Fn(RestoreProgress),
+{
+    fn report(&mut self, current_file: &Path, force: bool) {
+        if !force && self.last_update.is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        self.last_update = Some(Instant::now());
+
+        let percentage = if self.total_bytes == 0 {
+            100.0
+        } else {
+            ((self.bytes as f64 / self.total_bytes as f64) * 100.0).min(100.0) as f32
+        };
+        (self.on_progress)(RestoreProgress {
+            base: ProgressInfo {
+                current: self.files,
+                total: self.total_files,
+                message: Some("Archiv wird erstellt...".to_string()),
+                percentage: Some(percentage),
+            },
+            files_restored: self.files,
+            bytes_restored: self.bytes,
+            current_file: Some(format!("/{}", current_file.display())),
+        });
+    }
+}
+
+/// Liest den Inhalt eines Datei-Knotens aus dem Repository
+struct NodeReader<'a, P, S> {
+    repo: &'a Repository<P, S>,
+    file: OpenFile,
+    offset: usize,
+    cancel: &'a CancellationToken,
+    read: &'a Mutex<u64>,
+}
+
+impl<P, S: IndexedFull> Read for NodeReader<'_, P, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.cancel.is_cancelled() {
+            // Nicht `Interrupted`: `io::copy` würde den Lesevorgang sonst endlos wiederholen
+            return Err(io::Error::other("Restore wurde abgebrochen"));
+        }
+        let data =
+            self.repo.read_file_at(&self.file, self.offset, buf.len()).map_err(io::Error::other)?;
+        buf[..data.len()].copy_from_slice(&data);
+        self.offset += data.len();
+        *self.read.lock() += data.len() as u64;
+        Ok(data.len())
+    }
+}
+
+/// Einträge eines Archivs mit ihrem Pfad im Archiv, in Snapshot-Reihenfolge
+type ArchiveEntries<'a> = Box<dyn Iterator<Item = RusticResult<(PathBuf, Node)>> + 'a>;
+
+/// Schreibt Einträge als tar-Archiv
+///
+/// # Returns
+/// Pfade ausgelassener (nicht unterstützter) Einträge
+fn write_tar<W, P, S, F>(
+    builder: &mut tar::Builder<W>,
+    repo: &Repository<P, S>,
+    entries: ArchiveEntries<'_>,
+    cancel: &CancellationToken,
+    progress: &mut ArchiveProgress<F>,
+) -> Result<Vec<String>, RusticGuiError>
+where
+    W: Write,
+    S: IndexedFull,
+    F: Fn(RestoreProgress),
+{
+    let mut skipped = Vec::new();
+    for entry in entries {
+        let (path, node) = entry.map_err(|e| archive_error(cancel, e))?;
+
+        let mut header = tar::Header::new_gnu();
+        header.set_mtime(node.meta.mtime.map_or(0, |t| t.timestamp().max(0) as u64));
+        header.set_uid(u64::from(node.meta.uid.unwrap_or(0)));
+        header.set_gid(u64::from(node.meta.gid.unwrap_or(0)));
+        if let Some(user) = &node.meta.user {
+            let _ = header.set_username(user);
+        }
+        if let Some(group) = &node.meta.group {
+            let _ = header.set_groupname(group);
+        }
+
+        match &node.node_type {
+            NodeType::Dir => {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_mode(permissions(&node, 0o755));
+                header.set_size(0);
+                builder.append_data(&mut header, &path, io::empty())?;
+                progress.report(&path, false);
+            }
+            NodeType::File => {
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_mode(permissions(&node, 0o644));
+                header.set_size(node.meta.size);
+                let read = Mutex::new(0);
+                let reader = node_reader(repo, &node, cancel, &read)?;
+                builder.append_data(&mut header, &path, reader).map_err(|e| io_error(cancel, e))?;
+                progress.files += 1;
+                progress.bytes += *read.lock();
+                progress.report(&path, false);
+            }
+            NodeType::Symlink { .. } => {
+                header.set_entry_type(tar::EntryType::Symlink);
+                header.set_mode(permissions(&node, 0o777));
+                header.set_size(0);
+                builder.append_link(&mut header, &path, node.node_type.to_link())?;
+            }
+            _ => {
+                warn!(path = %path.display(), "Eintrag wird im Archiv ausgelassen");
+                skipped.push(format!("/{}", path.display()));
+            }
+        }
+    }
+    Ok(skipped)
+}
+
+/// Schreibt Einträge als zip-Archiv
+///
+/// # Returns
+/// Pfade ausgelassener (nicht unterstützter) Einträge
+fn write_zip<W, P, S, F>(
+    zip: &mut zip::ZipWriter<W>,
+    repo: &Repository<P, S>,
+    entries: ArchiveEntries<'_>,
+    cancel: &CancellationToken,
+    progress: &mut ArchiveProgress<F>,
+) -> Result<Vec<String>, RusticGuiError>
+where
+    W: Write + Seek,
+    S: IndexedFull,
+    F: Fn(RestoreProgress),
+{
+    let mut skipped = Vec::new();
+    for entry in entries {
+        let (path, node) = entry.map_err(|e| archive_error(cancel, e))?;
+        let name = path.to_string_lossy().to_string();
+
+        let mut options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        if let Some(mtime) = node.meta.mtime.and_then(|t| zip_time(&t)) {
+            options = options.last_modified_time(mtime);
+        }
+
+        match &node.node_type {
+            NodeType::Dir => {
+                zip.add_directory(name, options.unix_permissions(permissions(&node, 0o755)))
+                    .map_err(zip_error)?;
+                progress.report(&path, false);
+            }
+            NodeType::File => {
+                let options = options
+                    .unix_permissions(permissions(&node, 0o644))
+                    .large_file(node.meta.size >= u64::from(u32::MAX));
+                zip.start_file(name, options).map_err(zip_error)?;
+                let read = Mutex::new(0);
+                let mut reader = node_reader(repo, &node, cancel, &read)?;
+                io::copy(&mut reader, zip).map_err(|e| io_error(cancel, e))?;
+                progress.files += 1;
+                progress.bytes += *read.lock();
+                progress.report(&path, false);
+            }
+            NodeType::Symlink { .. } => {
+                let target = node.node_type.to_link().to_string_lossy().to_string();
+                zip.add_symlink(name, target, options).map_err(zip_error)?;
+            }
+            _ => {
+                warn!(path = %path.display(), "Eintrag wird im Archiv ausgelassen");
+                skipped.push(format!("/{}", path.display()));
+            }
+        }
+    }
+    Ok(skipped)
+}
+
+/// Öffnet den Inhalt eines Datei-Knotens als gepufferten Reader
+fn node_reader<'a, P, S: IndexedFull>(
+    repo: &'a Repository<P, S>,
+    node: &Node,
+    cancel: &'a CancellationToken,
+    read: &'a Mutex<u64>,
+) -> Result<BufReader<NodeReader<'a, P, S>>, RusticGuiError> {
+    let file = repo
+        .open_file(node)
+        .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?;
+    Ok(BufReader::with_capacity(
+        READ_BUFFER_SIZE,
+        NodeReader { repo, file, offset: 0, cancel, read },
+    ))
+}
+
+/// Berechtigungsbits eines Knotens (ohne Dateityp) mit Standardwert
+fn permissions(node: &Node, default: u32) -> u32 {
+    node.meta.mode.map_or(default, |mode| mode & 0o7777)
+}
+
+/// Konvertiert eine Änderungszeit in das zip-Format (nur 1980-2107 darstellbar)
+fn zip_time(time: &chrono::DateTime<chrono::Local>) -> Option<zip::DateTime> {
+    use chrono::{Datelike, Timelike};
+    zip::DateTime::from_date_and_time(
+        u16::try_from(time.year()).ok()?,
+        time.month() as u8,
+        time.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+    )
+    .ok()
+}
+
+fn archive_error(cancel: &CancellationToken, e: impl std::fmt::Display) -> RusticGuiError {
+    if cancel.is_cancelled() {
+        RusticGuiError::RestoreCancelled
+    } else {
+        RusticGuiError::RestoreFailed { reason: e.to_string() }
+    }
+}
+
+fn io_error(cancel: &CancellationToken, e: io::Error) -> RusticGuiError {
+    archive_error(cancel, e)
+}
+
+fn zip_error(e: zip::result::ZipError) -> RusticGuiError {
+    RusticGuiError::RestoreFailed { reason: format!("zip-Archiv: {e}") }
+}
+
+/// Stellt einen Teilbaum eines Snapshots als Archivdatei wieder her.
+///
+/// Verzeichnisse, Dateien und Symlinks werden mit Berechtigungen, Besitzer und
+/// Änderungszeit übernommen; Geräte, FIFOs und Sockets werden ausgelassen. Bei Fehler
+/// oder Abbruch wird die unvollständige Archivdatei wieder entfernt.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `snapshot_id` - ID des Snapshots
+/// * `path` - Pfad im Snapshot (None oder "/" = kompletter Snapshot); das Archiv enthält
+///   den ausgewählten Eintrag mit seinem Namen als oberste Ebene
+/// * `output_path` - Zu erstellende Archivdatei (wird überschrieben)
+/// * `format` - Archivformat
+/// * `cancel_token` - Token zum Abbrechen
+/// * `on_progress` - Callback für Fortschritts-Updates
+///
+/// # Returns
+/// Zusammenfassung des erstellten Archivs
+///
+/// # Errors
+/// `PathNotFound`, wenn der Pfad nicht im Snapshot existiert;
+/// `RestoreCancelled`, wenn der Vorgang abgebrochen wurde
+#[allow(clippy::too_many_arguments)]
+pub fn restore_to_archive<F>(
+    repository_path: &str,
+    password: &str,
+    snapshot_id: &str,
+    path: Option<&str>,
+    output_path: &str,
+    format: ArchiveFormat,
+    cancel_token: CancellationToken,
+    on_progress: F,
+) -> Result<ArchiveResultDto, RusticGuiError>
+where
+    F: Fn(RestoreProgress),
+{
+    info!(repo = repository_path, snapshot = snapshot_id, path = ?path, output = output_path, ?format, "Starte Restore in Archiv");
+
+    let mut repo_opts = RepositoryOptions::default();
+    repo_opts.password = Some(password.to_string());
+    let mut backend_opts = BackendOptions::default();
+    backend_opts.repository = Some(repository_path.to_string());
+    let backends = backend_opts.to_backends().map_err(|e| {
+        RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+    })?;
+    let repo = Repository::new(&repo_opts, &backends)
+        .map_err(|e| {
+            error!(?e, "Fehler beim Initialisieren des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .open()
+        .map_err(|e| {
+            error!(?e, "Fehler beim Öffnen des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .to_indexed()
+        .map_err(|e| {
+            error!(?e, "Repository-Indexierung fehlgeschlagen");
+            RusticGuiError::Internal(format!("Repository-Indexierung fehlgeschlagen: {e}"))
+        })?;
+
+    let snapshot = repo
+        .get_snapshot_from_str(snapshot_id, |_| true)
+        .map_err(|_| RusticGuiError::SnapshotNotFound { id: snapshot_id.to_string() })?;
+    let relative = path.unwrap_or("/").trim_matches('/');
+    let node = repo
+        .node_from_snapshot_and_path(&snapshot, relative)
+        .map_err(|_| RusticGuiError::PathNotFound { path: format!("/{relative}") })?;
+
+    // Oberste Ebene im Archiv: Name der Auswahl bzw. direkt der Inhalt des Snapshots
+    let base = if relative.is_empty() { PathBuf::new() } else { PathBuf::from(node.name()) };
+    let mut ls_opts = LsOptions::default();
+    ls_opts.recursive = true;
+    let entries = || -> Result<ArchiveEntries<'_>, RusticGuiError> {
+        let root: Option<RusticResult<(PathBuf, Node)>> =
+            (!relative.is_empty()).then(|| Ok((base.clone(), node.clone())));
+        if !node.is_dir() {
+            return Ok(Box::new(root.into_iter()));
+        }
+        let base = base.clone();
+        let children = repo
+            .ls(&node, &ls_opts)
+            .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?
+            .map(move |item| item.map(|(path, n)| (base.join(path), n)));
+        Ok(Box::new(root.into_iter().chain(children)))
+    };
+
+    // Vorab Dateien und Bytes zählen, damit der Fortschritt einen Gesamtwert hat
+    let (mut total_files, mut total_bytes, mut dirs) = (0, 0, 0);
+    for entry in entries()? {
+        let (_, n) = entry.map_err(|e| archive_error(&cancel_token, e))?;
+        if cancel_token.is_cancelled() {
+            return Err(RusticGuiError::RestoreCancelled);
+        }
+        match n.node_type {
+            NodeType::File => {
+                total_files += 1;
+                total_bytes += n.meta.size;
+            }
+            NodeType::Dir => dirs += 1,
+            _ => {}
+        }
+    }
+    let mut progress = ArchiveProgress {
+        on_progress,
+        total_files,
+        total_bytes,
+        files: 0,
+        bytes: 0,
+        last_update: None,
+    };
+
+    let output = PathBuf::from(output_path);
+    let file = std::fs::File::create(&output)?;
+    let result = (|| -> Result<Vec<String>, RusticGuiError> {
+        let entries = entries()?;
+        let cancel = &cancel_token;
+        let progress = &mut progress;
+        match format {
+            ArchiveFormat::Tar => {
+                let mut builder = tar::Builder::new(io::BufWriter::new(file));
+                let skipped = write_tar(&mut builder, &repo, entries, cancel, progress)?;
+                builder.into_inner()?.flush()?;
+                Ok(skipped)
+            }
+            ArchiveFormat::TarGz => {
+                let encoder = flate2::write::GzEncoder::new(
+                    io::BufWriter::new(file),
+                    flate2::Compression::default(),
+                );
+                let mut builder = tar::Builder::new(encoder);
+                let skipped = write_tar(&mut builder, &repo, entries, cancel, progress)?;
+                builder.into_inner()?.finish()?.flush()?;
+                Ok(skipped)
+            }
+            ArchiveFormat::TarZstd => {
+                let encoder = zstd::Encoder::new(io::BufWriter::new(file), 0)?;
+                let mut builder = tar::Builder::new(encoder);
+                let skipped = write_tar(&mut builder, &repo, entries, cancel, progress)?;
+                builder.into_inner()?.finish()?.flush()?;
+                Ok(skipped)
+            }
+            ArchiveFormat::Zip => {
+                let mut zip = zip::ZipWriter::new(io::BufWriter::new(file));
+                let skipped = write_zip(&mut zip, &repo, entries, cancel, progress)?;
+                zip.finish().map_err(zip_error)?.flush()?;
+                Ok(skipped)
+            }
+        }
+    })();
+
+    let skipped = match result {
+        Ok(skipped) => skipped,
+        Err(e) => {
+            warn!(output = output_path, error = %e, "Archiv unvollständig, wird entfernt");
+            let _ = std::fs::remove_file(&output);
+            return Err(if cancel_token.is_cancelled() {
+                RusticGuiError::RestoreCancelled
+            } else {
+                e
+            });
+        }
+    };
+    progress.report(Path::new(""), true);
+
+    let archive_size = std::fs::metadata(&output).map(|m| m.len()).unwrap_or_default();
+    info!(output = output_path, files = progress.files, bytes = archive_size, "Archiv erstellt");
+
+    Ok(ArchiveResultDto {
+        output_path: output_path.to_string(),
+        format,
+        files: progress.files,
+        dirs,
+        skipped,
+        bytes: progress.bytes,
+        archive_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rustic_core::repofile::Metadata;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_permissions_and_zip_time() {
+        let meta = Metadata { mode: Some(0o100640), ..Default::default() };
+        let node = Node::new_node(OsStr::new("a"), NodeType::File, meta);
+        assert_eq!(permissions(&node, 0o644), 0o640);
+
+        let dir = Node::new_node(OsStr::new("d"), NodeType::Dir, Metadata::default());
+        assert_eq!(permissions(&dir, 0o755), 0o755);
+
+        let time = chrono::Local.with_ymd_and_hms(2024, 5, 17, 13, 45, 30).unwrap();
+        let zip = zip_time(&time).unwrap();
+        assert_eq!((zip.year(), zip.month(), zip.day()), (2024, 5, 17));
+        assert_eq!((zip.hour(), zip.minute(), zip.second()), (13, 45, 30));
+
+        let too_old = chrono::Local.with_ymd_and_hms(1970, 1, 2, 0, 0, 0).unwrap();
+        assert!(zip_time(&too_old).is_none());
+    }
+}
@@ -0,0 +1,422 @@
+//! Speicherbelegung pro Snapshot, Host, Tag und Backup-Job
+//!
+//! Jeder Blob (Tree oder Data) wird den Snapshots zugeordnet, die ihn referenzieren.
+//! Daraus ergibt sich pro Gruppe von Snapshots, wie viel Speicher nach einem Forget der
+//! Gruppe (und anschließendem Prune) frei würde und wie viel sie mit anderen teilt.
+//!
+//! Jeder Tree wird genau einmal gelesen. Statt pro Blob eine eigene Snapshot-Liste zu
+//! speichern, werden identische Snapshot-Mengen ("Owner-Sets") nur einmal abgelegt; in
+//! typischen Repositories gibt es davon nur wenige tausend.
+
+use crate::error::RusticGuiError;
+use crate::rustic::backup::source_roots;
+use crate::types::{StorageAttributionDto, StorageUsageDto};
+use rustic_backend::BackendOptions;
+use rustic_core::{DataId, Repository, RepositoryOptions, TreeId, repofile::SnapshotFile};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tracing::{error, info, warn};
+
+/// Backup-Job, dessen Snapshots zusammengefasst werden sollen
+#[derive(Debug, Clone)]
+pub struct AttributionJob {
+    /// Job-ID
+    pub id: String,
+    /// Anzeigename
+    pub name: String,
+    /// Quellpfade des Jobs
+    pub source_paths: Vec<String>,
+    /// Tags des Jobs (müssen alle am Snapshot vorhanden sein)
+    pub tags: Vec<String>,
+}
+
+/// Referenzen eines Trees auf Unter-Trees und Datenblobs
+#[derive(Debug, Default)]
+struct TreeRefs {
+    subtrees: Vec<TreeId>,
+    data: Vec<DataId>,
+}
+
+/// Internierte Mengen von Snapshot-Indizes; Set 0 ist die leere Menge
+#[derive(Debug)]
+struct OwnerSets {
+    sets: Vec<Vec<u32>>,
+    ids: HashMap<Vec<u32>, usize>,
+    unions: HashMap<(usize, usize), usize>,
+}
+
+impl OwnerSets {
+    fn new() -> Self {
+        Self {
+            sets: vec![Vec::new()],
+            ids: HashMap::from([(Vec::new(), 0)]),
+            unions: HashMap::new(),
+        }
+    }
+
+    /// Liefert die ID der (sortierten) Menge und legt sie bei Bedarf an
+    fn intern(&mut self, set: Vec<u32>) -> usize {
+        if let Some(id) = self.ids.get(&set) {
+            return *id;
+        }
+        let id = self.sets.len();
+        self.sets.push(set.clone());
+        self.ids.insert(set, id);
+        id
+    }
+
+    /// Vereinigung zweier Mengen (zwischengespeichert)
+    fn union(&mut self, a: usize, b: usize) -> usize {
+        if a == b || b == 0 {
+            return a;
+        }
+        if a == 0 {
+            return b;
+        }
+        let key = (a.min(b), a.max(b));
+        if let Some(id) = self.unions.get(&key) {
+            return *id;
+        }
+        let mut merged: Vec<u32> =
+            self.sets[a].iter().chain(self.sets[b].iter()).copied().collect();
+        merged.sort_unstable();
+        merged.dedup();
+        let id = self.intern(merged);
+        self.unions.insert(key, id);
+        id
+    }
+}
+
+/// Owner-Sets aller Trees und Datenblobs
+struct Ownership {
+    sets: OwnerSets,
+    trees: HashMap<TreeId, usize>,
+    data: HashMap<DataId, usize>,
+}
+
+/// Ordnet jedem Tree und Datenblob die Menge der Snapshots zu, die ihn erreichen.
+///
+/// Die Trees bilden einen azyklischen Graphen; sie werden in topologischer Reihenfolge
+/// (Eltern vor Kindern) verarbeitet, sodass jeder Tree genau einmal besucht wird.
+fn ownership(graph: &HashMap<TreeId, TreeRefs>, roots: &[TreeId]) -> Ownership {
+    let mut sets = OwnerSets::new();
+    let mut trees: HashMap<TreeId, usize> = HashMap::new();
+    for (index, root) in roots.iter().enumerate() {
+        let own = sets.intern(vec![index as u32]);
+        let current = trees.get(root).copied().unwrap_or(0);
+        let merged = sets.union(current, own);
+        trees.insert(*root, merged);
+    }
+
+    let mut parents: HashMap<TreeId, usize> = HashMap::new();
+    for refs in graph.values() {
+        for subtree in &refs.subtrees {
+            *parents.entry(*subtree).or_default() += 1;
+        }
+    }
+    let mut ready: Vec<TreeId> =
+        graph.keys().filter(|id| !parents.contains_key(id)).copied().collect();
+
+    let mut data: HashMap<DataId, usize> = HashMap::new();
+    while let Some(id) = ready.pop() {
+        let owner = trees.get(&id).copied().unwrap_or(0);
+        let Some(refs) = graph.get(&id) else {
+            continue;
+        };
+        for subtree in &refs.subtrees {
+            let current = trees.get(subtree).copied().unwrap_or(0);
+            let merged = sets.union(current, owner);
+            trees.insert(*subtree, merged);
+            if let Some(count) = parents.get_mut(subtree) {
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(*subtree);
+                }
+            }
+        }
+        for blob in &refs.data {
+            let current = data.get(blob).copied().unwrap_or(0);
+            let merged = sets.union(current, owner);
+            data.insert(*blob, merged);
+        }
+    }
+
+    Ownership { sets, trees, data }
+}
+
+/// Belegung einer Gruppe: (nur von der Gruppe referenziert, insgesamt referenziert)
+///
+/// `groups_of[snapshot]` enthält die Gruppen eines Snapshots (ohne Duplikate).
+fn attribute(
+    sets: &[Vec<u32>],
+    bytes: &[u64],
+    groups_of: &[Vec<usize>],
+    group_count: usize,
+) -> Vec<(u64, u64)> {
+    let mut usage = vec![(0u64, 0u64); group_count];
+    let mut members: BTreeMap<usize, usize> = BTreeMap::new();
+    for (set, &size) in sets.iter().zip(bytes) {
+        if size == 0 || set.is_empty() {
+            continue;
+        }
+        members.clear();
+        for snapshot in set {
+            for group in &groups_of[*snapshot as usize] {
+                *members.entry(*group).or_default() += 1;
+            }
+        }
+        for (group, count) in &members {
+            usage[*group].1 += size;
+            if *count == set.len() {
+                usage[*group].0 += size;
+            }
+        }
+    }
+    usage
+}
+
+/// Gruppierung der Snapshots nach einem Schlüssel (Host, Tag, Job)
+struct Grouping {
+    keys: Vec<(String, String)>,
+    groups_of: Vec<Vec<usize>>,
+}
+
+impl Grouping {
+    /// # Arguments
+    /// * `snapshots` - Alle Snapshots
+    /// * `keys_of` - Liefert (Schlüssel, Anzeigename) aller Gruppen eines Snapshots
+    fn new<F>(snapshots: &[SnapshotFile], keys_of: F) -> Self
+    where
+        F: Fn(&SnapshotFile) -> Vec<(String, String)>,
+    {
+        let mut index: BTreeMap<String, usize> = BTreeMap::new();
+        let mut keys = Vec::new();
+        let groups_of = snapshots
+            .iter()
+            .map(|snap| {
+                let mut groups: Vec<usize> = keys_of(snap)
+                    .into_iter()
+                    .map(|(key, label)| {
+                        *index.entry(key.clone()).or_insert_with(|| {
+                            keys.push((key, label));
+                            keys.len() - 1
+                        })
+                    })
+                    .collect();
+                groups.sort_unstable();
+                groups.dedup();
+                groups
+            })
+            .collect();
+        Self { keys, groups_of }
+    }
+
+    /// Berechnet die Belegung jeder Gruppe, größte eigene Belegung zuerst
+    fn usage(&self, sets: &[Vec<u32>], bytes: &[u64]) -> Vec<StorageUsageDto> {
+        let usage = attribute(sets, bytes, &self.groups_of, self.keys.len());
+        let mut counts = vec![0u64; self.keys.len()];
+        for groups in &self.groups_of {
+            for group in groups {
+                counts[*group] += 1;
+            }
+        }
+        let mut result: Vec<StorageUsageDto> = self
+            .keys
+            .iter()
+            .zip(usage)
+            .zip(counts)
+            .map(|(((key, label), (unique, referenced)), count)| StorageUsageDto {
+                key: key.clone(),
+                label: label.clone(),
+                snapshot_count: count,
+                unique_size: unique,
+                shared_size: referenced - unique,
+                referenced_size: referenced,
+            })
+            .collect();
+        result.sort_by(|a, b| b.unique_size.cmp(&a.unique_size).then_with(|| a.key.cmp(&b.key)));
+        result
+    }
+}
+
+/// Ermittelt, wie viel Speicher jeder Snapshot, Host, Tag und Job belegt.
+///
+/// Größen sind die gespeicherten (komprimierten, verschlüsselten) Blob-Längen laut Index.
+/// `unique_size` ist der Speicher, der nach Forget der Gruppe und einem Prune frei würde;
+/// Pack-Overhead und Repacking bleiben unberücksichtigt.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `jobs` - Backup-Jobs dieses Repositories
+///
+/// # Returns
+/// Belegung pro Snapshot, Host, Tag und Job
+///
+/// # Errors
+/// `RusticError`, wenn Index, Snapshots oder Trees nicht gelesen werden können
+pub fn storage_attribution(
+    repository_path: &str,
+    password: &str,
+    jobs: &[AttributionJob],
+) -> Result<StorageAttributionDto, RusticGuiError> {
+    let rustic_error = |what: &str, e: Box<rustic_core::RusticError>| {
+        error!("{} fehlgeschlagen: {:?}", what, e);
+        RusticGuiError::RusticError { message: format!("{what} fehlgeschlagen: {e}") }
+    };
+
+    let mut repo_opts = RepositoryOptions::default();
+    repo_opts.password = Some(password.to_string());
+    let backends =
+        BackendOptions::default().repository(repository_path).to_backends().map_err(|e| {
+            RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+        })?;
+    let repo = Repository::new(&repo_opts, &backends)
+        .map_err(|e| {
+            error!(?e, "Fehler beim Initialisieren des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .open()
+        .map_err(|e| {
+            error!(?e, "Fehler beim Öffnen des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?;
+
+    let mut snapshots = repo.get_all_snapshots().map_err(|e| rustic_error("Snapshots laden", e))?;
+    snapshots.sort_by_key(|snap| std::cmp::Reverse(snap.time));
+    let repo = repo.to_indexed().map_err(|e| rustic_error("Repository-Indexierung", e))?;
+    info!(snapshots = snapshots.len(), "Berechne Speicherbelegung");
+
+    // 1. Alle erreichbaren Trees einmal lesen
+    let roots: Vec<TreeId> = snapshots.iter().map(|snap| snap.tree).collect();
+    let mut graph: HashMap<TreeId, TreeRefs> = HashMap::new();
+    let mut pending = roots.clone();
+    while let Some(id) = pending.pop() {
+        if graph.contains_key(&id) {
+            continue;
+        }
+        let tree = repo.get_tree(&id).map_err(|e| rustic_error("Tree lesen", e))?;
+        let mut refs = TreeRefs::default();
+        for node in tree.nodes {
+            if let Some(subtree) = node.subtree {
+                refs.subtrees.push(subtree);
+                pending.push(subtree);
+            }
+            refs.data.extend(node.content.into_iter().flatten());
+        }
+        graph.insert(id, refs);
+    }
+
+    // 2. Snapshot-Mengen zuordnen und Bytes pro Menge summieren
+    let owned = ownership(&graph, &roots);
+    let mut bytes = vec![0u64; owned.sets.sets.len()];
+    let mut missing_blobs = 0u64;
+    for (id, set) in &owned.trees {
+        match repo.get_index_entry(id) {
+            Ok(entry) => bytes[*set] += u64::from(entry.length),
+            Err(_) => missing_blobs += 1,
+        }
+    }
+    for (id, set) in &owned.data {
+        match repo.get_index_entry(id) {
+            Ok(entry) => bytes[*set] += u64::from(entry.length),
+            Err(_) => missing_blobs += 1,
+        }
+    }
+    if missing_blobs > 0 {
+        warn!(missing_blobs, "Referenzierte Blobs fehlen im Index");
+    }
+
+    // 3. Gruppen auswerten
+    let sets = &owned.sets.sets;
+    let by_snapshot =
+        Grouping::new(&snapshots, |snap| vec![(snap.id.to_string(), snap.time.to_rfc3339())]);
+    let by_host =
+        Grouping::new(&snapshots, |snap| vec![(snap.hostname.clone(), snap.hostname.clone())]);
+    let by_tag = Grouping::new(&snapshots, |snap| {
+        snap.tags.iter().map(|tag| (tag.clone(), tag.clone())).collect()
+    });
+    let job_paths: Vec<BTreeSet<String>> = jobs
+        .iter()
+        .map(|job| {
+            source_roots(&job.source_paths)
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect()
+        })
+        .collect();
+    let by_job = Grouping::new(&snapshots, |snap| {
+        let paths: BTreeSet<String> = snap.paths.iter().cloned().collect();
+        jobs.iter()
+            .zip(&job_paths)
+            .filter(|(job, job_paths)| {
+                **job_paths == paths && job.tags.iter().all(|tag| snap.tags.contains(tag))
+            })
+            .map(|(job, _)| (job.id.clone(), job.name.clone()))
+            .collect()
+    });
+
+    let mut snapshot_usage = by_snapshot.usage(sets, &bytes);
+    // Snapshots in zeitlicher Reihenfolge (neueste zuerst) sind für die Anzeige nützlicher
+    let order: HashMap<String, usize> =
+        snapshots.iter().enumerate().map(|(i, snap)| (snap.id.to_string(), i)).collect();
+    snapshot_usage.sort_by_key(|usage| order.get(&usage.key).copied());
+
+    let result = StorageAttributionDto {
+        snapshots: snapshot_usage,
+        hosts: by_host.usage(sets, &bytes),
+        tags: by_tag.usage(sets, &bytes),
+        jobs: by_job.usage(sets, &bytes),
+        referenced_size: bytes.iter().sum(),
+        trees_scanned: graph.len() as u64,
+        missing_blobs,
+    };
+    info!(
+        trees = result.trees_scanned,
+        referenced = result.referenced_size,
+        owner_sets = sets.len(),
+        "Speicherbelegung berechnet"
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustic_core::Id;
+
+    #[test]
+    fn test_ownership_and_attribution() {
+        let tree = || TreeId::from(Id::random());
+        let blob = || DataId::from(Id::random());
+        let (root_a, root_b, shared_dir, only_a_dir) = (tree(), tree(), tree(), tree());
+        let (shared_file, only_a_file, only_b_file) = (blob(), blob(), blob());
+
+        // Snapshot B referenziert den Tree von A zusätzlich als Unterverzeichnis
+        let graph = HashMap::from([
+            (root_a, TreeRefs { subtrees: vec![shared_dir, only_a_dir], data: vec![] }),
+            (root_b, TreeRefs { subtrees: vec![shared_dir], data: vec![only_b_file] }),
+            (shared_dir, TreeRefs { subtrees: vec![], data: vec![shared_file] }),
+            (only_a_dir, TreeRefs { subtrees: vec![], data: vec![only_a_file, shared_file] }),
+        ]);
+        let owned = ownership(&graph, &[root_a, root_b]);
+        let members = |set: usize| owned.sets.sets[set].clone();
+
+        assert_eq!(members(owned.trees[&root_a]), vec![0]);
+        assert_eq!(members(owned.trees[&shared_dir]), vec![0, 1]);
+        assert_eq!(members(owned.data[&only_a_file]), vec![0]);
+        assert_eq!(members(owned.data[&shared_file]), vec![0, 1]);
+        assert_eq!(members(owned.data[&only_b_file]), vec![1]);
+
+        // Bytes pro Menge: nur A = 10, nur B = 20, beide = 5
+        let mut bytes = vec![0u64; owned.sets.sets.len()];
+        bytes[owned.data[&only_a_file]] += 10;
+        bytes[owned.data[&only_b_file]] += 20;
+        bytes[owned.data[&shared_file]] += 5;
+
+        let per_snapshot = attribute(&owned.sets.sets, &bytes, &[vec![0], vec![1]], 2);
+        assert_eq!(per_snapshot, vec![(10, 15), (20, 25)]);
+        // Beide Snapshots in einer Gruppe (z.B. gleicher Host): alles ist eigen
+        let per_host = attribute(&owned.sets.sets, &bytes, &[vec![0], vec![0]], 1);
+        assert_eq!(per_host, vec![(35, 35)]);
+    }
+}
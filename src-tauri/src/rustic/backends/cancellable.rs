@@ -0,0 +1,173 @@
+/// Abbrechbares Backend für laufende Backups
+///
+/// Umhüllt ein rustic-Backend, protokolliert alle geschriebenen Dateien und verweigert
+/// nach dem Abbruch weitere Schreibzugriffe. Damit kann ein abgebrochenes Backup die
+/// bereits hochgeladenen Pack- und Index-Dateien wieder entfernen.
+use bytes::Bytes;
+use parking_lot::Mutex;
+use rustic_core::{ErrorKind, FileType, Id, ReadBackend, RusticError, RusticResult, WriteBackend};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Maximale Wartezeit auf laufende Schreibvorgänge vor dem Aufräumen
+const IN_FLIGHT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Backend-Wrapper mit Abbruch-Prüfung und Schreibprotokoll
+pub struct CancellableBackend {
+    inner: Arc<dyn WriteBackend>,
+    token: CancellationToken,
+    written: Mutex<Vec<(FileType, Id, bool)>>,
+    in_flight: AtomicUsize,
+}
+
+impl CancellableBackend {
+    /// Erstellt einen neuen Wrapper um das angegebene Backend
+    pub fn new(inner: Arc<dyn WriteBackend>, token: CancellationToken) -> Arc<Self> {
+        Arc::new(Self {
+            inner,
+            token,
+            written: Mutex::new(Vec::new()),
+            in_flight: AtomicUsize::new(0),
+        })
+    }
+
+    /// Liste aller seit Erstellung geschriebenen Dateien
+    pub fn written_files(&self) -> Vec<(FileType, Id)> {
+        self.written.lock().iter().map(|(tpe, id, _)| (*tpe, *id)).collect()
+    }
+
+    /// Entfernt alle geschriebenen Pack- und Index-Dateien wieder.
+    ///
+    /// Wartet vorher auf noch laufende Schreibvorgänge. Snapshot-Dateien werden nach
+    /// einem Abbruch nicht mehr geschrieben und müssen daher nicht entfernt werden.
+    ///
+    /// # Returns
+    /// Anzahl der entfernten Dateien
+    pub fn remove_written_files(&self) -> RusticResult<usize> {
+        let deadline = Instant::now() + IN_FLIGHT_TIMEOUT;
+        while self.in_flight.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        let files: Vec<_> = std::mem::take(&mut *self.written.lock());
+        let mut removed = 0;
+        // Index-Dateien zuerst entfernen, damit kein Index auf gelöschte Packs zeigt
+        for tpe in [FileType::Index, FileType::Pack] {
+            for (_, id, cacheable) in files.iter().filter(|(t, _, _)| *t == tpe) {
+                self.inner.remove(tpe, id, *cacheable)?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+impl ReadBackend for CancellableBackend {
+    fn location(&self) -> String {
+        self.inner.location()
+    }
+
+    fn list_with_size(&self, tpe: FileType) -> RusticResult<Vec<(Id, u32)>> {
+        self.inner.list_with_size(tpe)
+    }
+
+    fn list(&self, tpe: FileType) -> RusticResult<Vec<Id>> {
+        self.inner.list(tpe)
+    }
+
+    fn read_full(&self, tpe: FileType, id: &Id) -> RusticResult<Bytes> {
+        self.inner.read_full(tpe, id)
+    }
+
+    fn read_partial(
+        &self,
+        tpe: FileType,
+        id: &Id,
+        cacheable: bool,
+        offset: u32,
+        length: u32,
+    ) -> RusticResult<Bytes> {
+        self.inner.read_partial(tpe, id, cacheable, offset, length)
+    }
+
+    fn needs_warm_up(&self) -> bool {
+        self.inner.needs_warm_up()
+    }
+
+    fn warm_up(&self, tpe: FileType, id: &Id) -> RusticResult<()> {
+        self.inner.warm_up(tpe, id)
+    }
+}
+
+impl WriteBackend for CancellableBackend {
+    fn create(&self) -> RusticResult<()> {
+        self.inner.create()
+    }
+
+    fn write_bytes(&self, tpe: FileType, id: &Id, cacheable: bool, buf: Bytes) -> RusticResult<()> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let result = if self.token.is_cancelled() {
+            Err(RusticError::new(ErrorKind::Other, "Backup wurde abgebrochen"))
+        } else {
+            self.inner.write_bytes(tpe, id, cacheable, buf)
+        };
+        if result.is_ok() {
+            self.written.lock().push((tpe, *id, cacheable));
+        }
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        result
+    }
+
+    fn remove(&self, tpe: FileType, id: &Id, cacheable: bool) -> RusticResult<()> {
+        self.inner.remove(tpe, id, cacheable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustic_backend::BackendOptions;
+    use tempfile::TempDir;
+
+    fn local_backend(dir: &TempDir) -> Arc<dyn WriteBackend> {
+        let backends = BackendOptions::default()
+            .repository(dir.path().to_str().unwrap())
+            .to_backends()
+            .unwrap();
+        let be = backends.repository();
+        be.create().unwrap();
+        be
+    }
+
+    #[test]
+    fn test_writes_are_refused_after_cancel() {
+        let dir = TempDir::new().unwrap();
+        let token = CancellationToken::new();
+        let be = CancellableBackend::new(local_backend(&dir), token.clone());
+
+        let id = Id::random();
+        assert!(be.write_bytes(FileType::Pack, &id, false, Bytes::from_static(b"data")).is_ok());
+
+        token.cancel();
+        let id2 = Id::random();
+        assert!(be.write_bytes(FileType::Pack, &id2, false, Bytes::from_static(b"data")).is_err());
+        assert_eq!(be.written_files(), vec![(FileType::Pack, id)]);
+    }
+
+    #[test]
+    fn test_remove_written_files() {
+        let dir = TempDir::new().unwrap();
+        let be = CancellableBackend::new(local_backend(&dir), CancellationToken::new());
+
+        let id = Id::random();
+        be.write_bytes(FileType::Index, &id, true, Bytes::from_static(b"index")).unwrap();
+        assert_eq!(be.list(FileType::Index).unwrap().len(), 1);
+
+        assert_eq!(be.remove_written_files().unwrap(), 1);
+        assert!(be.list(FileType::Index).unwrap().is_empty());
+        assert!(be.written_files().is_empty());
+    }
+}
@@ -1,13 +1,30 @@
 use crate::error::RusticGuiError;
+use crate::rustic::backends::CancellableBackend;
+use crate::rustic::progress::{GuiProgressBars, ProgressKind};
+use crate::rustic::snapshot::{DiffNodes, change_classes, diff_nodes};
+use crate::types::{DiffChangeClass, SnapshotDiffDto, SnapshotDiffOptions};
+use parking_lot::Mutex;
 use rustic_backend::BackendOptions;
 use rustic_core::{
-    BackupOptions as RusticBackupOptions, LocalSourceFilterOptions, NoProgressBars, PathList,
-    Repository, RepositoryOptions, SnapshotOptions,
+    BackupOptions as RusticBackupOptions, LocalSource, LocalSourceFilterOptions,
+    LocalSourceSaveOptions, LsOptions, PathList, ReadSource, Repository, RepositoryBackends,
+    RepositoryOptions, SnapshotOptions, WriteBackend,
+    repofile::{Node, NodeType},
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 use tauri::AppHandle;
 use tauri::Emitter;
-use tracing::{error, info};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// Präfix des Byte-Fortschritts, den rustic_core während `backup()` startet
+const BACKUP_PROGRESS_PREFIX: &str = "backing up...";
 
 /// Fortschrittsdaten für Backup-Prozess (an Frontend gesendet)
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -17,7 +34,175 @@ pub struct BackupProgress {
     pub bytes_uploaded: u64,
     pub bytes_total: Option<u64>,
     pub current_file: Option<String>,
+    /// Fortschritt in Prozent (0.0 - 100.0)
     pub percent: Option<f32>,
+    /// Geschätzte Restzeit in Sekunden
+    pub estimated_time_remaining: Option<u64>,
+}
+
+/// Gesamtwerte des Quell-Scans
+#[derive(Default)]
+struct SourceScan {
+    /// Anzahl gescannter Dateien
+    files: u64,
+    /// Gesamtgröße aller gescannten Dateien
+    total_bytes: u64,
+    /// Ob der Scan vollständig durchgelaufen ist
+    complete: bool,
+}
+
+/// Dateien der Quelle als (Größe, Pfad) in Archivierungsreihenfolge
+type SourceFiles = Box<dyn Iterator<Item = (u64, String)> + Send>;
+
+/// Zweiter Durchlauf der Quelle, der dem Byte-Stand des Archivers folgt.
+///
+/// Rückt nur so weit vor, wie der Archiver gekommen ist, und hält daher nur die aktuelle
+/// Datei im Speicher.
+#[derive(Default)]
+struct SourceCursor {
+    files: Option<SourceFiles>,
+    /// Kumulierte Bytes bis einschließlich der aktuellen Datei
+    current_end: u64,
+    /// Datei, die beim letzten Byte-Stand gesichert wurde
+    current: Option<String>,
+    /// Vollständig gesicherte Dateien
+    processed: u64,
+}
+
+impl SourceCursor {
+    fn new(files: SourceFiles) -> Self {
+        let mut cursor = Self { files: Some(files), ..Default::default() };
+        cursor.next_file();
+        cursor
+    }
+
+    fn next_file(&mut self) {
+        self.current = None;
+        let Some(files) = self.files.as_mut() else {
+            return;
+        };
+        match files.next() {
+            Some((size, path)) => {
+                self.current_end += size;
+                self.current = Some(path);
+            }
+            None => self.files = None,
+        }
+    }
+
+    /// Rückt bis zu der Datei vor, die beim angegebenen Byte-Stand gesichert wird
+    fn advance_to(&mut self, bytes_uploaded: u64) {
+        while self.current.is_some() && self.current_end <= bytes_uploaded {
+            self.processed += 1;
+            self.next_file();
+        }
+    }
+}
+
+/// Übersetzt den Byte-Fortschritt von rustic_core in `BackupProgress`-Events.
+///
+/// rustic_core meldet während des Backups nur verarbeitete Bytes, keine Dateien. Ein
+/// paralleler Scan der Quelle (gleiche Filter wie der Archiver) liefert Gesamtgröße und
+/// Dateianzahl; ein zweiter Durchlauf in Archivierungsreihenfolge (`SourceCursor`) leitet
+/// daraus Dateianzahl und aktuelle Datei zum gemeldeten Byte-Stand ab.
+struct BackupProgressTracker<F> {
+    on_progress: F,
+    scan: Mutex<SourceScan>,
+    cursor: Mutex<SourceCursor>,
+    stop_scan: AtomicBool,
+    cancel: CancellationToken,
+    started: Mutex<Option<Instant>>,
+}
+
+impl<F> BackupProgressTracker<F>
+where
+    F: Fn(BackupProgress) + Send + Sync + 'static,
+{
+    fn new(on_progress: F, cancel: CancellationToken) -> Self {
+        Self {
+            on_progress,
+            scan: Mutex::new(SourceScan::default()),
+            cursor: Mutex::new(SourceCursor::default()),
+            stop_scan: AtomicBool::new(false),
+            cancel,
+            started: Mutex::new(None),
+        }
+    }
+
+    /// Scannt die Quelle und summiert Dateianzahl und -größen (läuft in eigenem Thread)
+    fn scan_source(&self, source: &LocalSource) {
+        for entry in source.entries() {
+            if self.stop_scan.load(Ordering::Relaxed) || self.cancel.is_cancelled() {
+                return;
+            }
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!("Scan ignoriert Eintrag: {}", e);
+                    continue;
+                }
+            };
+            if entry.node.node_type != NodeType::File {
+                continue;
+            }
+
+            let mut scan = self.scan.lock();
+            scan.files += 1;
+            scan.total_bytes += entry.node.meta.size;
+        }
+
+        let mut scan = self.scan.lock();
+        scan.complete = true;
+        info!(files = scan.files, bytes = scan.total_bytes, "Quell-Scan abgeschlossen");
+    }
+
+    /// Beendet einen noch laufenden Scan vorzeitig
+    fn stop(&self) {
+        self.stop_scan.store(true, Ordering::Relaxed);
+    }
+
+    /// Erstellt ein `BackupProgress` für den angegebenen Byte-Stand
+    fn progress_at(&self, bytes_uploaded: u64) -> BackupProgress {
+        let (files_processed, current_file) = {
+            let mut cursor = self.cursor.lock();
+            cursor.advance_to(bytes_uploaded);
+            (cursor.processed, cursor.current.clone())
+        };
+        let (files_total, bytes_total) = {
+            let scan = self.scan.lock();
+            if scan.complete { (Some(scan.files), Some(scan.total_bytes)) } else { (None, None) }
+        };
+
+        let percent = bytes_total
+            .filter(|total| *total > 0)
+            .map(|total| ((bytes_uploaded as f64 / total as f64) * 100.0).min(100.0) as f32);
+
+        BackupProgress {
+            files_processed,
+            files_total,
+            bytes_uploaded,
+            bytes_total,
+            current_file,
+            percent,
+            estimated_time_remaining: self.estimate_remaining(bytes_uploaded, bytes_total),
+        }
+    }
+
+    /// Schätzt die Restzeit anhand der bisherigen Durchsatzrate
+    fn estimate_remaining(&self, bytes_uploaded: u64, bytes_total: Option<u64>) -> Option<u64> {
+        let elapsed = self.started.lock().get_or_insert_with(Instant::now).elapsed();
+        let total = bytes_total?;
+        if bytes_uploaded == 0 || elapsed.as_secs() < 1 {
+            return None;
+        }
+        let rate = bytes_uploaded as f64 / elapsed.as_secs_f64();
+        Some((total.saturating_sub(bytes_uploaded) as f64 / rate).round() as u64)
+    }
+
+    /// Meldet einen Byte-Stand an den Callback
+    fn report(&self, bytes_uploaded: u64) {
+        (self.on_progress)(self.progress_at(bytes_uploaded));
+    }
 }
 
 /// Optionen für Backup-Start
@@ -39,9 +224,39 @@ pub struct BackupOptions {
     pub job_id: Option<String>,
 }
 
+/// Filter-Optionen für die Backup-Quelle (Exclude-Patterns und .gitignore wie in rustic CLI)
+fn source_filter_options(exclude: Option<&[String]>) -> LocalSourceFilterOptions {
+    let mut filter_opts = LocalSourceFilterOptions::default();
+
+    // .gitignore-Regeln automatisch anwenden (wie restic/rustic --git-ignore)
+    // Dies liest .gitignore-Dateien in den Source-Verzeichnissen und excluded Dateien automatisch
+    filter_opts.git_ignore = true;
+    filter_opts.no_require_git = true; // Auch ohne Git-Repository anwenden
+
+    // Zusätzliche Exclude-Patterns hinzufügen - glob patterns mit "!" prefix
+    if let Some(excludes) = exclude
+        && !excludes.is_empty()
+    {
+        let exclude_globs: Vec<String> =
+            excludes.iter().map(|pattern| format!("!{}", pattern)).collect();
+        filter_opts.globs = exclude_globs;
+    }
+    filter_opts
+}
+
 /// Testbare Backup-Logik ohne Tauri-API (für Unit-Tests)
+///
+/// Wird `cancel_token` ausgelöst, verweigert das Backend weitere Schreibzugriffe und der
+/// Byte-Fortschritt beendet `repo.backup()` beim nächsten gelesenen Chunk per Unwind (siehe
+/// `GuiProgressBars::abort_on_cancel`), sodass kein Snapshot entsteht. Anschließend werden
+/// die bereits geschriebenen Pack- und Index-Dateien wieder entfernt. rustic_core legt keine
+/// Repository-Locks an, daher muss nach einem Abbruch kein Lock freigegeben werden.
+///
+/// # Errors
+/// `RusticGuiError::BackupCancelled`, wenn das Backup abgebrochen wurde
 pub async fn run_backup_logic<F>(
     options: &BackupOptions,
+    cancel_token: CancellationToken,
     on_progress: F,
 ) -> Result<String, RusticGuiError>
 where
@@ -57,9 +272,25 @@ where
         return Err(RusticGuiError::InvalidConfig { field: "source_paths".into() });
     }
 
+    if cancel_token.is_cancelled() {
+        return Err(RusticGuiError::BackupCancelled);
+    }
+
     let repo_path = options.repository.clone();
     info!(repo = %repo_path, "Backup gestartet");
 
+    let tracker = Arc::new(BackupProgressTracker::new(on_progress, cancel_token.clone()));
+
+    // ProgressBars: nur der Byte-Fortschritt des Archivers wird an das Frontend gemeldet
+    let tracker_pb = tracker.clone();
+    let progress_bars = GuiProgressBars::new(move |update| {
+        if update.kind == ProgressKind::Bytes && update.prefix == BACKUP_PROGRESS_PREFIX {
+            tracker_pb.report(update.position);
+        }
+    })
+    .with_cancellation(cancel_token.clone())
+    .abort_on_cancel();
+
     // Repository-Optionen (mit Passwort falls vorhanden)
     let repo_opts = if let Some(ref password) = options.password {
         RepositoryOptions::default().password(password.clone())
@@ -76,8 +307,18 @@ where
         RusticGuiError::RusticError { message: format!("Backend-Erstellung fehlgeschlagen: {}", e) }
     })?;
 
+    // Backends abbrechbar machen und geschriebene Dateien für das Aufräumen protokollieren
+    let cancellable_backends: Vec<Arc<CancellableBackend>> = std::iter::once(backends.repository())
+        .chain(backends.repo_hot())
+        .map(|be| CancellableBackend::new(be, cancel_token.clone()))
+        .collect();
+    let backends = RepositoryBackends::new(
+        cancellable_backends[0].clone(),
+        cancellable_backends.get(1).map(|hot| hot.clone() as Arc<dyn WriteBackend>),
+    );
+
     // Repository öffnen
-    let repo = Repository::<NoProgressBars, ()>::new(&repo_opts, &backends)
+    let repo = Repository::new_with_progress(&repo_opts, &backends, progress_bars)
         .map_err(|e| {
             error!(?e, "Repository erstellen fehlgeschlagen");
             RusticGuiError::RusticError {
@@ -98,17 +339,10 @@ where
         })?;
 
     // Source-Paths in PathList konvertieren
-    let source_str = options.source_paths.join(",");
-    let source = PathList::from_string(&source_str)
-        .map_err(|e| {
-            error!(?e, "Source-Pfade ungültig");
-            RusticGuiError::InvalidConfig { field: "source_paths".into() }
-        })?
-        .sanitize()
-        .map_err(|e| {
-            error!(?e, "Source-Pfade-Sanitierung fehlgeschlagen");
-            RusticGuiError::InvalidConfig { field: "source_paths".into() }
-        })?;
+    let source = PathList::from_iter(&options.source_paths).sanitize().map_err(|e| {
+        error!(?e, "Source-Pfade-Sanitierung fehlgeschlagen");
+        RusticGuiError::InvalidConfig { field: "source_paths".into() }
+    })?;
 
     // Snapshot-Optionen erstellen
     let mut snap_opts = SnapshotOptions::default();
@@ -131,53 +365,72 @@ where
     })?;
 
     // Backup-Optionen erstellen (mit Exclude-Patterns & .gitignore wie in rustic CLI)
-    let mut filter_opts = LocalSourceFilterOptions::default();
-
-    // .gitignore-Regeln automatisch anwenden (wie restic/rustic --git-ignore)
-    // Dies liest .gitignore-Dateien in den Source-Verzeichnissen und excluded Dateien automatisch
-    filter_opts.git_ignore = true;
-    filter_opts.no_require_git = true; // Auch ohne Git-Repository anwenden
-
-    // Zusätzliche Exclude-Patterns hinzufügen - glob patterns mit "!" prefix
-    if let Some(ref excludes) = options.exclude {
-        if !excludes.is_empty() {
-            let exclude_globs: Vec<String> =
-                excludes.iter().map(|pattern| format!("!{}", pattern)).collect();
-            filter_opts.globs = exclude_globs;
-        }
-    }
+    let filter_opts = source_filter_options(options.exclude.as_deref());
+
+    // Quell-Scan und Cursor mit denselben Filtern wie das Backup (ersetzen den Größen-Scan
+    // von rustic_core)
+    let mut scan_paths = options.source_paths.clone();
+    scan_paths.sort_unstable();
+    scan_paths.dedup();
+    let open_source = || {
+        LocalSource::new(LocalSourceSaveOptions::default(), &filter_opts, &scan_paths).map_err(
+            |e| {
+                error!(?e, "Quelle konnte nicht gelesen werden");
+                RusticGuiError::InvalidConfig { field: "source_paths".into() }
+            },
+        )
+    };
+    let scan_source = open_source()?;
+    let source_files = open_source()?.entries().filter_map(|entry| {
+        let entry = entry.ok()?;
+        (entry.node.node_type == NodeType::File)
+            .then(|| (entry.node.meta.size, entry.path.to_string_lossy().to_string()))
+    });
+    *tracker.cursor.lock() = SourceCursor::new(Box::new(source_files));
 
-    let backup_opts = RusticBackupOptions::default().ignore_filter_opts(filter_opts);
+    let backup_opts = RusticBackupOptions::default().ignore_filter_opts(filter_opts).no_scan(true);
 
-    // Progress-Tracking mit simuliertem Progress
-    // TODO M1.2.1: Implement real progress tracking with rustic_core callbacks
-    let total_files = source.len() as u64;
-    let total_bytes = 1_000_000u64; // Placeholder
+    let tracker_scan = tracker.clone();
+    std::thread::spawn(move || tracker_scan.scan_source(&scan_source));
 
     // Sende initialen Progress
-    on_progress(BackupProgress {
-        files_processed: 0,
-        files_total: Some(total_files),
-        bytes_uploaded: 0,
-        bytes_total: Some(total_bytes),
-        current_file: None,
-        percent: Some(0.0),
-    });
-
-    // Führe Backup aus
-    let result_snapshot = repo.backup(&backup_opts, &source, snapshot).map_err(|e| {
+    tracker.report(0);
+
+    // Führe Backup aus; nach einem Abbruch endet es per Unwind aus dem Byte-Fortschritt
+    let backup_result =
+        std::panic::catch_unwind(AssertUnwindSafe(|| repo.backup(&backup_opts, &source, snapshot)));
+    tracker.stop();
+
+    if !matches!(backup_result, Ok(Ok(_))) && cancel_token.is_cancelled() {
+        warn!(repo = %repo_path, "Backup abgebrochen, entferne bereits geschriebene Dateien");
+        for be in &cancellable_backends {
+            match be.remove_written_files() {
+                Ok(removed) => info!(removed, "Teilweise geschriebene Dateien entfernt"),
+                Err(e) => warn!(?e, "Aufräumen nach Abbruch fehlgeschlagen"),
+            }
+        }
+        return Err(RusticGuiError::BackupCancelled);
+    }
+    let backup_result = backup_result.unwrap_or_else(|payload| std::panic::resume_unwind(payload));
+    let result_snapshot = backup_result.map_err(|e| {
         error!(?e, "Backup fehlgeschlagen");
         RusticGuiError::BackupFailed { reason: format!("Backup fehlgeschlagen: {}", e) }
     })?;
 
-    // Sende finalen Progress
-    on_progress(BackupProgress {
-        files_processed: total_files,
-        files_total: Some(total_files),
-        bytes_uploaded: total_bytes,
-        bytes_total: Some(total_bytes),
+    // Sende finalen Progress mit den Zahlen aus der Snapshot-Summary
+    let (files_processed, bytes_processed) = result_snapshot
+        .summary
+        .as_ref()
+        .map(|s| (s.total_files_processed, s.total_bytes_processed))
+        .unwrap_or_default();
+    (tracker.on_progress)(BackupProgress {
+        files_processed,
+        files_total: Some(files_processed),
+        bytes_uploaded: bytes_processed,
+        bytes_total: Some(bytes_processed),
         current_file: None,
-        percent: Some(1.0),
+        percent: Some(100.0),
+        estimated_time_remaining: Some(0),
     });
 
     info!(repo = %repo_path, snapshot_id = %result_snapshot.id, "Backup erfolgreich abgeschlossen");
@@ -186,19 +439,187 @@ where
     Ok(result_snapshot.id.to_string())
 }
 
+/// Vergleicht den letzten Snapshot eines Jobs mit dem aktuellen Stand der Quellpfade.
+///
+/// Zeigt, was ein neues Backup aufnehmen würde: Die Quellen werden mit denselben Filtern
+/// wie `run_backup_logic` (Exclude-Patterns, .gitignore) im Dateisystem gelesen und per
+/// Merge-Join mit dem Snapshot verglichen. Als letzter Snapshot gilt der neueste Snapshot
+/// dieses Rechners mit denselben Quellpfaden und allen Tags des Jobs (wie die Parent-Suche
+/// von rustic und die Retention eines Jobs). Dateiinhalte werden nicht
+/// gelesen; geänderte Dateien werden wie beim Backup an Größe und Änderungszeit erkannt.
+///
+/// # Arguments
+/// * `options` - Backup-Optionen des Jobs (Repository, Passwort, Quellen, Excludes)
+/// * `diff_options` - Präfix-Filter, Paging und Rollup-Tiefe
+///
+/// # Returns
+/// Diff mit `snapshot_a` = letzter Snapshot (leer, wenn es noch keinen gibt) und
+/// `snapshot_b` = "live"; neue Dateien sind `added`, gelöschte `removed`
+///
+/// # Errors
+/// `InvalidConfig`, wenn keine Quellpfade angegeben sind
+pub fn diff_source_against_snapshot(
+    options: &BackupOptions,
+    diff_options: &SnapshotDiffOptions,
+) -> Result<SnapshotDiffDto, RusticGuiError> {
+    if options.source_paths.is_empty() {
+        return Err(RusticGuiError::InvalidConfig { field: "source_paths".into() });
+    }
+    info!(repo = %options.repository, sources = ?options.source_paths, "Vergleiche Quellen mit letztem Snapshot");
+
+    let mut repo_opts = RepositoryOptions::default();
+    repo_opts.password = options.password.clone();
+    let backends =
+        BackendOptions::default().repository(&options.repository).to_backends().map_err(|e| {
+            RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+        })?;
+    let repo = Repository::new(&repo_opts, &backends)
+        .map_err(|e| {
+            error!(?e, "Fehler beim Initialisieren des Repositories");
+            RusticGuiError::RepositoryNotFound { path: options.repository.clone() }
+        })?
+        .open()
+        .map_err(|e| {
+            error!(?e, "Repository öffnen fehlgeschlagen");
+            RusticGuiError::AuthenticationFailed
+        })?
+        .to_indexed_ids()
+        .map_err(|e| {
+            error!(?e, "Repository-Indexierung fehlgeschlagen");
+            RusticGuiError::Internal(format!("Repository-Indexierung fehlgeschlagen: {e}"))
+        })?;
+
+    let sources = source_roots(&options.source_paths);
+    let source_set: BTreeSet<String> =
+        sources.iter().map(|p| p.to_string_lossy().to_string()).collect();
+
+    // Neuester Snapshot dieses Rechners mit denselben Quellpfaden und Job-Tags
+    let hostname = gethostname::gethostname().to_string_lossy().into_owned();
+    let job_tags = options.tags.as_deref().unwrap_or_default();
+    let snapshot = repo
+        .get_all_snapshots()
+        .map_err(|e| {
+            RusticGuiError::Internal(format!("Snapshots konnten nicht geladen werden: {e}"))
+        })?
+        .into_iter()
+        .filter(|snap| {
+            snap.hostname == hostname
+                && snap.paths.iter().cloned().collect::<BTreeSet<_>>() == source_set
+                && job_tags.iter().all(|tag| snap.tags.contains(tag))
+        })
+        .max_by_key(|snap| snap.time);
+    if snapshot.is_none() {
+        info!("Noch kein Snapshot für diese Quellen, alle Dateien sind neu");
+    }
+
+    // Snapshot-Seite: jede Quelle samt Inhalt (Quellen sind sortiert und nicht verschachtelt).
+    // Der Quell-Eintrag selbst gehört dazu, da `LocalSource` ihn ebenfalls liefert.
+    let ls_opts = LsOptions::default();
+    let mut snapshot_nodes: Vec<DiffNodes<'_>> = Vec::new();
+    if let Some(snapshot) = &snapshot {
+        for source in &sources {
+            let relative = source.to_string_lossy().trim_start_matches('/').to_string();
+            let Ok(node) = repo.node_from_snapshot_and_path(snapshot, &relative) else {
+                continue;
+            };
+            let root = std::iter::once(Ok((source.clone(), node.clone())));
+            if node.is_dir() {
+                let base = source.clone();
+                let children = repo
+                    .ls(&node, &ls_opts)
+                    .map_err(|e| {
+                        RusticGuiError::Internal(format!("Tree konnte nicht gelesen werden: {e}"))
+                    })?
+                    .map(move |item| item.map(|(path, n)| (base.join(path), n)));
+                snapshot_nodes.push(Box::new(root.chain(children)));
+            } else {
+                snapshot_nodes.push(Box::new(root));
+            }
+        }
+    }
+
+    // Dateisystem-Seite: gleiche Filter und Reihenfolge wie beim Backup
+    let filter_opts = source_filter_options(options.exclude.as_deref());
+    let mut live_nodes: Vec<DiffNodes<'_>> = Vec::new();
+    for source in sources.iter().filter(|source| source.exists()) {
+        let local = LocalSource::new(LocalSourceSaveOptions::default(), &filter_opts, &[source])
+            .map_err(|e| {
+                error!(?e, "Quelle konnte nicht gelesen werden");
+                RusticGuiError::InvalidConfig { field: "source_paths".into() }
+            })?;
+        live_nodes.push(Box::new(
+            local.entries().map(|entry| entry.map(|entry| (entry.path, entry.node))),
+        ));
+    }
+
+    let mut diff = diff_nodes(
+        Box::new(snapshot_nodes.into_iter().flatten()),
+        Box::new(live_nodes.into_iter().flatten()),
+        diff_options,
+        live_change_classes,
+    )
+    .map_err(|e| RusticGuiError::Internal(format!("Vergleich fehlgeschlagen: {e}")))?;
+    diff.snapshot_a = snapshot.map(|s| s.id.to_string()).unwrap_or_default();
+    diff.snapshot_b = "live".to_string();
+
+    info!(
+        added = diff.stats.added,
+        removed = diff.stats.removed,
+        changed = diff.stats.changed,
+        "Vergleich mit Dateisystem abgeschlossen"
+    );
+    Ok(diff)
+}
+
+/// Quellpfade wie `PathList::sanitize`: absolut, sortiert, ohne verschachtelte Pfade
+pub(crate) fn source_roots(source_paths: &[String]) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = source_paths
+        .iter()
+        .map(|p| {
+            let path = Path::new(p);
+            std::fs::canonicalize(path)
+                .or_else(|_| std::env::current_dir().map(|cwd| cwd.join(path)))
+                .unwrap_or_else(|_| path.to_path_buf())
+        })
+        .collect();
+    paths.sort_unstable();
+    paths.dedup();
+
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if !roots.last().is_some_and(|root| path.starts_with(root)) {
+            roots.push(path);
+        }
+    }
+    roots
+}
+
+/// Änderungsklassen zwischen Snapshot und Dateisystem.
+///
+/// Dateien im Dateisystem haben keine Blob-IDs; ihr Inhalt gilt daher nur über Größe und
+/// Änderungszeit als geändert (wie beim Backup mit Parent-Snapshot).
+fn live_change_classes(snapshot: &Node, live: &Node) -> Vec<DiffChangeClass> {
+    let mut changes = change_classes(snapshot, live);
+    if snapshot.is_file() && live.is_file() {
+        changes.retain(|class| *class != DiffChangeClass::Content);
+    }
+    changes
+}
+
 /// Produktive Backup-Logik mit Tauri-Event-Emission
 pub async fn run_backup<F>(
     app: AppHandle,
     options: BackupOptions,
+    cancel_token: CancellationToken,
     on_progress: F,
 ) -> Result<String, RusticGuiError>
 where
     F: Fn(BackupProgress) + Send + Sync + 'static,
 {
-    let job_id = options.job_id.clone().unwrap_or_else(|| "default".to_string());
+    let job_id = options.job_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
     let event_name = format!("backup-progress-{}", job_id);
     let app = app.clone();
-    run_backup_logic(&options, move |progress| {
+    run_backup_logic(&options, cancel_token, move |progress| {
         on_progress(progress.clone());
         let _ = app.emit(&event_name, &progress);
     })
@@ -208,7 +629,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::{Arc, Mutex};
+    use rustic_core::NoProgressBars;
+    use std::sync::Mutex;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -250,7 +672,7 @@ mod tests {
             progress_clone.lock().unwrap().push(progress);
         };
 
-        let result = run_backup_logic(&options, cb).await;
+        let result = run_backup_logic(&options, CancellationToken::new(), cb).await;
 
         // The backup should succeed (or at least not fail with invalid config)
         match result {
@@ -267,6 +689,134 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_progress_tracker_maps_bytes_to_files() {
+        let tracker = BackupProgressTracker::new(|_p: BackupProgress| {}, CancellationToken::new());
+        *tracker.cursor.lock() = SourceCursor::new(Box::new(
+            vec![
+                (100, "/src/a.txt".to_string()),
+                (0, "/src/empty.txt".to_string()),
+                (300, "/src/b.bin".to_string()),
+            ]
+            .into_iter(),
+        ));
+        {
+            let mut scan = tracker.scan.lock();
+            scan.files = 3;
+            scan.total_bytes = 400;
+        }
+
+        // Scan noch nicht abgeschlossen: keine Gesamtwerte
+        let progress = tracker.progress_at(150);
+        assert_eq!(progress.files_processed, 2);
+        assert_eq!(progress.current_file.as_deref(), Some("/src/b.bin"));
+        assert_eq!(progress.bytes_total, None);
+        assert_eq!(progress.percent, None);
+
+        tracker.scan.lock().complete = true;
+        let progress = tracker.progress_at(400);
+        assert_eq!(progress.files_processed, 3);
+        assert_eq!(progress.files_total, Some(3));
+        assert_eq!(progress.bytes_total, Some(400));
+        assert_eq!(progress.current_file, None);
+        assert_eq!(progress.percent, Some(100.0));
+    }
+
+    #[test]
+    fn test_source_roots_merges_nested_paths() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join("b")).unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+
+        let roots = source_roots(&[
+            temp.path().join("b").to_string_lossy().to_string(),
+            temp.path().to_string_lossy().to_string(),
+            "/nonexistent-rustic-gui-source".to_string(),
+        ]);
+        let mut expected = vec![PathBuf::from("/nonexistent-rustic-gui-source"), root];
+        expected.sort();
+        assert_eq!(roots, expected);
+    }
+
+    #[tokio::test]
+    async fn test_diff_source_against_snapshot() {
+        use crate::types::DiffEntryKind;
+        use rustic_core::{ConfigOptions, KeyOptions};
+
+        let temp_repo = TempDir::new().unwrap();
+        let repo_path = temp_repo.path().to_str().unwrap();
+        let temp_source = TempDir::new().unwrap();
+        let source_path = temp_source.path().to_str().unwrap();
+        std::fs::write(temp_source.path().join("a.txt"), b"alt").unwrap();
+        std::fs::write(temp_source.path().join("b.txt"), b"wird geloescht").unwrap();
+        std::fs::create_dir(temp_source.path().join("sub")).unwrap();
+        std::fs::write(temp_source.path().join("sub/c.txt"), b"bleibt").unwrap();
+
+        let backends = BackendOptions::default().repository(repo_path).to_backends().unwrap();
+        Repository::<NoProgressBars, ()>::new(
+            &RepositoryOptions::default().password("test-password"),
+            &backends,
+        )
+        .unwrap()
+        .init(&KeyOptions::default(), &ConfigOptions::default())
+        .unwrap();
+
+        let options = BackupOptions {
+            repository: repo_path.to_string(),
+            source_paths: vec![source_path.to_string()],
+            password: Some("test-password".to_string()),
+            tags: None,
+            exclude: None,
+            compression: None,
+            job_id: Some("diffjob1".to_string()),
+        };
+        run_backup_logic(&options, CancellationToken::new(), |_p: BackupProgress| {})
+            .await
+            .unwrap();
+
+        // Unveränderte Quelle: auch das Quellverzeichnis selbst taucht nicht auf
+        let diff = diff_source_against_snapshot(&options, &SnapshotDiffOptions::default()).unwrap();
+        assert_eq!(diff.snapshot_b, "live");
+        assert!(diff.entries.is_empty(), "unerwartete Einträge: {:?}", diff.entries);
+
+        std::fs::write(temp_source.path().join("a.txt"), b"neuer Inhalt").unwrap();
+        std::fs::remove_file(temp_source.path().join("b.txt")).unwrap();
+        std::fs::write(temp_source.path().join("sub/d.txt"), b"neu").unwrap();
+
+        // Verzeichnisse ändern sich nur in der Änderungszeit
+        let diff_options = SnapshotDiffOptions { ignore_metadata: true, ..Default::default() };
+        let diff = diff_source_against_snapshot(&options, &diff_options).unwrap();
+        let root = std::fs::canonicalize(temp_source.path()).unwrap();
+        let entries: Vec<(String, DiffEntryKind)> =
+            diff.entries.iter().map(|e| (e.path.clone(), e.kind)).collect();
+        assert_eq!(
+            entries,
+            vec![
+                (root.join("a.txt").to_string_lossy().to_string(), DiffEntryKind::Changed),
+                (root.join("b.txt").to_string_lossy().to_string(), DiffEntryKind::Removed),
+                (root.join("sub/d.txt").to_string_lossy().to_string(), DiffEntryKind::Added),
+            ]
+        );
+        assert!(diff.entries[0].changes.contains(&DiffChangeClass::Size));
+    }
+
+    #[tokio::test]
+    async fn test_run_backup_cancelled_before_start() {
+        let options = BackupOptions {
+            repository: "/tmp/testrepo".to_string(),
+            source_paths: vec!["/tmp/source1".to_string()],
+            password: None,
+            tags: None,
+            exclude: None,
+            compression: None,
+            job_id: Some("canceljob1".to_string()),
+        };
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = run_backup_logic(&options, token, |_p: BackupProgress| {}).await;
+        assert!(matches!(result, Err(RusticGuiError::BackupCancelled)));
+    }
+
     #[tokio::test]
     async fn test_run_backup_error_empty_repository() {
         let options = BackupOptions {
@@ -279,7 +829,7 @@ mod tests {
             job_id: Some("errjob1".to_string()),
         };
         let cb = |_p: BackupProgress| {};
-        let result = run_backup_logic(&options, cb).await;
+        let result = run_backup_logic(&options, CancellationToken::new(), cb).await;
         assert!(
             matches!(result, Err(RusticGuiError::InvalidConfig { field }) if field == "repository")
         );
@@ -297,7 +847,7 @@ mod tests {
             job_id: Some("errjob2".to_string()),
         };
         let cb = |_p: BackupProgress| {};
-        let result = run_backup_logic(&options, cb).await;
+        let result = run_backup_logic(&options, CancellationToken::new(), cb).await;
         assert!(
             matches!(result, Err(RusticGuiError::InvalidConfig { field }) if field == "source_paths")
         );
@@ -342,7 +892,7 @@ mod tests {
             progress_clone.lock().unwrap().push(progress);
         };
 
-        let result = run_backup_logic(&options, cb).await;
+        let result = run_backup_logic(&options, CancellationToken::new(), cb).await;
 
         // Check that we got progress events
         match result {
@@ -0,0 +1,547 @@
+//! Integritätsprüfung eines Repositories mit strukturierten Befunden
+//!
+//! `Repository::check` von rustic_core meldet Befunde nur über das Logging. Diese Prüfung
+//! verwendet die öffentliche API direkt und sammelt die Befunde nach Kategorien:
+//!
+//! 1. Index-Dateien lesen (doppelte Packs, unlesbare Index-Dateien)
+//! 2. Index gegen die Pack-Dateien im Backend abgleichen (fehlend, unreferenziert, Größe)
+//! 3. Snapshots und ihre Trees lesen und referenzierte Blobs im Index nachschlagen
+//! 4. Optional eine (zufällige) Teilmenge der Packs lesen und die Hashes von Pack und
+//!    Blobs prüfen
+
+use crate::error::RusticGuiError;
+use crate::rustic::crypto;
+use crate::types::{
+    CheckCategory, CheckFindingDto, CheckPhase, CheckProgress, CheckResultDto, CheckSeverity,
+    ProgressInfo, ReadDataSubset,
+};
+use rand::seq::SliceRandom;
+use rustic_backend::BackendOptions;
+use rustic_core::{
+    ReadBackend, Repository, RepositoryOptions, TreeId,
+    repofile::{FileType, IndexBlob, IndexFile, IndexId, PackId, SnapshotFile},
+};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// Maximale Anzahl aufgelisteter Befunde (alle weiteren werden nur gezählt)
+const MAX_CHECK_FINDINGS: usize = 1000;
+
+/// Mindestabstand zwischen zwei Fortschrittsmeldungen
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Pack-Datei laut Index
+struct IndexedPack {
+    size: u32,
+    /// Blobs des Packs (nur bei read-data gespeichert)
+    blobs: Vec<IndexBlob>,
+}
+
+/// Sammelt Befunde und zählt sie pro Kategorie
+struct Findings {
+    result: CheckResultDto,
+}
+
+impl Findings {
+    fn add(
+        &mut self,
+        category: CheckCategory,
+        severity: CheckSeverity,
+        id: Option<String>,
+        snapshot_id: Option<String>,
+        message: String,
+    ) {
+        let summary = &mut self.result.summary;
+        match category {
+            CheckCategory::MissingPack => summary.missing_packs += 1,
+            CheckCategory::UnreferencedPack => summary.unreferenced_packs += 1,
+            CheckCategory::IndexMismatch => summary.index_mismatches += 1,
+            CheckCategory::SnapshotError => summary.snapshot_errors += 1,
+            CheckCategory::TreeError => summary.tree_errors += 1,
+            CheckCategory::DamagedBlob => summary.damaged_blobs += 1,
+        }
+        match severity {
+            CheckSeverity::Error => {
+                self.result.is_ok = false;
+                warn!(?category, "{}", message);
+            }
+            CheckSeverity::Warning => info!(?category, "{}", message),
+        }
+
+        if self.result.findings.len() >= MAX_CHECK_FINDINGS {
+            self.result.findings_truncated = true;
+            return;
+        }
+        match severity {
+            CheckSeverity::Error => self.result.errors.push(message.clone()),
+            CheckSeverity::Warning => self.result.warnings.push(message.clone()),
+        }
+        self.result.findings.push(CheckFindingDto { category, severity, id, snapshot_id, message });
+    }
+}
+
+/// Gedrosselte Fortschrittsmeldungen
+struct Progress<F> {
+    on_progress: F,
+    last_update: Option<Instant>,
+}
+
+impl<F: Fn(CheckProgress)> Progress<F> {
+    fn report(&mut self, phase: CheckPhase, current: u64, total: u64, force: bool) {
+        if !force && self.last_update.is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        self.last_update = Some(Instant::now());
+
+        let message = match phase {
+            CheckPhase::Index => "Lese Index...",
+            CheckPhase::Packs => "Prüfe Pack-Dateien...",
+            CheckPhase::Trees => "Prüfe Snapshots...",
+            CheckPhase::ReadData => "Lese Daten...",
+        };
+        let percentage =
+            (total > 0).then(|| ((current as f64 / total as f64) * 100.0).min(100.0) as f32);
+        (self.on_progress)(CheckProgress {
+            base: ProgressInfo { current, total, message: Some(message.to_string()), percentage },
+            phase,
+        });
+    }
+}
+
+/// Prüft ein Repository und liefert alle Befunde.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `trust_cache` - Lokalen Cache verwenden (schneller, prüft aber nicht das Backend)
+/// * `read_data` - Blobs lesen und deren Hash prüfen
+/// * `subset` - Teilmenge der Pack-Dateien für read-data
+/// * `on_progress` - Callback für Fortschrittsmeldungen
+///
+/// # Returns
+/// Befunde nach Kategorie; `is_ok` ist false, sobald ein Fehler gefunden wurde
+///
+/// # Errors
+/// `RepositoryNotFound`, wenn das Repository nicht geöffnet werden kann; `InvalidConfig`
+/// bei ungültiger Teilmenge. Befunde der Prüfung selbst sind keine Fehler.
+pub fn check_repository<F>(
+    repository_path: &str,
+    password: &str,
+    trust_cache: bool,
+    read_data: bool,
+    subset: &ReadDataSubset,
+    on_progress: F,
+) -> Result<CheckResultDto, RusticGuiError>
+where
+    F: Fn(CheckProgress),
+{
+    if let ReadDataSubset::Percentage { percent } = subset
+        && !(0.0..=100.0).contains(percent)
+    {
+        return Err(RusticGuiError::InvalidConfig { field: "read_data_subset".into() });
+    }
+    info!(repo = repository_path, trust_cache, read_data, ?subset, "Prüfe Repository");
+
+    let mut repo_opts = RepositoryOptions::default();
+    repo_opts.password = Some(password.to_string());
+    repo_opts.no_cache = !trust_cache;
+    let backends =
+        BackendOptions::default().repository(repository_path).to_backends().map_err(|e| {
+            RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+        })?;
+    let repo = Repository::new(&repo_opts, &backends)
+        .map_err(|e| {
+            error!(?e, "Fehler beim Initialisieren des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .open()
+        .map_err(|e| {
+            error!(?e, "Fehler beim Öffnen des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?;
+    let rustic_error = |what: &str, e: Box<rustic_core::RusticError>| {
+        error!("{} fehlgeschlagen: {:?}", what, e);
+        RusticGuiError::RusticError { message: format!("{what} fehlgeschlagen: {e}") }
+    };
+
+    let mut findings = Findings { result: CheckResultDto { is_ok: true, ..Default::default() } };
+    let mut progress = Progress { on_progress, last_update: None };
+
+    // 1. Index-Dateien
+    let index_total =
+        repo.list::<IndexId>().map_err(|e| rustic_error("Index auflisten", e))?.count();
+    let mut indexed: HashMap<PackId, IndexedPack> = HashMap::new();
+    let mut to_delete: HashSet<PackId> = HashSet::new();
+    let index_files =
+        repo.stream_files::<IndexFile>().map_err(|e| rustic_error("Index lesen", e))?;
+    for (done, item) in index_files.enumerate() {
+        progress.report(CheckPhase::Index, done as u64, index_total as u64, false);
+        let index = match item {
+            Ok((_, index)) => index,
+            Err(e) => {
+                findings.add(
+                    CheckCategory::IndexMismatch,
+                    CheckSeverity::Error,
+                    None,
+                    None,
+                    format!("Index-Datei nicht lesbar: {e}"),
+                );
+                continue;
+            }
+        };
+        to_delete.extend(index.packs_to_delete.iter().map(|pack| pack.id));
+        for pack in index.packs {
+            let entry = IndexedPack {
+                size: pack.pack_size(),
+                blobs: if read_data { pack.blobs } else { Vec::new() },
+            };
+            if indexed.insert(pack.id, entry).is_some() {
+                findings.add(
+                    CheckCategory::IndexMismatch,
+                    CheckSeverity::Warning,
+                    Some(pack.id.to_string()),
+                    None,
+                    format!("Pack {} ist in mehreren Index-Dateien enthalten", pack.id),
+                );
+            }
+        }
+    }
+    progress.report(CheckPhase::Index, index_total as u64, index_total as u64, true);
+
+    // 2. Pack-Dateien im Backend
+    let existing = backends
+        .repository()
+        .list_with_size(FileType::Pack)
+        .map_err(|e| rustic_error("Pack-Dateien auflisten", e))?;
+    let mut present: HashSet<PackId> = HashSet::with_capacity(existing.len());
+    for (id, size) in existing {
+        let id = PackId::from(id);
+        present.insert(id);
+        match indexed.get(&id) {
+            Some(pack) if pack.size != size => findings.add(
+                CheckCategory::IndexMismatch,
+                CheckSeverity::Error,
+                Some(id.to_string()),
+                None,
+                format!(
+                    "Pack {id}: Größe laut Index {} Bytes, tatsächlich {size} Bytes",
+                    pack.size
+                ),
+            ),
+            Some(_) => {}
+            None if to_delete.contains(&id) => {}
+            None => findings.add(
+                CheckCategory::UnreferencedPack,
+                CheckSeverity::Warning,
+                Some(id.to_string()),
+                None,
+                format!("Pack {id} ist in keinem Index enthalten"),
+            ),
+        }
+    }
+    let mut missing: Vec<&PackId> = indexed.keys().filter(|id| !present.contains(id)).collect();
+    missing.sort_unstable();
+    for id in missing {
+        findings.add(
+            CheckCategory::MissingPack,
+            CheckSeverity::Error,
+            Some(id.to_string()),
+            None,
+            format!("Pack {id} fehlt im Backend"),
+        );
+    }
+    findings.result.packs_checked = indexed.len() as u64;
+    progress.report(CheckPhase::Packs, indexed.len() as u64, indexed.len() as u64, true);
+
+    // 3. Snapshots und ihre Trees; unlesbare Snapshot-Dateien sind Befunde
+    let mut snapshots = Vec::new();
+    match repo.stream_files::<SnapshotFile>() {
+        Ok(files) => {
+            for item in files {
+                match item {
+                    Ok((id, mut snap)) => {
+                        snap.id = id;
+                        snapshots.push(snap);
+                    }
+                    Err(e) => findings.add(
+                        CheckCategory::SnapshotError,
+                        CheckSeverity::Error,
+                        None,
+                        None,
+                        format!("Snapshot-Datei nicht lesbar: {e}"),
+                    ),
+                }
+            }
+        }
+        Err(e) => findings.add(
+            CheckCategory::SnapshotError,
+            CheckSeverity::Error,
+            None,
+            None,
+            format!("Snapshots konnten nicht gelesen werden: {e}"),
+        ),
+    }
+    let key = if read_data {
+        Some(crypto::master_key(&backends.repository(), password, Some(repo.key_id()))?)
+    } else {
+        None
+    };
+    let repo = repo.to_indexed().map_err(|e| rustic_error("Repository-Indexierung", e))?;
+    let mut visited: HashSet<TreeId> = HashSet::new();
+    let mut reported_blobs = HashSet::new();
+    for (done, snap) in snapshots.iter().enumerate() {
+        progress.report(CheckPhase::Trees, done as u64, snapshots.len() as u64, false);
+        let snapshot_id = Some(snap.id.to_string());
+        let mut pending = vec![(snap.tree, PathBuf::from("/"))];
+        while let Some((tree_id, path)) = pending.pop() {
+            if !visited.insert(tree_id) {
+                continue;
+            }
+            let tree = match repo.get_tree(&tree_id) {
+                Ok(tree) => tree,
+                Err(e) => {
+                    findings.add(
+                        CheckCategory::TreeError,
+                        CheckSeverity::Error,
+                        Some(tree_id.to_string()),
+                        snapshot_id.clone(),
+                        format!("Tree {tree_id} ({}) nicht lesbar: {e}", path.display()),
+                    );
+                    continue;
+                }
+            };
+            for node in tree.nodes {
+                let node_path = path.join(node.name());
+                if node.is_dir() {
+                    match node.subtree {
+                        Some(subtree) => pending.push((subtree, node_path.clone())),
+                        None => findings.add(
+                            CheckCategory::TreeError,
+                            CheckSeverity::Error,
+                            Some(tree_id.to_string()),
+                            snapshot_id.clone(),
+                            format!("Verzeichnis {} hat keinen Subtree", node_path.display()),
+                        ),
+                    }
+                }
+                for blob in node.content.iter().flatten() {
+                    if repo.get_index_entry(blob).is_err() && reported_blobs.insert(*blob) {
+                        findings.add(
+                            CheckCategory::TreeError,
+                            CheckSeverity::Error,
+                            Some(blob.to_string()),
+                            snapshot_id.clone(),
+                            format!(
+                                "Datei {} verweist auf Blob {blob}, der im Index fehlt",
+                                node_path.display()
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    findings.result.snapshots_checked = snapshots.len() as u64;
+    findings.result.trees_checked = visited.len() as u64;
+    progress.report(CheckPhase::Trees, snapshots.len() as u64, snapshots.len() as u64, true);
+
+    // 4. Daten lesen: jedes ausgewählte Pack einmal komplett, dann die Blobs darin
+    if let Some(key) = key {
+        let be = backends.repository();
+        let mut candidates: Vec<(&PackId, &IndexedPack)> =
+            indexed.iter().filter(|(id, _)| present.contains(id)).collect();
+        candidates.shuffle(&mut rand::thread_rng());
+        let selected = select_packs(candidates, subset, |(_, pack)| u64::from(pack.size));
+        let total_bytes: u64 = selected.iter().map(|(_, pack)| u64::from(pack.size)).sum();
+        info!(packs = selected.len(), bytes = total_bytes, "Lese Pack-Dateien");
+
+        for (pack_id, pack) in selected {
+            progress.report(CheckPhase::ReadData, findings.result.bytes_read, total_bytes, false);
+            let data = match be.read_full(FileType::Pack, pack_id) {
+                Ok(data) => data,
+                Err(e) => {
+                    findings.add(
+                        CheckCategory::DamagedBlob,
+                        CheckSeverity::Error,
+                        Some(pack_id.to_string()),
+                        None,
+                        format!("Pack {pack_id} nicht lesbar: {e}"),
+                    );
+                    continue;
+                }
+            };
+            findings.result.bytes_read += data.len() as u64;
+            findings.result.packs_read += 1;
+            if hex_sha256(&data) != pack_id.to_hex().as_str() {
+                findings.add(
+                    CheckCategory::DamagedBlob,
+                    CheckSeverity::Error,
+                    Some(pack_id.to_string()),
+                    None,
+                    format!("Pack {pack_id}: Hash stimmt nicht"),
+                );
+            }
+            for blob in &pack.blobs {
+                if let Err(reason) = verify_blob(&key, &data, blob) {
+                    findings.add(
+                        CheckCategory::DamagedBlob,
+                        CheckSeverity::Error,
+                        Some(blob.id.to_string()),
+                        None,
+                        format!("Pack {pack_id}, Blob {}: {reason}", blob.id),
+                    );
+                }
+            }
+        }
+        progress.report(CheckPhase::ReadData, total_bytes, total_bytes, true);
+    }
+
+    let result = findings.result;
+    info!(
+        ok = result.is_ok,
+        errors = result.errors.len(),
+        warnings = result.warnings.len(),
+        "Repository-Prüfung abgeschlossen"
+    );
+    Ok(result)
+}
+
+/// Wählt die Teilmenge der (bereits gemischten) Packs für read-data aus
+fn select_packs<T>(packs: Vec<T>, subset: &ReadDataSubset, size: impl Fn(&T) -> u64) -> Vec<T> {
+    match *subset {
+        ReadDataSubset::All => packs,
+        ReadDataSubset::Percentage { percent } => {
+            let count = (packs.len() as f64 * percent / 100.0).ceil() as usize;
+            packs.into_iter().take(count).collect()
+        }
+        ReadDataSubset::Bytes { max_bytes } => {
+            let mut budget = max_bytes;
+            packs
+                .into_iter()
+                .filter(|pack| {
+                    let size = size(pack);
+                    let fits = size <= budget;
+                    if fits {
+                        budget -= size;
+                    }
+                    fits
+                })
+                .collect()
+        }
+    }
+}
+
+/// Entschlüsselt einen Blob an seiner Position im Pack und prüft seinen Hash
+fn verify_blob(
+    key: &aes256ctr_poly1305aes::Key,
+    pack: &[u8],
+    blob: &IndexBlob,
+) -> std::result::Result<(), String> {
+    let start = blob.offset as usize;
+    let encrypted = pack
+        .get(start..start + blob.length as usize)
+        .ok_or_else(|| "liegt außerhalb der Pack-Datei".to_string())?;
+    let data = crypto::decrypt(key, encrypted)?;
+    let data = match blob.uncompressed_length {
+        Some(_) => zstd::decode_all(data.as_slice())
+            .map_err(|e| format!("Entpacken fehlgeschlagen: {e}"))?,
+        None => data,
+    };
+    if hex_sha256(&data) == blob.id.to_hex().as_str() {
+        Ok(())
+    } else {
+        Err("Hash stimmt nicht".to_string())
+    }
+}
+
+/// SHA-256 als Hex-String (entspricht Pack- und Blob-ID)
+fn hex_sha256(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_packs() {
+        let packs = vec![40u64, 30, 20, 10];
+        let select = |subset| select_packs(packs.clone(), &subset, |size| *size);
+
+        assert_eq!(select(ReadDataSubset::All), packs);
+        assert_eq!(select(ReadDataSubset::Percentage { percent: 50.0 }), vec![40, 30]);
+        assert_eq!(select(ReadDataSubset::Percentage { percent: 10.0 }), vec![40]);
+        assert!(select(ReadDataSubset::Percentage { percent: 0.0 }).is_empty());
+        // 40 passt nicht, die kleineren Packs füllen das Budget
+        assert_eq!(select(ReadDataSubset::Bytes { max_bytes: 35 }), vec![30]);
+        assert_eq!(select(ReadDataSubset::Bytes { max_bytes: 65 }), vec![40, 20]);
+    }
+
+    #[test]
+    fn test_findings_are_capped_but_counted() {
+        let mut findings =
+            Findings { result: CheckResultDto { is_ok: true, ..Default::default() } };
+        for i in 0..MAX_CHECK_FINDINGS + 5 {
+            findings.add(
+                CheckCategory::UnreferencedPack,
+                CheckSeverity::Warning,
+                None,
+                None,
+                format!("pack {i}"),
+            );
+        }
+        assert!(findings.result.is_ok);
+        assert!(findings.result.findings_truncated);
+        assert_eq!(findings.result.findings.len(), MAX_CHECK_FINDINGS);
+        assert_eq!(findings.result.summary.unreferenced_packs, MAX_CHECK_FINDINGS as u64 + 5);
+
+        findings.add(CheckCategory::MissingPack, CheckSeverity::Error, None, None, "x".into());
+        assert!(!findings.result.is_ok);
+        assert_eq!(findings.result.summary.missing_packs, 1);
+    }
+
+    #[test]
+    fn test_verify_blob() {
+        use aes256ctr_poly1305aes::{
+            Aes256CtrPoly1305Aes,
+            aead::{Aead, Nonce},
+        };
+
+        let key = aes256ctr_poly1305aes::Key::default();
+        let content = b"blob content";
+        let nonce = Nonce::<Aes256CtrPoly1305Aes>::default();
+        let mut encrypted = nonce.to_vec();
+        encrypted.extend(Aes256CtrPoly1305Aes::new(&key).encrypt(&nonce, &content[..]).unwrap());
+        let mut pack = b"header".to_vec();
+        pack.extend(&encrypted);
+
+        let id: rustic_core::Id = hex_sha256(content).parse().unwrap();
+        let blob = IndexBlob {
+            id: id.into(),
+            tpe: rustic_core::repofile::BlobType::Data,
+            offset: 6,
+            length: encrypted.len() as u32,
+            uncompressed_length: None,
+        };
+        assert!(verify_blob(&key, &pack, &blob).is_ok());
+
+        let outside = IndexBlob { offset: 7, ..blob };
+        assert!(verify_blob(&key, &pack, &outside).is_err());
+        let other = IndexBlob { id: rustic_core::Id::random().into(), ..blob };
+        assert_eq!(verify_blob(&key, &pack, &other).unwrap_err(), "Hash stimmt nicht");
+        let last = pack.len() - 1;
+        pack[last] ^= 1;
+        assert!(verify_blob(&key, &pack, &blob).is_err());
+    }
+
+    #[test]
+    fn test_hex_sha256() {
+        assert_eq!(
+            hex_sha256(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Master-Key und Entschlüsselung von Repository-Dateien
+//!
+//! rustic_core gibt den Master-Key und die Entschlüsselung nicht nach außen. Für Daten,
+//! die rustic_core nicht strukturiert liest (Lock-Dateien, read-data der Prüfung), wird der
+//! Key hier wie beim Öffnen des Repositories aus einer Key-Datei abgeleitet.
+
+use crate::error::{Result, RusticGuiError};
+use aes256ctr_poly1305aes::{
+    Aes256CtrPoly1305Aes, Key,
+    aead::{Aead, Nonce},
+};
+use rustic_core::{
+    WriteBackend,
+    repofile::{FileType, KeyFile, KeyId},
+};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Leitet den Master-Key aus dem Passwort ab.
+///
+/// Ohne `key_id` werden alle Key-Dateien durchprobiert (wie beim Öffnen des Repositories).
+///
+/// # Errors
+/// `AuthenticationFailed`, wenn das Passwort zu keiner Key-Datei passt
+pub fn master_key(
+    be: &Arc<dyn WriteBackend>,
+    password: &str,
+    key_id: Option<&KeyId>,
+) -> Result<Key> {
+    let ids = match key_id {
+        Some(id) => vec![**id],
+        None => be.list(FileType::Key)?,
+    };
+    for id in ids {
+        let data = be.read_full(FileType::Key, &id)?;
+        let key_file: KeyFile = match serde_json::from_slice(&data) {
+            Ok(key_file) => key_file,
+            Err(e) => {
+                warn!(key = %id, "Key-Datei nicht lesbar: {e}");
+                continue;
+            }
+        };
+        let Ok(master) = key_file.key_from_password(&password) else {
+            continue;
+        };
+        let (encrypt, k, r) = master.to_keys();
+        let mut key = Key::default();
+        key[0..32].copy_from_slice(&encrypt);
+        key[32..48].copy_from_slice(&k);
+        key[48..64].copy_from_slice(&r);
+        return Ok(key);
+    }
+    Err(RusticGuiError::AuthenticationFailed)
+}
+
+/// Entschlüsselt Daten im Repository-Format (Nonce + Daten + MAC)
+pub fn decrypt(key: &Key, data: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    if data.len() < 32 {
+        return Err("Daten sind zu kurz".to_string());
+    }
+    let nonce = Nonce::<Aes256CtrPoly1305Aes>::from_slice(&data[0..16]);
+    Aes256CtrPoly1305Aes::new(key)
+        .decrypt(nonce, &data[16..])
+        .map_err(|_| "Entschlüsselung fehlgeschlagen".to_string())
+}
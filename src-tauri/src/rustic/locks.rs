@@ -0,0 +1,346 @@
+//! Lock-Dateien eines Repositories
+//!
+//! rustic arbeitet ohne Locks; Lock-Dateien unter `locks/` stammen von restic, das dasselbe
+//! Repository-Format verwendet. rustic_core kennt diesen Dateityp nicht, deshalb werden
+//! Lock-Dateien nur bei lokalen Repositories direkt aus dem Dateisystem gelesen und mit dem
+//! Master-Key des Repositories entschlüsselt.
+
+use crate::error::{Result, RusticGuiError};
+use crate::rustic::crypto;
+use crate::types::{
+    LockInfoDto, LockSkipReason, RepositoryLocksDto, SkippedLockDto, UnlockResultDto,
+};
+use chrono::{DateTime, FixedOffset, Utc};
+use rustic_backend::BackendOptions;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Alter, ab dem ein Lock als veraltet gilt (wie bei restic)
+pub const DEFAULT_STALE_LOCK_AGE_SECS: u64 = 30 * 60;
+
+/// Inhalt einer restic-Lock-Datei
+#[derive(Debug, Deserialize)]
+struct LockFile {
+    time: DateTime<FixedOffset>,
+    #[serde(default)]
+    exclusive: bool,
+    hostname: String,
+    #[serde(default)]
+    username: Option<String>,
+    pid: i64,
+}
+
+/// Verzeichnis der Lock-Dateien, falls das Repository lokal ist
+pub fn local_locks_dir(repository_path: &str) -> Option<PathBuf> {
+    let path = Path::new(repository_path.strip_prefix("local:").unwrap_or(repository_path));
+    path.join("config").is_file().then(|| path.join("locks"))
+}
+
+/// Prüft, ob im (lokalen) Repository Lock-Dateien liegen
+///
+/// Die Dateien werden dafür nicht gelesen; ein Passwort ist nicht nötig.
+pub fn has_locks(repository_path: &str) -> bool {
+    local_locks_dir(repository_path).and_then(|dir| std::fs::read_dir(dir).ok()).is_some_and(
+        |mut entries| {
+            entries.any(|entry| entry.is_ok_and(|e| e.file_type().is_ok_and(|t| t.is_file())))
+        },
+    )
+}
+
+/// Listet die Lock-Dateien eines Repositories mit Host, PID und Alter auf.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort (zum Entschlüsseln der Lock-Dateien)
+///
+/// # Returns
+/// Lock-Dateien; bei nicht lokalen Repositories `supported = false`
+///
+/// # Errors
+/// `AuthenticationFailed`, wenn das Passwort zu keiner Key-Datei passt
+pub fn list_locks(repository_path: &str, password: &str) -> Result<RepositoryLocksDto> {
+    let Some(dir) = local_locks_dir(repository_path) else {
+        info!(repo = repository_path, "Lock-Dateien nur für lokale Repositories lesbar");
+        return Ok(RepositoryLocksDto::default());
+    };
+    let mut result = RepositoryLocksDto { supported: true, locks: Vec::new() };
+    if !dir.is_dir() {
+        return Ok(result);
+    }
+
+    let backends =
+        BackendOptions::default().repository(repository_path).to_backends().map_err(|e| {
+            RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+        })?;
+    let key = crypto::master_key(&backends.repository(), password, None)?;
+    let hostname = gethostname::gethostname().to_string_lossy().into_owned();
+    let now = Utc::now();
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let id = entry.file_name().to_string_lossy().into_owned();
+        let modified: DateTime<Utc> = entry.metadata()?.modified()?.into();
+        let data = std::fs::read(entry.path())?;
+        let lock = match decrypt_lock(&key, &data) {
+            Ok(lock) => lock_info(id, lock, &hostname, now),
+            // Ohne lesbaren Inhalt gibt es keinen Hinweis, dass das Lock veraltet ist
+            Err(e) => {
+                warn!(lock = %id, "Lock-Datei nicht lesbar: {e}");
+                LockInfoDto {
+                    id,
+                    hostname: None,
+                    username: None,
+                    pid: None,
+                    exclusive: false,
+                    time: modified.to_rfc3339(),
+                    age_seconds: age_seconds(modified, now),
+                    local_host: false,
+                    process_alive: None,
+                    stale: false,
+                    error: Some(e),
+                }
+            }
+        };
+        result.locks.push(lock);
+    }
+    result.locks.sort_by_key(|lock| std::cmp::Reverse(lock.age_seconds));
+    info!(repo = repository_path, locks = result.locks.len(), "Lock-Dateien gelesen");
+    Ok(result)
+}
+
+/// Entfernt veraltete Lock-Dateien.
+///
+/// Ein Lock wird nur entfernt, wenn es mindestens `min_age_secs` alt ist und nicht von
+/// einem noch laufenden Prozess dieses Rechners gehalten wird. Nicht lesbare Lock-Dateien
+/// werden nur entfernt, wenn sie ausdrücklich in `lock_ids` stehen.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `min_age_secs` - Mindestalter der zu entfernenden Locks
+/// * `lock_ids` - Nur diese Locks entfernen (leer = alle veralteten)
+///
+/// # Returns
+/// Entfernte und übersprungene Locks
+///
+/// # Errors
+/// `InvalidConfiguration` bei nicht lokalen Repositories
+pub fn remove_stale_locks(
+    repository_path: &str,
+    password: &str,
+    min_age_secs: u64,
+    lock_ids: &[String],
+) -> Result<UnlockResultDto> {
+    let listed = list_locks(repository_path, password)?;
+    let Some(dir) = local_locks_dir(repository_path).filter(|_| listed.supported) else {
+        return Err(RusticGuiError::InvalidConfiguration {
+            message: "Lock-Dateien können nur bei lokalen Repositories entfernt werden".into(),
+        });
+    };
+
+    let mut result = UnlockResultDto::default();
+    for id in lock_ids {
+        if !listed.locks.iter().any(|lock| &lock.id == id) {
+            result
+                .skipped
+                .push(SkippedLockDto { id: id.clone(), reason: LockSkipReason::NotFound });
+        }
+    }
+    for lock in &listed.locks {
+        if !lock_ids.is_empty() && !lock_ids.contains(&lock.id) {
+            continue;
+        }
+        match removal_check(lock, min_age_secs, lock_ids.contains(&lock.id)) {
+            Ok(()) => {
+                std::fs::remove_file(dir.join(&lock.id))?;
+                info!(lock = %lock.id, host = ?lock.hostname, pid = ?lock.pid, "Lock entfernt");
+                result.removed.push(lock.id.clone());
+            }
+            Err(reason) => {
+                info!(lock = %lock.id, ?reason, "Lock nicht entfernt");
+                result.skipped.push(SkippedLockDto { id: lock.id.clone(), reason });
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Prüft, ob ein Lock entfernt werden darf (`selected` = ausdrücklich ausgewählt)
+fn removal_check(
+    lock: &LockInfoDto,
+    min_age_secs: u64,
+    selected: bool,
+) -> std::result::Result<(), LockSkipReason> {
+    if lock.error.is_some() && !selected {
+        return Err(LockSkipReason::Unreadable);
+    }
+    if lock.local_host && lock.process_alive != Some(false) {
+        return Err(LockSkipReason::ProcessAlive);
+    }
+    if lock.age_seconds < min_age_secs {
+        return Err(LockSkipReason::TooRecent);
+    }
+    Ok(())
+}
+
+fn lock_info(id: String, lock: LockFile, hostname: &str, now: DateTime<Utc>) -> LockInfoDto {
+    let time = lock.time.with_timezone(&Utc);
+    let age_seconds = age_seconds(time, now);
+    let local_host = lock.hostname == hostname;
+    let process_alive = if local_host { process_alive(lock.pid) } else { None };
+    LockInfoDto {
+        id,
+        hostname: Some(lock.hostname),
+        username: lock.username,
+        pid: Some(lock.pid),
+        exclusive: lock.exclusive,
+        time: time.to_rfc3339(),
+        age_seconds,
+        local_host,
+        process_alive,
+        // Locks dieses Rechners sind veraltet, sobald der Prozess beendet ist
+        stale: if local_host {
+            process_alive == Some(false)
+        } else {
+            age_seconds >= DEFAULT_STALE_LOCK_AGE_SECS
+        },
+        error: None,
+    }
+}
+
+fn age_seconds(time: DateTime<Utc>, now: DateTime<Utc>) -> u64 {
+    u64::try_from((now - time).num_seconds()).unwrap_or(0)
+}
+
+/// Prüft, ob ein Prozess auf diesem Rechner läuft
+#[cfg(unix)]
+fn process_alive(pid: i64) -> Option<bool> {
+    let pid = libc::pid_t::try_from(pid).ok().filter(|pid| *pid > 0)?;
+    // SAFETY: Signal 0 wird nicht zugestellt, es prüft nur die Existenz des Prozesses
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return Some(true);
+    }
+    // EPERM: Prozess existiert, gehört aber einem anderen Benutzer
+    Some(std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH))
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: i64) -> Option<bool> {
+    None
+}
+
+/// Entschlüsselt eine Lock-Datei (Nonce + Daten + MAC, ab Repository-Version 2 ggf. komprimiert)
+fn decrypt_lock(
+    key: &aes256ctr_poly1305aes::Key,
+    data: &[u8],
+) -> std::result::Result<LockFile, String> {
+    let plain = crypto::decrypt(key, data)?;
+    let json = match plain.first() {
+        Some(2) => {
+            zstd::decode_all(&plain[1..]).map_err(|e| format!("Entpacken fehlgeschlagen: {e}"))?
+        }
+        _ => plain,
+    };
+    serde_json::from_slice(&json).map_err(|e| format!("Ungültiger Inhalt: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes256ctr_poly1305aes::{
+        Aes256CtrPoly1305Aes,
+        aead::{Aead, Nonce},
+    };
+
+    fn encrypt(key: &aes256ctr_poly1305aes::Key, data: &[u8]) -> Vec<u8> {
+        let nonce = Nonce::<Aes256CtrPoly1305Aes>::default();
+        let mut result = nonce.to_vec();
+        result.extend(Aes256CtrPoly1305Aes::new(key).encrypt(&nonce, data).unwrap());
+        result
+    }
+
+    fn lock(local_host: bool, process_alive: Option<bool>, age_seconds: u64) -> LockInfoDto {
+        LockInfoDto {
+            id: "lock".into(),
+            hostname: Some("host".into()),
+            username: None,
+            pid: Some(42),
+            exclusive: false,
+            time: String::new(),
+            age_seconds,
+            local_host,
+            process_alive,
+            stale: false,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_decrypt_lock() {
+        let key = aes256ctr_poly1305aes::Key::clone_from_slice(&[7u8; 64]);
+        let json = br#"{"time":"2024-05-01T10:00:00.123456789+02:00","exclusive":true,"hostname":"nas","username":"backup","pid":1234,"uid":1000,"gid":1000}"#;
+
+        let lock = decrypt_lock(&key, &encrypt(&key, json)).unwrap();
+        assert_eq!(lock.hostname, "nas");
+        assert_eq!(lock.pid, 1234);
+        assert!(lock.exclusive);
+
+        // Repository-Version 2: komprimiert mit Versions-Byte
+        let mut compressed = vec![2u8];
+        compressed.extend(zstd::encode_all(&json[..], 0).unwrap());
+        let lock = decrypt_lock(&key, &encrypt(&key, &compressed)).unwrap();
+        assert_eq!(lock.username.as_deref(), Some("backup"));
+
+        let other = aes256ctr_poly1305aes::Key::clone_from_slice(&[8u8; 64]);
+        assert!(decrypt_lock(&other, &encrypt(&key, json)).is_err());
+    }
+
+    #[test]
+    fn test_removal_check() {
+        let min_age = DEFAULT_STALE_LOCK_AGE_SECS;
+        assert_eq!(
+            removal_check(&lock(true, Some(true), min_age * 10), min_age, false),
+            Err(LockSkipReason::ProcessAlive)
+        );
+        // Status unbekannt: lieber nicht entfernen
+        assert_eq!(
+            removal_check(&lock(true, None, min_age * 10), min_age, false),
+            Err(LockSkipReason::ProcessAlive)
+        );
+        assert_eq!(
+            removal_check(&lock(true, Some(false), 10), min_age, false),
+            Err(LockSkipReason::TooRecent)
+        );
+        assert_eq!(removal_check(&lock(true, Some(false), 10), 0, false), Ok(()));
+        assert_eq!(
+            removal_check(&lock(false, None, 10), min_age, false),
+            Err(LockSkipReason::TooRecent)
+        );
+        assert_eq!(removal_check(&lock(false, None, min_age), min_age, false), Ok(()));
+
+        // Nicht lesbare Locks nur, wenn sie ausdrücklich ausgewählt wurden
+        let unreadable = LockInfoDto { error: Some("kaputt".into()), ..lock(false, None, min_age) };
+        assert_eq!(removal_check(&unreadable, min_age, false), Err(LockSkipReason::Unreadable));
+        assert_eq!(removal_check(&unreadable, min_age, true), Ok(()));
+    }
+
+    #[test]
+    fn test_local_locks_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().to_str().unwrap().to_string();
+        assert_eq!(local_locks_dir(&path), None);
+        assert!(!has_locks(&path));
+
+        std::fs::write(dir.path().join("config"), b"").unwrap();
+        assert_eq!(local_locks_dir(&format!("local:{path}")), Some(dir.path().join("locks")));
+        assert!(!has_locks(&path));
+
+        std::fs::create_dir(dir.path().join("locks")).unwrap();
+        std::fs::write(dir.path().join("locks").join("abc"), b"").unwrap();
+        assert!(has_locks(&path));
+        assert_eq!(local_locks_dir("s3:bucket/repo"), None);
+    }
+}
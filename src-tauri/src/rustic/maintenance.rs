@@ -0,0 +1,184 @@
+//! Wartung nach Backups: Retention auf die Snapshots eines Jobs und Prune
+//!
+//! Die Retention wird nur auf Snapshots angewendet, die zum selben Job gehören wie der
+//! gerade erstellte Snapshot (gleicher Host, gleiche Pfade, alle Job-Tags vorhanden).
+//! Snapshots anderer Rechner oder Jobs im selben Repository bleiben unberührt.
+
+use crate::error::{Result, RusticGuiError};
+use crate::types::PruneResultDto;
+use chrono::{DateTime, Utc};
+use rustic_backend::BackendOptions;
+use rustic_core::{
+    KeepOptions, NoProgressBars, OpenStatus, PruneOptions, Repository, RepositoryOptions,
+    SnapshotGroupCriterion,
+    repofile::{SnapshotFile, SnapshotId},
+};
+
+/// Öffnet ein Repository für Wartungsschritte
+///
+/// # Arguments
+/// * `path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+///
+/// # Errors
+/// `RusticError` wenn Backend-Erstellung oder Entsperren fehlschlägt
+pub fn open_repository(
+    path: &str,
+    password: &str,
+) -> Result<Repository<NoProgressBars, OpenStatus>> {
+    let repo_opts = RepositoryOptions::default().password(password.to_string());
+    let backends = BackendOptions::default().repository(path).to_backends().map_err(|e| {
+        RusticGuiError::RusticError { message: format!("Backend-Erstellung fehlgeschlagen: {}", e) }
+    })?;
+
+    Repository::<NoProgressBars, ()>::new(&repo_opts, &backends)
+        .map_err(|e| RusticGuiError::RusticError {
+            message: format!("Repository öffnen fehlgeschlagen: {}", e),
+        })?
+        .open()
+        .map_err(|e| RusticGuiError::RusticError {
+            message: format!("Repository entsperren fehlgeschlagen: {}", e),
+        })
+}
+
+/// Prüft, ob ein Snapshot zum selben Job gehört wie der Referenz-Snapshot
+///
+/// # Arguments
+/// * `reference` - Vom Job gerade erstellter Snapshot
+/// * `candidate` - Zu prüfender Snapshot
+/// * `job_tags` - Tags des Jobs (müssen alle am Kandidaten vorhanden sein)
+pub fn belongs_to_job(
+    reference: &SnapshotFile,
+    candidate: &SnapshotFile,
+    job_tags: &[String],
+) -> bool {
+    candidate.hostname == reference.hostname
+        && candidate.paths == reference.paths
+        && job_tags.iter().all(|tag| candidate.tags.contains(tag))
+}
+
+/// Wendet eine Retention-Policy auf die Snapshots eines Jobs an und löscht die übrigen
+///
+/// Der Referenz-Snapshot selbst wird nie gelöscht.
+///
+/// # Arguments
+/// * `repo` - Geöffnetes Repository
+/// * `snapshot_id` - ID des gerade erstellten Snapshots
+/// * `job_tags` - Tags des Jobs
+/// * `keep` - Retention-Policy als rustic KeepOptions
+/// * `group_by` - Gruppierung, innerhalb der die Policy angewendet wird
+///
+/// # Returns
+/// IDs der gelöschten Snapshots
+pub fn apply_job_retention(
+    repo: &Repository<NoProgressBars, OpenStatus>,
+    snapshot_id: &str,
+    job_tags: &[String],
+    keep: &KeepOptions,
+    group_by: SnapshotGroupCriterion,
+) -> Result<Vec<String>> {
+    let rustic_err = |context: &str, e: Box<rustic_core::RusticError>| {
+        RusticGuiError::RusticError { message: format!("{}: {}", context, e) }
+    };
+
+    let reference = repo
+        .get_snapshot_from_str(snapshot_id, |_| true)
+        .map_err(|e| rustic_err("Snapshot laden fehlgeschlagen", e))?;
+    let forget = repo
+        .get_forget_snapshots(keep, group_by, |sn| belongs_to_job(&reference, sn, job_tags))
+        .map_err(|e| rustic_err("Retention-Policy fehlgeschlagen", e))?;
+    let to_delete: Vec<SnapshotId> =
+        forget.into_forget_ids().into_iter().filter(|id| *id != reference.id).collect();
+
+    if !to_delete.is_empty() {
+        tracing::info!("Lösche {} Snapshots gemäß Retention-Policy des Jobs", to_delete.len());
+        repo.delete_snapshots(&to_delete)
+            .map_err(|e| rustic_err("Snapshots löschen fehlgeschlagen", e))?;
+    }
+
+    Ok(to_delete.iter().map(ToString::to_string).collect())
+}
+
+/// Entfernt nicht mehr referenzierte Daten aus dem Repository
+///
+/// # Returns
+/// Prune-Statistiken
+pub fn prune(repo: &Repository<NoProgressBars, OpenStatus>) -> Result<PruneResultDto> {
+    let opts = PruneOptions::default();
+    let plan = repo.prune_plan(&opts).map_err(|e| RusticGuiError::RusticError {
+        message: format!("Prune-Plan fehlgeschlagen: {}", e),
+    })?;
+
+    let result = PruneResultDto {
+        packs_removed: plan.stats.packs_to_delete.remove,
+        packs_kept: plan.stats.packs_to_delete.keep,
+        packs_recovered: plan.stats.packs_to_delete.recover,
+        size_removed: plan.stats.size_to_delete.remove,
+        size_kept: plan.stats.size_to_delete.keep,
+        size_recovered: plan.stats.size_to_delete.recover,
+        dry_run: false,
+    };
+
+    repo.prune(&opts, plan).map_err(|e| RusticGuiError::RusticError {
+        message: format!("Prune fehlgeschlagen: {}", e),
+    })?;
+
+    Ok(result)
+}
+
+/// Prüft, ob ein Prune gemäß Intervall fällig ist
+///
+/// # Arguments
+/// * `last_prune` - Zeitpunkt des letzten erfolgreichen Prune (None = noch nie)
+/// * `interval_days` - Mindestabstand zwischen zwei Prune-Läufen in Tagen
+/// * `now` - Aktueller Zeitpunkt
+pub fn prune_due(
+    last_prune: Option<DateTime<Utc>>,
+    interval_days: u32,
+    now: DateTime<Utc>,
+) -> bool {
+    match last_prune {
+        Some(last) => now - last >= chrono::Duration::days(i64::from(interval_days)),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustic_core::repofile::StringList;
+
+    fn snapshot(hostname: &str, paths: &str, tags: &str) -> SnapshotFile {
+        SnapshotFile {
+            hostname: hostname.to_string(),
+            paths: paths.parse::<StringList>().unwrap(),
+            tags: tags.parse::<StringList>().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_belongs_to_job() {
+        let reference = snapshot("laptop", "/home/user", "daily");
+        let tags = vec!["daily".to_string()];
+
+        assert!(belongs_to_job(
+            &reference,
+            &snapshot("laptop", "/home/user", "daily,extra"),
+            &tags
+        ));
+        assert!(!belongs_to_job(&reference, &snapshot("server", "/home/user", "daily"), &tags));
+        assert!(!belongs_to_job(&reference, &snapshot("laptop", "/etc", "daily"), &tags));
+        assert!(!belongs_to_job(&reference, &snapshot("laptop", "/home/user", "weekly"), &tags));
+    }
+
+    #[test]
+    fn test_prune_due() {
+        let now = DateTime::parse_from_rfc3339("2025-03-10T08:00:00Z").unwrap().to_utc();
+        let last = DateTime::parse_from_rfc3339("2025-03-05T08:00:00Z").unwrap().to_utc();
+
+        assert!(prune_due(None, 7, now));
+        assert!(!prune_due(Some(last), 7, now));
+        assert!(prune_due(Some(last), 5, now));
+    }
+}
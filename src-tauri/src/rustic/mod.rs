@@ -1,5 +1,14 @@
+pub mod archive;
+pub mod attribution;
 pub mod backends;
 pub mod backup;
+pub mod check;
+pub mod crypto;
+pub mod locks;
+pub mod maintenance;
+pub mod preview;
+pub mod progress;
+pub mod repair;
 pub mod repository;
 pub mod restore;
 pub mod snapshot;
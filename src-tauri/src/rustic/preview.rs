@@ -0,0 +1,280 @@
+//! Einzelne Dateien aus einem Snapshot lesen (Vorschau und Download)
+//!
+//! Liest den Inhalt einer Datei direkt aus den Packs des Repositories, ohne einen
+//! Restore-Plan zu erstellen: entweder die ersten Bytes für eine Vorschau im
+//! Datei-Browser oder die komplette Datei gestreamt in eine Zieldatei.
+
+use crate::error::RusticGuiError;
+use crate::types::{ExtractedFileDto, FilePreviewDto};
+use base64::{Engine as _, engine::general_purpose};
+use rustic_backend::BackendOptions;
+use rustic_core::{
+    FullIndex, IndexedStatus, NoProgressBars, OpenStatus, Repository, RepositoryOptions,
+    repofile::Node,
+};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{error, info, warn};
+
+/// Standardgröße der Vorschau, wenn keine Länge angegeben ist
+const DEFAULT_PREVIEW_BYTES: u64 = 64 * 1024;
+
+/// Obergrenze für die Vorschau (größere Dateien werden gespeichert statt angezeigt)
+const MAX_PREVIEW_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Blockgröße beim Streamen in eine Datei
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+type IndexedRepository = Repository<NoProgressBars, IndexedStatus<FullIndex, OpenStatus>>;
+
+/// Öffnet das Repository mit vollständigem Index und sucht die Datei im Snapshot.
+///
+/// # Errors
+/// `SnapshotNotFound`/`PathNotFound`, wenn Snapshot oder Pfad nicht existieren;
+/// `RestoreFailed`, wenn der Pfad keine reguläre Datei ist
+fn open_file_node(
+    repository_path: &str,
+    password: &str,
+    snapshot_id: &str,
+    path: &str,
+) -> Result<(IndexedRepository, Node, String), RusticGuiError> {
+    let mut repo_opts = RepositoryOptions::default();
+    repo_opts.password = Some(password.to_string());
+    let mut backend_opts = BackendOptions::default();
+    backend_opts.repository = Some(repository_path.to_string());
+    let backends = backend_opts.to_backends().map_err(|e| {
+        RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+    })?;
+    let repo = Repository::new(&repo_opts, &backends)
+        .map_err(|e| {
+            error!(?e, "Fehler beim Initialisieren des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .open()
+        .map_err(|e| {
+            error!(?e, "Fehler beim Öffnen des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .to_indexed()
+        .map_err(|e| {
+            error!(?e, "Repository-Indexierung fehlgeschlagen");
+            RusticGuiError::Internal(format!("Repository-Indexierung fehlgeschlagen: {e}"))
+        })?;
+
+    let snapshot = repo
+        .get_snapshot_from_str(snapshot_id, |_| true)
+        .map_err(|_| RusticGuiError::SnapshotNotFound { id: snapshot_id.to_string() })?;
+
+    let relative = path.trim_matches('/');
+    let display_path = format!("/{relative}");
+    let node = repo
+        .node_from_snapshot_and_path(&snapshot, relative)
+        .map_err(|_| RusticGuiError::PathNotFound { path: display_path.clone() })?;
+    if !node.is_file() {
+        return Err(RusticGuiError::RestoreFailed {
+            reason: format!("{display_path} ist keine reguläre Datei"),
+        });
+    }
+
+    Ok((repo, node, display_path))
+}
+
+/// Liest die ersten Bytes einer Datei aus einem Snapshot für eine Vorschau.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `snapshot_id` - ID des Snapshots
+/// * `path` - Pfad der Datei im Snapshot
+/// * `max_bytes` - Maximal zu lesende Bytes (Standard 64 KiB, höchstens 16 MiB)
+///
+/// # Returns
+/// Vorschau mit Base64-kodiertem Inhalt, MIME-Typ und Text-Erkennung
+///
+/// # Errors
+/// `PathNotFound`, wenn die Datei nicht im Snapshot existiert
+pub fn read_file_preview(
+    repository_path: &str,
+    password: &str,
+    snapshot_id: &str,
+    path: &str,
+    max_bytes: Option<u64>,
+) -> Result<FilePreviewDto, RusticGuiError> {
+    info!(repo = repository_path, snapshot = snapshot_id, path, "Lade Dateivorschau");
+
+    let (repo, node, display_path) = open_file_node(repository_path, password, snapshot_id, path)?;
+    let size = node.meta.size;
+    let length = size.min(max_bytes.unwrap_or(DEFAULT_PREVIEW_BYTES).min(MAX_PREVIEW_BYTES));
+
+    let content = if length == 0 {
+        Vec::new()
+    } else {
+        let file = repo.open_file(&node).map_err(|e| RusticGuiError::RestoreFailed {
+            reason: format!("Datei konnte nicht geöffnet werden: {e}"),
+        })?;
+        repo.read_file_at(&file, 0, length as usize)
+            .map_err(|e| RusticGuiError::RestoreFailed {
+                reason: format!("Datei konnte nicht gelesen werden: {e}"),
+            })?
+            .to_vec()
+    };
+    let truncated = (content.len() as u64) < size;
+
+    Ok(FilePreviewDto {
+        path: display_path,
+        size,
+        modified: node.meta.mtime.map(|t| t.to_rfc3339()),
+        mime_type: mime_type(node.name().as_ref()).map(str::to_string),
+        is_text: is_text(&content, truncated),
+        content_base64: general_purpose::STANDARD.encode(&content),
+        bytes_read: content.len() as u64,
+        truncated,
+    })
+}
+
+/// Speichert eine einzelne Datei aus einem Snapshot.
+///
+/// Der Inhalt wird blockweise gestreamt, große Dateien werden also nicht komplett in den
+/// Speicher geladen. Ohne `output_path` wird in ein temporäres Verzeichnis geschrieben
+/// (z.B. um die Datei mit einer externen Anwendung zu öffnen).
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `snapshot_id` - ID des Snapshots
+/// * `path` - Pfad der Datei im Snapshot
+/// * `output_path` - Zieldatei (wird überschrieben); None = temporäre Datei
+///
+/// # Returns
+/// Pfad der geschriebenen Datei und Anzahl geschriebener Bytes
+///
+/// # Errors
+/// `PathNotFound`, wenn die Datei nicht im Snapshot existiert
+pub fn extract_file(
+    repository_path: &str,
+    password: &str,
+    snapshot_id: &str,
+    path: &str,
+    output_path: Option<&str>,
+) -> Result<ExtractedFileDto, RusticGuiError> {
+    let (repo, node, display_path) = open_file_node(repository_path, password, snapshot_id, path)?;
+
+    let output = match output_path {
+        Some(output) => PathBuf::from(output),
+        None => {
+            let dir =
+                std::env::temp_dir().join("rustic-gui").join(uuid::Uuid::new_v4().to_string());
+            std::fs::create_dir_all(&dir)?;
+            dir.join(node.name())
+        }
+    };
+    info!(snapshot = snapshot_id, path = %display_path, output = %output.display(), "Speichere Datei aus Snapshot");
+
+    let result = write_node(&repo, &node, &output);
+    if let Err(e) = &result {
+        warn!(output = %output.display(), error = %e, "Datei unvollständig, wird entfernt");
+        let _ = std::fs::remove_file(&output);
+    }
+    let bytes = result?;
+
+    // Änderungszeit übernehmen, damit die Datei wie im Snapshot aussieht
+    if let Some(mtime) = node.meta.mtime {
+        let file = std::fs::File::options().write(true).open(&output)?;
+        if let Err(e) = file.set_modified(mtime.into()) {
+            warn!(?e, "Änderungszeit konnte nicht gesetzt werden");
+        }
+    }
+
+    Ok(ExtractedFileDto { output_path: output.to_string_lossy().to_string(), bytes })
+}
+
+/// Schreibt den Inhalt eines Datei-Knotens blockweise nach `output`
+fn write_node(repo: &IndexedRepository, node: &Node, output: &Path) -> Result<u64, RusticGuiError> {
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(output)?);
+    let file = repo.open_file(node).map_err(|e| RusticGuiError::RestoreFailed {
+        reason: format!("Datei konnte nicht geöffnet werden: {e}"),
+    })?;
+
+    let mut offset = 0;
+    loop {
+        let data = repo.read_file_at(&file, offset, CHUNK_SIZE).map_err(|e| {
+            RusticGuiError::RestoreFailed {
+                reason: format!("Datei konnte nicht gelesen werden: {e}"),
+            }
+        })?;
+        if data.is_empty() {
+            break;
+        }
+        writer.write_all(&data)?;
+        offset += data.len();
+    }
+    writer.flush()?;
+    Ok(offset as u64)
+}
+
+/// Prüft, ob der (ggf. abgeschnittene) Inhalt als Text angezeigt werden kann.
+///
+/// Ein am Ende abgeschnittenes UTF-8-Zeichen gilt bei `truncated` nicht als Fehler.
+fn is_text(content: &[u8], truncated: bool) -> bool {
+    if content.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(content) {
+        Ok(_) => true,
+        Err(e) => truncated && e.error_len().is_none(),
+    }
+}
+
+/// Leitet den MIME-Typ aus der Dateiendung ab (nur für die Vorschau relevante Typen)
+fn mime_type(name: &Path) -> Option<&'static str> {
+    let extension = name.extension()?.to_str()?.to_ascii_lowercase();
+    let mime = match extension.as_str() {
+        "txt" | "log" | "conf" | "cfg" | "ini" | "md" | "rs" | "py" | "sh" | "c" | "h" | "cpp"
+        | "java" | "go" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "ts" => "text/typescript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "toml" => "application/toml",
+        "yaml" | "yml" => "application/yaml",
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_text() {
+        assert!(is_text(b"hello\nworld", false));
+        assert!(is_text(b"", false));
+        assert!(!is_text(b"PNG\0\x01", false));
+
+        // "ä" = 0xC3 0xA4, am Ende abgeschnitten
+        let cut = &"aä".as_bytes()[..2];
+        assert!(is_text(cut, true));
+        assert!(!is_text(cut, false));
+        assert!(!is_text(&[0xff, 0xfe, b'a'], true));
+    }
+
+    #[test]
+    fn test_mime_type() {
+        assert_eq!(mime_type(Path::new("photo.JPG")), Some("image/jpeg"));
+        assert_eq!(mime_type(Path::new("notes.md")), Some("text/plain"));
+        assert_eq!(mime_type(Path::new("Makefile")), None);
+        assert_eq!(mime_type(Path::new("archive.tar.gz")), None);
+    }
+}
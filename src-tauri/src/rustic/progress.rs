@@ -0,0 +1,326 @@
+use parking_lot::Mutex;
+use rustic_core::{Progress, ProgressBars};
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Standard-Intervall zwischen zwei Progress-Updates eines Balkens
+const DEFAULT_UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Art eines Fortschrittsbalkens (entspricht den Konstruktoren von `ProgressBars`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressKind {
+    /// Versteckter Fortschritt (wird nicht gemeldet)
+    Hidden,
+    /// Spinner ohne Länge
+    Spinner,
+    /// Zähler (z.B. Anzahl Dateien)
+    Counter,
+    /// Byte-Zähler (z.B. gesicherte Daten)
+    Bytes,
+}
+
+/// Momentaufnahme eines Fortschrittsbalkens aus rustic_core
+#[derive(Debug, Clone)]
+pub struct ProgressUpdate {
+    /// Art des Fortschritts
+    pub kind: ProgressKind,
+    /// Präfix, mit dem rustic_core den Fortschritt gestartet hat (z.B. "backing up...")
+    pub prefix: String,
+    /// Optionaler Titel (via `set_title`)
+    pub title: Option<String>,
+    /// Aktueller Stand
+    pub position: u64,
+    /// Gesamtlänge (falls bekannt)
+    pub length: Option<u64>,
+    /// Ob der Fortschritt abgeschlossen ist
+    pub finished: bool,
+}
+
+type ProgressCallback = Arc<dyn Fn(&ProgressUpdate) + Send + Sync>;
+
+/// Unwind-Payload, mit dem `GuiProgress::inc` einen abgebrochenen Vorgang beendet
+/// (siehe `GuiProgressBars::abort_on_cancel`)
+#[derive(Debug)]
+pub struct ProgressAborted;
+
+/// `ProgressBars`-Implementierung, die rustic_core-Fortschritte an einen Callback weiterleitet.
+///
+/// Updates werden pro Balken gedrosselt, `set_length` und `finish` werden immer gemeldet.
+/// Nach einem Abbruch (siehe `with_cancellation`) werden keine Updates mehr gemeldet.
+#[derive(Clone)]
+pub struct GuiProgressBars {
+    callback: ProgressCallback,
+    interval: Duration,
+    cancel: Option<CancellationToken>,
+    abort_on_cancel: bool,
+}
+
+impl GuiProgressBars {
+    /// Erstellt neue ProgressBars mit dem angegebenen Callback
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&ProgressUpdate) + Send + Sync + 'static,
+    {
+        Self {
+            callback: Arc::new(callback),
+            interval: DEFAULT_UPDATE_INTERVAL,
+            cancel: None,
+            abort_on_cancel: false,
+        }
+    }
+
+    /// Setzt das minimale Intervall zwischen zwei Updates eines Balkens
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Verknüpft die ProgressBars mit einem Abbruch-Token
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Beendet den Vorgang nach einem Abbruch beim nächsten `inc` per Unwind.
+    ///
+    /// rustic_core bietet beim Lesen der Backup-Quelle keinen Abbruch-Hook, ruft `inc` aber
+    /// für jeden gelesenen Chunk auf. Der Aufrufer muss den Vorgang mit `catch_unwind`
+    /// ausführen; Worker-Threads von rustic_core reichen den Unwind als eigenen Panic weiter.
+    pub fn abort_on_cancel(mut self) -> Self {
+        self.abort_on_cancel = true;
+        self
+    }
+
+    fn start(&self, kind: ProgressKind, prefix: Cow<'static, str>) -> GuiProgress {
+        let progress = GuiProgress {
+            state: Arc::new(ProgressState {
+                kind,
+                prefix: prefix.into_owned(),
+                title: Mutex::new(None),
+                position: AtomicU64::new(0),
+                length: Mutex::new(None),
+                finished: AtomicBool::new(false),
+                last_update: Mutex::new(None),
+                interval: self.interval,
+            }),
+            callback: self.callback.clone(),
+            cancel: self.cancel.clone(),
+            abort_on_cancel: self.abort_on_cancel,
+        };
+        progress.emit(true);
+        progress
+    }
+}
+
+impl std::fmt::Debug for GuiProgressBars {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuiProgressBars").field("interval", &self.interval).finish()
+    }
+}
+
+impl ProgressBars for GuiProgressBars {
+    type P = GuiProgress;
+
+    fn progress_hidden(&self) -> Self::P {
+        self.start(ProgressKind::Hidden, Cow::Borrowed(""))
+    }
+
+    fn progress_spinner(&self, prefix: impl Into<Cow<'static, str>>) -> Self::P {
+        self.start(ProgressKind::Spinner, prefix.into())
+    }
+
+    fn progress_counter(&self, prefix: impl Into<Cow<'static, str>>) -> Self::P {
+        self.start(ProgressKind::Counter, prefix.into())
+    }
+
+    fn progress_bytes(&self, prefix: impl Into<Cow<'static, str>>) -> Self::P {
+        self.start(ProgressKind::Bytes, prefix.into())
+    }
+}
+
+/// Interner, zwischen Threads geteilter Zustand eines Balkens
+struct ProgressState {
+    kind: ProgressKind,
+    prefix: String,
+    title: Mutex<Option<&'static str>>,
+    position: AtomicU64,
+    length: Mutex<Option<u64>>,
+    finished: AtomicBool,
+    last_update: Mutex<Option<Instant>>,
+    interval: Duration,
+}
+
+/// Einzelner Fortschrittsbalken (wird von rustic_core aus mehreren Threads bedient)
+#[derive(Clone)]
+pub struct GuiProgress {
+    state: Arc<ProgressState>,
+    callback: ProgressCallback,
+    cancel: Option<CancellationToken>,
+    abort_on_cancel: bool,
+}
+
+impl GuiProgress {
+    /// Liefert den aktuellen Stand als `ProgressUpdate`
+    pub fn snapshot(&self) -> ProgressUpdate {
+        ProgressUpdate {
+            kind: self.state.kind,
+            prefix: self.state.prefix.clone(),
+            title: self.state.title.lock().map(str::to_string),
+            position: self.state.position.load(Ordering::Relaxed),
+            length: *self.state.length.lock(),
+            finished: self.state.finished.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Ob der zugehörige Vorgang abgebrochen wurde
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Meldet den aktuellen Stand an den Callback (gedrosselt, außer bei `force`)
+    fn emit(&self, force: bool) {
+        if self.state.kind == ProgressKind::Hidden || self.is_cancelled() {
+            return;
+        }
+
+        {
+            let mut last_update = self.state.last_update.lock();
+            let now = Instant::now();
+            if !force && last_update.is_some_and(|last| now - last < self.state.interval) {
+                return;
+            }
+            *last_update = Some(now);
+        }
+
+        (self.callback)(&self.snapshot());
+    }
+}
+
+impl std::fmt::Debug for GuiProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuiProgress")
+            .field("kind", &self.state.kind)
+            .field("prefix", &self.state.prefix)
+            .field("position", &self.state.position.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl Progress for GuiProgress {
+    fn is_hidden(&self) -> bool {
+        self.state.kind == ProgressKind::Hidden
+    }
+
+    fn set_length(&self, len: u64) {
+        *self.state.length.lock() = Some(len);
+        self.emit(true);
+    }
+
+    fn set_title(&self, title: &'static str) {
+        *self.state.title.lock() = Some(title);
+        self.emit(true);
+    }
+
+    fn inc(&self, inc: u64) {
+        if self.abort_on_cancel && self.is_cancelled() {
+            std::panic::resume_unwind(Box::new(ProgressAborted));
+        }
+        self.state.position.fetch_add(inc, Ordering::Relaxed);
+        self.emit(false);
+    }
+
+    fn finish(&self) {
+        self.state.finished.store(true, Ordering::Relaxed);
+        self.emit(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collecting_bars(interval: Duration) -> (GuiProgressBars, Arc<Mutex<Vec<ProgressUpdate>>>) {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let updates_clone = updates.clone();
+        let bars = GuiProgressBars::new(move |update| updates_clone.lock().push(update.clone()))
+            .with_interval(interval);
+        (bars, updates)
+    }
+
+    #[test]
+    fn test_bytes_progress_reports_position_and_length() {
+        let (bars, updates) = collecting_bars(Duration::ZERO);
+        let p = bars.progress_bytes("backing up...");
+        p.set_length(100);
+        p.inc(40);
+        p.inc(60);
+        p.finish();
+
+        let updates = updates.lock();
+        let last = updates.last().unwrap();
+        assert_eq!(last.kind, ProgressKind::Bytes);
+        assert_eq!(last.prefix, "backing up...");
+        assert_eq!(last.position, 100);
+        assert_eq!(last.length, Some(100));
+        assert!(last.finished);
+    }
+
+    #[test]
+    fn test_hidden_progress_is_not_reported() {
+        let (bars, updates) = collecting_bars(Duration::ZERO);
+        let p = bars.progress_hidden();
+        assert!(p.is_hidden());
+        p.inc(10);
+        p.finish();
+        assert!(updates.lock().is_empty());
+    }
+
+    #[test]
+    fn test_updates_are_throttled() {
+        let (bars, updates) = collecting_bars(Duration::from_secs(60));
+        let p = bars.progress_counter("reading index...");
+        for _ in 0..100 {
+            p.inc(1);
+        }
+        p.finish();
+
+        // Start und Finish werden immer gemeldet, die Zwischenschritte gedrosselt
+        let updates = updates.lock();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[1].position, 100);
+    }
+
+    #[test]
+    fn test_cancelled_progress_is_not_reported() {
+        let token = CancellationToken::new();
+        let (bars, updates) = collecting_bars(Duration::ZERO);
+        let bars = bars.with_cancellation(token.clone());
+        let p = bars.progress_bytes("backing up...");
+        p.inc(10);
+        token.cancel();
+        assert!(p.is_cancelled());
+        p.inc(10);
+        p.finish();
+
+        let updates = updates.lock();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[1].position, 10);
+    }
+
+    #[test]
+    fn test_abort_on_cancel_unwinds_in_inc() {
+        let token = CancellationToken::new();
+        let (bars, _updates) = collecting_bars(Duration::ZERO);
+        let p =
+            bars.with_cancellation(token.clone()).abort_on_cancel().progress_bytes("backing up...");
+        p.inc(10);
+        token.cancel();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| p.inc(10)));
+        assert!(result.unwrap_err().is::<ProgressAborted>());
+        assert_eq!(p.snapshot().position, 10);
+    }
+}
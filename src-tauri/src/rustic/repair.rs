@@ -0,0 +1,518 @@
+//! Reparatur von Index und Snapshots
+//!
+//! `Repository::repair_index` und `Repository::repair_snapshots` von rustic_core melden nur
+//! über das Logging, was sie ändern. Der Plan wird deshalb vorab mit denselben Regeln
+//! berechnet (nur lesend). Ausgeführt wird ein Plan nur, wenn seine `plan_id` bestätigt wird
+//! und das Repository sich seit dem Dry-Run nicht verändert hat.
+
+use crate::error::RusticGuiError;
+use crate::rustic::progress::GuiProgressBars;
+use crate::types::{
+    ProgressInfo, RepairIndexPlanDto, RepairProgress, RepairSnapshotsOptionsDto,
+    RepairSnapshotsPlanDto, RepairStage, SnapshotRepairAction, SnapshotRepairDto,
+};
+use rustic_backend::BackendOptions;
+use rustic_core::{
+    IndexedFull, OpenStatus, ReadBackend, RepairIndexOptions, RepairSnapshotsOptions, Repository,
+    RepositoryBackends, RepositoryOptions, TreeId,
+    repofile::{FileType, IndexFile, IndexId, PackId, SnapshotFile, StringList},
+};
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{error, info, warn};
+
+type ProgressCallback = Arc<dyn Fn(RepairProgress) + Send + Sync>;
+
+/// Meldet Fortschritt der Planung und (über `GuiProgressBars`) der Ausführung
+#[derive(Clone)]
+struct RepairReporter {
+    callback: ProgressCallback,
+    applying: Arc<AtomicBool>,
+}
+
+impl RepairReporter {
+    fn report(&self, message: &str, current: u64, total: Option<u64>) {
+        let stage = if self.applying.load(Ordering::Relaxed) {
+            RepairStage::Applying
+        } else {
+            RepairStage::Planning
+        };
+        let percentage = total
+            .filter(|total| *total > 0)
+            .map(|total| ((current as f64 / total as f64) * 100.0).min(100.0) as f32);
+        (self.callback)(RepairProgress {
+            base: ProgressInfo {
+                current,
+                total: total.unwrap_or(0),
+                message: Some(message.to_string()),
+                percentage,
+            },
+            stage,
+        });
+    }
+}
+
+/// Öffnet das Repository mit ProgressBars, die an den Reporter weiterleiten
+fn open_repository(
+    repository_path: &str,
+    password: &str,
+    reporter: &RepairReporter,
+) -> Result<(Repository<GuiProgressBars, OpenStatus>, RepositoryBackends), RusticGuiError> {
+    let bars_reporter = reporter.clone();
+    let progress_bars = GuiProgressBars::new(move |update| {
+        bars_reporter.report(&update.prefix, update.position, update.length);
+    });
+
+    let mut repo_opts = RepositoryOptions::default();
+    repo_opts.password = Some(password.to_string());
+    let backends =
+        BackendOptions::default().repository(repository_path).to_backends().map_err(|e| {
+            RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+        })?;
+    let repo = Repository::new_with_progress(&repo_opts, &backends, progress_bars)
+        .map_err(|e| {
+            error!(?e, "Fehler beim Initialisieren des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .open()
+        .map_err(|e| {
+            error!(?e, "Fehler beim Öffnen des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?;
+    Ok((repo, backends))
+}
+
+fn rustic_error(what: &str, e: Box<rustic_core::RusticError>) -> RusticGuiError {
+    error!("{} fehlgeschlagen: {:?}", what, e);
+    RusticGuiError::RusticError { message: format!("{what} fehlgeschlagen: {e}") }
+}
+
+/// Prüft die bestätigte Plan-ID gegen den aktuellen Plan
+fn confirm_plan(plan_id: &str, confirm: &str) -> Result<(), RusticGuiError> {
+    if plan_id == confirm {
+        Ok(())
+    } else {
+        warn!(expected = confirm, current = plan_id, "Reparaturplan hat sich geändert");
+        Err(RusticGuiError::RepairPlanOutdated { plan_id: confirm.to_string() })
+    }
+}
+
+fn plan_id(value: impl Hash) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Repariert den Index (oder plant die Reparatur).
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `read_all` - Alle Pack-Header lesen und den Index komplett neu aufbauen
+/// * `confirm` - Plan-ID eines vorherigen Dry-Runs; `None` = nur planen
+/// * `on_progress` - Callback für Fortschrittsmeldungen
+///
+/// # Returns
+/// Der (ausgeführte) Reparaturplan
+///
+/// # Errors
+/// `RepairPlanOutdated`, wenn sich der Plan seit dem Dry-Run geändert hat
+pub fn repair_index<F>(
+    repository_path: &str,
+    password: &str,
+    read_all: bool,
+    confirm: Option<&str>,
+    on_progress: F,
+) -> Result<RepairIndexPlanDto, RusticGuiError>
+where
+    F: Fn(RepairProgress) + Send + Sync + 'static,
+{
+    info!(repo = repository_path, read_all, apply = confirm.is_some(), "Index-Reparatur");
+    let reporter = RepairReporter {
+        callback: Arc::new(on_progress),
+        applying: Arc::new(AtomicBool::new(false)),
+    };
+    let (repo, backends) = open_repository(repository_path, password, &reporter)?;
+
+    reporter.report("Liste Pack-Dateien...", 0, None);
+    let packs = backends
+        .repository()
+        .list_with_size(FileType::Pack)
+        .map_err(|e| rustic_error("Pack-Dateien auflisten", e))?
+        .into_iter()
+        .map(|(id, size)| (PackId::from(id), size))
+        .collect();
+
+    let index_total =
+        repo.list::<IndexId>().map_err(|e| rustic_error("Index auflisten", e))?.count();
+    let index_files =
+        repo.stream_files::<IndexFile>().map_err(|e| rustic_error("Index lesen", e))?;
+    let mut planner = IndexPlanner::new(packs, read_all);
+    for (done, item) in index_files.enumerate() {
+        reporter.report("Lese Index...", done as u64, Some(index_total as u64));
+        let (index_id, index) = item.map_err(|e| rustic_error("Index lesen", e))?;
+        planner.add_index(index_id, index);
+    }
+    let mut plan = planner.finish();
+
+    if let Some(confirm) = confirm {
+        confirm_plan(&plan.plan_id, confirm)?;
+        if plan.index_files_changed > 0 || plan.packs_to_read > 0 {
+            reporter.applying.store(true, Ordering::Relaxed);
+            repo.repair_index(&RepairIndexOptions::default().read_all(read_all), false)
+                .map_err(|e| rustic_error("Index-Reparatur", e))?;
+        }
+        plan.dry_run = false;
+        info!(
+            changed = plan.index_files_changed,
+            packs_read = plan.packs_to_read,
+            "Index repariert"
+        );
+    }
+    Ok(plan)
+}
+
+/// Berechnet den Plan der Index-Reparatur nach den Regeln von rustic_core
+struct IndexPlanner {
+    /// Noch nicht im Index gefundene Packs im Backend (mit Größe)
+    packs: HashMap<PackId, u32>,
+    seen: HashSet<PackId>,
+    read_all: bool,
+    index_files: u64,
+    changed: Vec<IndexId>,
+    removed: Vec<PackId>,
+    to_read: Vec<PackId>,
+    plan: RepairIndexPlanDto,
+}
+
+impl IndexPlanner {
+    fn new(packs: HashMap<PackId, u32>, read_all: bool) -> Self {
+        Self {
+            packs,
+            seen: HashSet::new(),
+            read_all,
+            index_files: 0,
+            changed: Vec::new(),
+            removed: Vec::new(),
+            to_read: Vec::new(),
+            plan: RepairIndexPlanDto::default(),
+        }
+    }
+
+    fn add_index(&mut self, index_id: IndexId, index: IndexFile) {
+        self.index_files += 1;
+        let mut changed = false;
+        for pack in index.packs.iter().chain(&index.packs_to_delete) {
+            match self.packs.remove(&pack.id) {
+                // fehlt im Backend oder steht bereits in einer anderen Index-Datei
+                None => {
+                    if self.seen.contains(&pack.id) {
+                        self.plan.packs_duplicate += 1;
+                    } else {
+                        self.plan.packs_missing += 1;
+                    }
+                    self.removed.push(pack.id);
+                    changed = true;
+                }
+                Some(size) => {
+                    self.seen.insert(pack.id);
+                    let mismatch = pack.pack_size() != size;
+                    if mismatch {
+                        self.plan.packs_size_mismatch += 1;
+                    }
+                    if mismatch || self.read_all {
+                        self.to_read.push(pack.id);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        if changed {
+            self.changed.push(index_id);
+        }
+    }
+
+    fn finish(mut self) -> RepairIndexPlanDto {
+        // Packs ohne Index-Eintrag: Header lesen
+        self.plan.packs_unindexed = self.packs.len() as u64;
+        self.to_read.extend(self.packs.into_keys());
+
+        self.changed.sort_unstable();
+        self.removed.sort_unstable();
+        self.to_read.sort_unstable();
+        let mut plan = self.plan;
+        plan.plan_id = plan_id((self.read_all, &self.changed, &self.removed, &self.to_read));
+        plan.dry_run = true;
+        plan.read_all = self.read_all;
+        plan.index_files = self.index_files;
+        plan.index_files_changed = self.changed.len() as u64;
+        plan.packs_to_read = self.to_read.len() as u64;
+        plan
+    }
+}
+
+/// Repariert Snapshots (oder plant die Reparatur).
+///
+/// Dateien mit fehlenden Blobs werden gekürzt und mit `suffix` umbenannt, nicht lesbare
+/// Verzeichnisse leer gespeichert. Die reparierten Snapshots werden neu gespeichert; die
+/// Originale (und nicht reparierbare Snapshots) werden nur mit `delete` entfernt.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `options` - Auswahl der Snapshots, Suffix, Tags und Löschen der Originale
+/// * `confirm` - Plan-ID eines vorherigen Dry-Runs; `None` = nur planen
+/// * `on_progress` - Callback für Fortschrittsmeldungen
+///
+/// # Returns
+/// Der (ausgeführte) Reparaturplan
+///
+/// # Errors
+/// `RepairPlanOutdated`, wenn sich der Plan seit dem Dry-Run geändert hat;
+/// `InvalidConfig` bei ungültigen Tags
+pub fn repair_snapshots<F>(
+    repository_path: &str,
+    password: &str,
+    options: &RepairSnapshotsOptionsDto,
+    confirm: Option<&str>,
+    on_progress: F,
+) -> Result<RepairSnapshotsPlanDto, RusticGuiError>
+where
+    F: Fn(RepairProgress) + Send + Sync + 'static,
+{
+    info!(
+        repo = repository_path,
+        snapshots = options.snapshot_ids.len(),
+        delete = options.delete,
+        apply = confirm.is_some(),
+        "Snapshot-Reparatur"
+    );
+    // Tags vorab prüfen, damit schon der Dry-Run ungültige Tags ablehnt
+    let tags = if options.tags.is_empty() {
+        Vec::new()
+    } else {
+        vec![
+            options
+                .tags
+                .join(",")
+                .parse::<StringList>()
+                .map_err(|_| RusticGuiError::InvalidConfig { field: "tags".to_string() })?,
+        ]
+    };
+
+    let reporter = RepairReporter {
+        callback: Arc::new(on_progress),
+        applying: Arc::new(AtomicBool::new(false)),
+    };
+    let (repo, _) = open_repository(repository_path, password, &reporter)?;
+
+    let snapshots = if options.snapshot_ids.is_empty() {
+        repo.get_all_snapshots()
+    } else {
+        repo.get_snapshots(&options.snapshot_ids)
+    }
+    .map_err(|e| rustic_error("Snapshots laden", e))?;
+    let repo = repo.to_indexed().map_err(|e| rustic_error("Repository-Indexierung", e))?;
+
+    let mut walker = TreeWalker { repo: &repo, memo: HashMap::new() };
+    let mut plan = RepairSnapshotsPlanDto {
+        dry_run: true,
+        snapshots_checked: snapshots.len() as u64,
+        ..Default::default()
+    };
+    let mut damaged: Vec<SnapshotFile> = Vec::new();
+    for (done, snap) in snapshots.into_iter().enumerate() {
+        reporter.report("Prüfe Snapshots...", done as u64, Some(plan.snapshots_checked));
+        let (changed, damage) = walker.walk(Some(snap.tree));
+        let action = match changed {
+            Changed::None => continue,
+            // Ohne `delete` lässt rustic_core Snapshots mit unlesbarem Root-Tree unverändert
+            Changed::This if options.delete => SnapshotRepairAction::Remove,
+            Changed::This => SnapshotRepairAction::Unrepairable,
+            Changed::SubTree => SnapshotRepairAction::Repair,
+        };
+        warn!(snapshot = %snap.id, ?action, "Snapshot beschädigt");
+        plan.snapshots.push(SnapshotRepairDto {
+            snapshot_id: snap.id.to_string(),
+            time: snap.time.to_rfc3339(),
+            hostname: snap.hostname.clone(),
+            paths: snap.paths.iter().cloned().collect(),
+            action,
+            damaged_trees: damage.trees,
+            damaged_files: damage.files,
+            corrected_sizes: damage.sizes,
+        });
+        damaged.push(snap);
+    }
+    if options.delete {
+        plan.snapshots_removed = plan.snapshots.len() as u64;
+    }
+
+    let mut planned: Vec<(&str, SnapshotRepairAction)> =
+        plan.snapshots.iter().map(|s| (s.snapshot_id.as_str(), s.action)).collect();
+    planned.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    plan.plan_id = plan_id((&planned, options.delete, &options.suffix, &options.tags));
+
+    if let Some(confirm) = confirm {
+        confirm_plan(&plan.plan_id, confirm)?;
+        if !damaged.is_empty() {
+            let opts = RepairSnapshotsOptions::default()
+                .delete(options.delete)
+                .suffix(options.suffix.clone())
+                .tag(tags);
+            reporter.applying.store(true, Ordering::Relaxed);
+            repo.repair_snapshots(&opts, damaged, false)
+                .map_err(|e| rustic_error("Snapshot-Reparatur", e))?;
+        }
+        plan.dry_run = false;
+        info!(
+            repaired = plan.snapshots.len(),
+            removed = plan.snapshots_removed,
+            "Snapshots repariert"
+        );
+    }
+    Ok(plan)
+}
+
+/// Änderung eines Trees bei der Reparatur (wie `Changed` in rustic_core)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Changed {
+    /// Tree intakt
+    None,
+    /// Tree selbst nicht lesbar
+    This,
+    /// Inhalt des Trees wird korrigiert
+    SubTree,
+}
+
+/// Gefundene Schäden unterhalb eines Trees
+#[derive(Debug, Clone, Copy, Default)]
+struct Damage {
+    trees: u64,
+    files: u64,
+    sizes: u64,
+}
+
+impl Damage {
+    fn add(&mut self, other: Damage) {
+        self.trees += other.trees;
+        self.files += other.files;
+        self.sizes += other.sizes;
+    }
+}
+
+/// Simuliert `repair_tree` von rustic_core ohne zu schreiben
+struct TreeWalker<'a, P, S> {
+    repo: &'a Repository<P, S>,
+    memo: HashMap<TreeId, (Changed, Damage)>,
+}
+
+impl<P, S: IndexedFull> TreeWalker<'_, P, S> {
+    fn walk(&mut self, id: Option<TreeId>) -> (Changed, Damage) {
+        let Some(id) = id else {
+            return (Changed::This, Damage { trees: 1, ..Default::default() });
+        };
+        if let Some(result) = self.memo.get(&id) {
+            return *result;
+        }
+
+        let mut damage = Damage::default();
+        let result = match self.repo.get_tree(&id) {
+            Err(e) => {
+                warn!(tree = %id, "Tree nicht lesbar: {e}");
+                damage.trees += 1;
+                (Changed::This, damage)
+            }
+            Ok(tree) => {
+                let mut changed = Changed::None;
+                for node in tree.nodes {
+                    if node.is_file() {
+                        let mut missing = false;
+                        let mut size = 0u64;
+                        for blob in node.content.iter().flatten() {
+                            match self.repo.get_index_entry(blob) {
+                                Ok(entry) => size += u64::from(entry.data_length()),
+                                Err(_) => missing = true,
+                            }
+                        }
+                        if missing {
+                            damage.files += 1;
+                            changed = Changed::SubTree;
+                        } else if size != node.meta.size {
+                            damage.sizes += 1;
+                            changed = Changed::SubTree;
+                        }
+                    } else if node.is_dir() {
+                        let (subtree_changed, subtree_damage) = self.walk(node.subtree);
+                        damage.add(subtree_damage);
+                        if subtree_changed != Changed::None {
+                            changed = Changed::SubTree;
+                        }
+                    }
+                }
+                (changed, damage)
+            }
+        };
+        self.memo.insert(id, result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustic_core::Id;
+    use rustic_core::repofile::IndexPack;
+
+    fn pack(id: PackId, size: u32) -> IndexPack {
+        IndexPack { id, size: Some(size), ..Default::default() }
+    }
+
+    #[test]
+    fn test_index_planner() {
+        let ok = PackId::from(Id::random());
+        let resized = PackId::from(Id::random());
+        let missing = PackId::from(Id::random());
+        let unindexed = PackId::from(Id::random());
+        let packs = HashMap::from([(ok, 100), (resized, 200), (unindexed, 300)]);
+
+        let first =
+            || IndexFile { packs: vec![pack(ok, 100), pack(resized, 150)], ..Default::default() };
+        let second =
+            IndexFile { packs: vec![pack(ok, 100), pack(missing, 50)], ..Default::default() };
+        let untouched = IndexFile::default();
+
+        let mut planner = IndexPlanner::new(packs.clone(), false);
+        planner.add_index(IndexId::from(Id::random()), first());
+        planner.add_index(IndexId::from(Id::random()), second);
+        planner.add_index(IndexId::from(Id::random()), untouched);
+        let plan = planner.finish();
+
+        assert!(plan.dry_run);
+        assert_eq!(plan.index_files, 3);
+        assert_eq!(plan.index_files_changed, 2);
+        assert_eq!(plan.packs_size_mismatch, 1);
+        assert_eq!(plan.packs_duplicate, 1);
+        assert_eq!(plan.packs_missing, 1);
+        assert_eq!(plan.packs_unindexed, 1);
+        assert_eq!(plan.packs_to_read, 2);
+
+        // read_all liest alle vorhandenen Packs und ergibt einen anderen Plan
+        let mut planner = IndexPlanner::new(packs, true);
+        planner.add_index(IndexId::from(Id::random()), first());
+        let full = planner.finish();
+        assert_eq!(full.packs_to_read, 3);
+        assert_ne!(full.plan_id, plan.plan_id);
+    }
+
+    #[test]
+    fn test_confirm_plan() {
+        assert!(confirm_plan("abc", "abc").is_ok());
+        assert!(matches!(
+            confirm_plan("abc", "def"),
+            Err(RusticGuiError::RepairPlanOutdated { plan_id }) if plan_id == "def"
+        ));
+    }
+}
@@ -6,7 +6,10 @@ use crate::{
     types::RepositoryDto,
 };
 use rustic_backend::BackendOptions;
-use rustic_core::{ConfigOptions, KeyOptions, NoProgressBars, Repository, RepositoryOptions};
+use rustic_core::{
+    ConfigOptions, KeyOptions, NoProgressBars, Repository, RepositoryOptions,
+    repofile::{BlobType, FileType, IndexId, SnapshotId},
+};
 use std::path::Path;
 
 /// Einfache Struktur um Repository-Informationen zurückzugeben
@@ -293,18 +296,17 @@ pub fn check_repository(path: &str, password: &str) -> Result<RepositoryDto> {
                 "Repository-Check erfolgreich: {} Snapshots gefunden",
                 opened.snapshot_count
             );
-            (crate::types::RepositoryStatus::Healthy, opened.snapshot_count, opened.total_size)
-        }
-        Err(e) => {
-            tracing::warn!("Repository-Check fehlgeschlagen: {}", e);
-            // Unterscheide zwischen verschiedenen Fehlerarten
-            let err_msg = format!("{:?}", e);
-            let status = if err_msg.contains("password") || err_msg.contains("decrypt") {
+            // Lock-Dateien (z.B. von restic) werden als `Locked` gemeldet
+            let status = if crate::rustic::locks::has_locks(path) {
                 crate::types::RepositoryStatus::Locked
             } else {
-                crate::types::RepositoryStatus::Unavailable
+                crate::types::RepositoryStatus::Healthy
             };
-            (status, 0, 0)
+            (status, opened.snapshot_count, opened.total_size)
+        }
+        Err(e) => {
+            tracing::warn!("Repository-Check fehlgeschlagen: {}", e);
+            (crate::types::RepositoryStatus::Unavailable, 0, 0)
         }
     };
 
@@ -563,63 +565,140 @@ mod tests {
         assert_eq!(dto.snapshot_count, 5);
         assert_eq!(dto.total_size, 1024);
     }
+
+    #[test]
+    fn test_stats_ratios() {
+        assert_eq!(compression_ratio(0, 0), 1.0);
+        assert_eq!(compression_ratio(250, 1000), 0.25);
+
+        assert_eq!(deduplication_ratio(100, 0), 0.0);
+        assert_eq!(deduplication_ratio(300, 1200), 0.75);
+        // Mehr eindeutige Daten als verarbeitet (z.B. Snapshots ohne Summary)
+        assert_eq!(deduplication_ratio(500, 100), 0.0);
+    }
 }
 
 /// Holt detaillierte Statistiken für ein geöffnetes Repository
 ///
 /// M4.3: Repository-Statistiken
 ///
+/// Größen und Blob-Zahlen stammen aus den Index-Dateien, Datei-Anzahlen und die
+/// Gesamtgröße aus dem Listing des Backends. Blobs in Packs, die zum Löschen markiert
+/// sind, werden nicht mitgezählt.
+///
 /// # Arguments
 /// * `repo` - Geöffnetes Repository
 ///
 /// # Returns
 /// RepositoryStatsDto mit Statistiken
+///
+/// # Errors
+/// `RusticError`, wenn Snapshots, Indizes oder das Datei-Listing nicht gelesen werden können
 pub fn get_repository_stats(
     repo: &Repository<NoProgressBars, rustic_core::OpenStatus>,
 ) -> Result<crate::types::RepositoryStatsDto> {
-    tracing::debug!("Sammle Repository-Statistiken (vereinfachte Version)");
-
-    // Hole alle Snapshots
-    let snapshots = repo.get_all_snapshots().map_err(|e| {
-        tracing::error!("Snapshots laden fehlgeschlagen: {:?}", e);
-        crate::error::RusticGuiError::RusticError {
-            message: format!("Snapshots laden fehlgeschlagen: {}", e),
-        }
-    })?;
-    let snapshot_count = snapshots.len() as u64;
+    tracing::debug!("Sammle Repository-Statistiken");
+    let rustic_error = |what: &str, e: Box<rustic_core::RusticError>| {
+        tracing::error!("{} fehlgeschlagen: {:?}", what, e);
+        crate::error::RusticGuiError::RusticError { message: format!("{what} fehlgeschlagen: {e}") }
+    };
 
-    // TODO Phase 3: Implementiere mit neuer rustic_core 0.8.0 API
-    // Die alte API mit PackFile::TYPE und iter_type ist nicht mehr verfügbar
-    // Neue API-Dokumentation prüfen: https://docs.rs/rustic_core/0.8.0
-    // Temporär verwenden wir Platzhalter-Werte
+    let snapshots = repo.get_all_snapshots().map_err(|e| rustic_error("Snapshots laden", e))?;
+    let files = repo.infos_files().map_err(|e| rustic_error("Datei-Listing", e))?;
+    let index = repo.infos_index().map_err(|e| rustic_error("Index lesen", e))?;
 
-    tracing::warn!("Repository-Statistiken verwenden Platzhalter-Werte (API-Migration ausstehend)");
+    let file_count = |tpe: FileType| {
+        files.repo.iter().filter(|info| info.tpe == tpe).map(|info| info.count).sum::<u64>()
+    };
+    let total_size =
+        files.repo.iter().chain(files.repo_hot.iter().flatten()).map(|info| info.size).sum::<u64>();
+
+    let blob_stats = |blob_type: BlobType| {
+        let mut stats = crate::types::BlobTypeStatsDto::default();
+        for blob in index.blobs.iter().filter(|blob| blob.blob_type == blob_type) {
+            stats.blob_count += blob.count;
+            stats.packed_size += blob.size;
+            stats.data_size += blob.data_size;
+        }
+        stats.pack_count = index
+            .packs
+            .iter()
+            .filter(|pack| pack.blob_type == blob_type)
+            .map(|pack| pack.count)
+            .sum();
+        stats
+    };
+    let tree_blobs = blob_stats(BlobType::Tree);
+    let data_blobs = blob_stats(BlobType::Data);
 
-    let index_count = 0u64; // Placeholder
-    let pack_count = 0u64; // Placeholder
-    let total_size = 0u64; // Placeholder
-    let data_size = 0u64; // Placeholder
-    let unique_blobs = 0u64; // Placeholder
-    let compression_ratio = 1.0; // Placeholder
-    let deduplication_ratio = 0.0; // Placeholder
+    let snapshot_data_size = snapshots
+        .iter()
+        .filter_map(|snap| snap.summary.as_ref())
+        .map(|summary| summary.total_bytes_processed)
+        .sum::<u64>();
 
     let stats = crate::types::RepositoryStatsDto {
-        snapshot_count,
-        index_count,
-        pack_count,
+        snapshot_count: snapshots.len() as u64,
+        index_count: file_count(FileType::Index),
+        pack_count: file_count(FileType::Pack),
         total_size,
-        data_size,
-        compression_ratio,
-        deduplication_ratio,
-        unique_blobs,
+        data_size: tree_blobs.data_size + data_blobs.data_size,
+        compression_ratio: compression_ratio(
+            tree_blobs.packed_size + data_blobs.packed_size,
+            tree_blobs.data_size + data_blobs.data_size,
+        ),
+        deduplication_ratio: deduplication_ratio(data_blobs.data_size, snapshot_data_size),
+        unique_blobs: tree_blobs.blob_count + data_blobs.blob_count,
+        snapshot_data_size,
+        tree_blobs,
+        data_blobs,
+        computed_at: chrono::Utc::now().to_rfc3339(),
     };
 
     tracing::debug!(
         "Statistiken gesammelt: {} Snapshots, {} Packs, {:.2} MB gesamt",
-        snapshot_count,
-        pack_count,
-        total_size as f64 / 1024.0 / 1024.0
+        stats.snapshot_count,
+        stats.pack_count,
+        stats.total_size as f64 / 1024.0 / 1024.0
     );
 
     Ok(stats)
 }
+
+/// Fingerprint des Repository-Inhalts für den Statistik-Cache
+///
+/// Ändert sich, sobald Index- oder Snapshot-Dateien hinzukommen oder wegfallen (Backup,
+/// Forget, Prune). Das Listing ist deutlich günstiger als das Lesen aller Indizes.
+///
+/// # Errors
+/// `RusticError`, wenn das Backend nicht gelistet werden kann
+pub fn repository_stats_fingerprint(
+    repo: &Repository<NoProgressBars, rustic_core::OpenStatus>,
+) -> Result<u64> {
+    use std::hash::{Hash, Hasher};
+
+    let list_error = |e: Box<rustic_core::RusticError>| crate::error::RusticGuiError::RusticError {
+        message: format!("Datei-Listing fehlgeschlagen: {e}"),
+    };
+    let mut ids: Vec<String> =
+        repo.list::<IndexId>().map_err(list_error)?.map(|id| id.to_string()).collect();
+    ids.extend(repo.list::<SnapshotId>().map_err(list_error)?.map(|id| format!("s{id}")));
+    ids.sort_unstable();
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    ids.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Verhältnis gespeicherte Größe / Rohgröße (1.0 = keine Kompression)
+fn compression_ratio(packed_size: u64, data_size: u64) -> f64 {
+    if data_size == 0 { 1.0 } else { packed_size as f64 / data_size as f64 }
+}
+
+/// Anteil der in Snapshots verarbeiteten Daten, der durch Deduplizierung eingespart wurde
+fn deduplication_ratio(unique_data_size: u64, snapshot_data_size: u64) -> f64 {
+    if snapshot_data_size == 0 {
+        return 0.0;
+    }
+    (1.0 - unique_data_size as f64 / snapshot_data_size as f64).clamp(0.0, 1.0)
+}
@@ -1,27 +1,43 @@
 use crate::error::RusticGuiError;
-use crate::types::{FileTreeNode, RestoreOptionsDto, RestoreProgress};
+use crate::rustic::progress::{GuiProgressBars, ProgressKind};
+use crate::types::{
+    FileNodeType, FileTreeNode, OverwritePolicy, ProgressInfo, RestoreLayout, RestoreOptionsDto,
+    RestorePlanDto, RestoreProgress,
+};
+use chrono::{DateTime, Local};
+use parking_lot::Mutex;
 use rustic_backend::BackendOptions;
 use rustic_core::{
-    LocalDestination, LsOptions, NoProgressBars, Repository, RepositoryOptions, RestoreOptions,
-    repofile::SnapshotFile,
+    ErrorKind, IndexedTree, LocalDestination, LsOptions, ProgressBars, Repository,
+    RepositoryOptions, RestoreOptions, RestorePlan, RusticError, RusticResult,
+    repofile::{Node, NodeType, SnapshotFile},
 };
-use std::collections::HashMap;
-use tauri::{AppHandle, Emitter};
-use tracing::{error, info};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 
-/// Lädt die Dateistruktur eines Snapshots.
+/// Lädt eine Ebene der Dateistruktur eines Snapshots.
 ///
-/// Erstellt eine hierarchische Baumstruktur aller Dateien und Verzeichnisse
-/// in einem Snapshot für die Anzeige im File-Browser.
+/// Liest die Tree-Blobs des Snapshots und liefert den Knoten unter `path` mit seinen
+/// direkten Kindern (Lazy-Loading: Unterverzeichnisse haben `children: None` und werden
+/// bei Bedarf mit ihrem eigenen Pfad nachgeladen).
 ///
 /// # Arguments
 /// * `repository_path` - Pfad zum Repository
 /// * `password` - Repository-Passwort
 /// * `snapshot_id` - ID des Snapshots
-/// * `path` - Optionaler Pfad innerhalb des Snapshots (für Lazy-Loading)
+/// * `path` - Optionaler Pfad innerhalb des Snapshots (None oder "/" = Wurzel)
 ///
 /// # Returns
-/// Wurzelknoten des Dateibaums
+/// Knoten unter `path`; bei Verzeichnissen mit den direkten Kindern
+/// (Verzeichnisse zuerst, dann alphabetisch)
+///
+/// # Errors
+/// `SnapshotNotFound` bzw. `PathNotFound`, wenn Snapshot oder Pfad nicht existieren
 pub async fn get_file_tree(
     repository_path: &str,
     password: &str,
@@ -46,102 +62,531 @@ pub async fn get_file_tree(
         .map_err(|e| {
             error!(?e, "Fehler beim Öffnen des Repositories");
             RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .to_indexed_ids()
+        .map_err(|e| {
+            error!(?e, "Repository-Indexierung fehlgeschlagen");
+            RusticGuiError::Internal(format!("Repository-Indexierung fehlgeschlagen: {e}"))
         })?;
 
     // Snapshot laden
-    let snaps = repo.get_snapshots(&[snapshot_id]).map_err(|e| {
-        RusticGuiError::Internal(format!("Snapshot konnte nicht geladen werden: {e}"))
-    })?;
-    let snapshot = snaps
-        .into_iter()
-        .next()
-        .ok_or_else(|| RusticGuiError::SnapshotNotFound { id: snapshot_id.to_string() })?;
+    let snapshot = repo
+        .get_snapshot_from_str(snapshot_id, |_| true)
+        .map_err(|_| RusticGuiError::SnapshotNotFound { id: snapshot_id.to_string() })?;
 
-    // Erstelle Baumstruktur aus den Pfaden des Snapshots
-    let mut _root = FileTreeNode {
-        name: "/".to_string(),
-        path: "/".to_string(),
-        is_directory: true,
-        size: Some(0),
-        modified: None,
-        children: Some(Vec::new()),
+    // Knoten für den angeforderten Pfad suchen (Pfade im Snapshot sind relativ zur Wurzel)
+    let relative = path.unwrap_or("/").trim_matches('/');
+    let display_path = format!("/{relative}");
+    let node = repo
+        .node_from_snapshot_and_path(&snapshot, relative)
+        .map_err(|_| RusticGuiError::PathNotFound { path: display_path.clone() })?;
+
+    let mut result = node_to_file_tree_node(&node, &display_path);
+    if relative.is_empty() {
+        result.name = "/".to_string();
+    }
+
+    // Bei Verzeichnissen nur die direkten Kinder laden
+    if let Some(subtree) = node.subtree {
+        let tree = repo.get_tree(&subtree).map_err(|e| {
+            RusticGuiError::Internal(format!("Verzeichnis konnte nicht gelesen werden: {e}"))
+        })?;
+
+        let mut children: Vec<FileTreeNode> = tree
+            .nodes
+            .iter()
+            .map(|child| {
+                let name = child.name().to_string_lossy().to_string();
+                let child_path = if relative.is_empty() {
+                    format!("/{name}")
+                } else {
+                    format!("{display_path}/{name}")
+                };
+                node_to_file_tree_node(child, &child_path)
+            })
+            .collect();
+        children.sort_by(|a, b| b.is_directory.cmp(&a.is_directory).then(a.name.cmp(&b.name)));
+        result.children = Some(children);
+    }
+
+    Ok(result)
+}
+
+/// Konvertiert einen rustic-Knoten in einen `FileTreeNode` (ohne Kinder)
+fn node_to_file_tree_node(node: &Node, path: &str) -> FileTreeNode {
+    let (node_type, link_target, device) = match &node.node_type {
+        NodeType::File => (FileNodeType::File, None, None),
+        NodeType::Dir => (FileNodeType::Dir, None, None),
+        NodeType::Symlink { .. } => (
+            FileNodeType::Symlink,
+            Some(node.node_type.to_link().to_string_lossy().to_string()),
+            None,
+        ),
+        NodeType::Dev { device } => (FileNodeType::Dev, None, Some(*device)),
+        NodeType::Chardev { device } => (FileNodeType::Chardev, None, Some(*device)),
+        NodeType::Fifo => (FileNodeType::Fifo, None, None),
+        NodeType::Socket => (FileNodeType::Socket, None, None),
     };
+    let is_directory = node.is_dir();
+    let meta = &node.meta;
+
+    FileTreeNode {
+        name: node.name().to_string_lossy().to_string(),
+        path: path.to_string(),
+        is_directory,
+        size: (!is_directory).then_some(meta.size),
+        modified: meta.mtime.map(|t| t.to_rfc3339()),
+        children: None,
+        node_type,
+        mode: meta.mode,
+        owner: meta.user.clone().or_else(|| meta.uid.map(|uid| uid.to_string())),
+        group: meta.group.clone().or_else(|| meta.gid.map(|gid| gid.to_string())),
+        link_target,
+        device,
+    }
+}
+
+/// Präfix des Byte-Fortschritts, den rustic_core beim Schreiben der Dateiinhalte startet
+const RESTORE_CONTENTS_PREFIX: &str = "restoring file contents...";
 
-    // Gruppiere Pfade nach Verzeichnissen
-    let mut path_map: HashMap<String, Vec<String>> = HashMap::new();
+/// Mindestabstand zwischen zwei Progress-Events beim Sammeln und beim Setzen der Metadaten
+const NODE_UPDATE_INTERVAL: Duration = Duration::from_millis(250);
 
-    for snap_path in &snapshot.paths {
-        let path_str = snap_path.to_string();
-        let parts: Vec<&str> = path_str.split('/').collect();
+/// Höchstzahl Dateien je Abschnitt beim Schreiben der Inhalte
+const RESTORE_CHUNK_FILES: usize = 5_000;
 
-        for i in 0..parts.len() {
-            let parent_path =
-                if i == 0 { "/".to_string() } else { format!("/{}", parts[0..i].join("/")) };
-            let item_name = parts[i].to_string();
+/// Höchstgröße je Abschnitt beim Schreiben der Inhalte (ein Abbruch greift spätestens danach)
+const RESTORE_CHUNK_BYTES: u64 = 512 * 1024 * 1024;
 
-            path_map.entry(parent_path).or_insert_with(Vec::new).push(item_name);
+/// Dateien des Snapshots in der Reihenfolge des Node-Streamers
+#[derive(Default)]
+struct RestoreFileList {
+    /// Kumulierte Bytes bis einschließlich der Datei und deren Pfad
+    files: Vec<(u64, String)>,
+    /// Gesamtgröße aller Dateien
+    total_bytes: u64,
+}
+
+/// Übersetzt den Fortschritt von rustic_core in `RestoreProgress`-Events.
+///
+/// rustic_core meldet beim Wiederherstellen nur geschriebene Bytes. Die Dateiliste wird
+/// beim Erstellen des Restore-Plans aus dem Node-Streamer gesammelt; aus dem Byte-Stand
+/// werden Anzahl wiederhergestellter Dateien und aktuelle Datei abgeleitet. Beim Setzen
+/// der Metadaten wird die tatsächlich bearbeitete Datei gemeldet.
+struct RestoreProgressTracker<F> {
+    on_progress: F,
+    file_list: Mutex<RestoreFileList>,
+    /// Zu schreibende Bytes laut Restore-Plan
+    restore_size: AtomicU64,
+    /// Geschriebene Bytes der bereits abgeschlossenen Abschnitte
+    chunk_offset: AtomicU64,
+    last_node_update: Mutex<Option<Instant>>,
+}
+
+impl<F> RestoreProgressTracker<F>
+where
+    F: Fn(RestoreProgress) + Send + Sync + 'static,
+{
+    fn new(on_progress: F) -> Self {
+        Self {
+            on_progress,
+            file_list: Mutex::new(RestoreFileList::default()),
+            restore_size: AtomicU64::new(0),
+            chunk_offset: AtomicU64::new(0),
+            last_node_update: Mutex::new(None),
         }
     }
 
-    // Baue rekursiv den Baum auf
-    fn build_tree(
-        path: &str,
-        path_map: &HashMap<String, Vec<String>>,
-        snapshot: &SnapshotFile,
-    ) -> FileTreeNode {
-        let children = path_map.get(path).cloned().unwrap_or_default();
-        let mut child_nodes = Vec::new();
+    /// Anzahl der Dateien im Restore
+    fn total_files(&self) -> u64 {
+        self.file_list.lock().files.len() as u64
+    }
 
-        for child_name in children {
-            let child_path = if path == "/" {
-                format!("/{}", child_name)
-            } else {
-                format!("{}/{}", path, child_name)
-            };
+    /// Prüft, ob seit dem letzten knotenbasierten Event genug Zeit vergangen ist
+    fn node_update_due(&self) -> bool {
+        let mut last = self.last_node_update.lock();
+        if last.is_some_and(|t| t.elapsed() < NODE_UPDATE_INTERVAL) {
+            return false;
+        }
+        *last = Some(Instant::now());
+        true
+    }
 
-            // Prüfe ob es ein Verzeichnis ist (hat Kinder)
-            let is_dir = path_map.contains_key(&child_path);
-
-            let node = if is_dir {
-                FileTreeNode {
-                    name: child_name,
-                    path: child_path.clone(),
-                    is_directory: true,
-                    size: Some(0),
-                    modified: None,
-                    children: Some(
-                        build_tree(&child_path, path_map, snapshot).children.unwrap_or_default(),
-                    ),
-                }
-            } else {
-                // Für Dateien: Versuche Größe und Modified aus Summary zu bekommen
-                // Dies ist eine Vereinfachung - in der Realität bräuchte man Index-Daten
-                FileTreeNode {
-                    name: child_name,
-                    path: child_path,
-                    is_directory: false,
-                    size: Some(1024), // Placeholder
-                    modified: Some(snapshot.time.to_rfc3339()),
-                    children: None,
+    /// Nimmt einen Knoten beim Erstellen des Restore-Plans auf
+    fn collect(&self, path: &Path, node: &Node) {
+        let display_path = format!("/{}", path.display());
+        if node.is_file() {
+            let mut list = self.file_list.lock();
+            list.total_bytes += node.meta.size;
+            let cumulative = list.total_bytes;
+            list.files.push((cumulative, display_path.clone()));
+        }
+
+        if self.node_update_due() {
+            let files = self.total_files();
+            self.emit(
+                "Dateiinformationen werden gesammelt...",
+                0,
+                files,
+                0,
+                Some(display_path),
+                0.0,
+            );
+        }
+    }
+
+    /// Meldet den Byte-Stand beim Schreiben der Dateiinhalte
+    fn contents(&self, bytes_restored: u64) {
+        let restore_size = self.restore_size.load(Ordering::Relaxed);
+        let fraction = if restore_size == 0 {
+            1.0
+        } else {
+            (bytes_restored as f64 / restore_size as f64).min(1.0)
+        };
+
+        let list = self.file_list.lock();
+        let scaled = (list.total_bytes as f64 * fraction) as u64;
+        let files_restored = list.files.partition_point(|(end, _)| *end <= scaled);
+        let current_file = list.files.get(files_restored).map(|(_, path)| path.clone());
+        let total = list.files.len() as u64;
+        drop(list);
+
+        self.emit(
+            "Dateiinhalte werden wiederhergestellt...",
+            files_restored as u64,
+            total,
+            bytes_restored,
+            current_file,
+            (fraction * 100.0) as f32,
+        );
+    }
+
+    /// Meldet den Knoten, dessen Metadaten gerade gesetzt werden
+    fn metadata(&self, path: &Path) {
+        if self.node_update_due() {
+            let total = self.total_files();
+            let bytes = self.restore_size.load(Ordering::Relaxed);
+            let current_file = Some(format!("/{}", path.display()));
+            self.emit("Metadaten werden gesetzt...", total, total, bytes, current_file, 100.0);
+        }
+    }
+
+    /// Erstellt den abschließenden Fortschritt eines erfolgreichen Restores
+    fn finished(&self) -> RestoreProgress {
+        let total = self.total_files();
+        RestoreProgress {
+            base: ProgressInfo {
+                current: total,
+                total,
+                message: Some("Restore abgeschlossen".to_string()),
+                percentage: Some(100.0),
+            },
+            files_restored: total,
+            bytes_restored: self.restore_size.load(Ordering::Relaxed),
+            current_file: None,
+        }
+    }
+
+    fn emit(
+        &self,
+        message: &str,
+        files_restored: u64,
+        total: u64,
+        bytes_restored: u64,
+        current_file: Option<String>,
+        percentage: f32,
+    ) {
+        (self.on_progress)(RestoreProgress {
+            base: ProgressInfo {
+                current: files_restored,
+                total,
+                message: Some(message.to_string()),
+                percentage: Some(percentage),
+            },
+            files_restored,
+            bytes_restored,
+            current_file,
+        });
+    }
+}
+
+/// Reicht die Knoten eines Node-Streamers durch und meldet sie an `on_node`.
+///
+/// Nach einem Abbruch liefert der Iterator einen Fehler, womit rustic_core das Erstellen
+/// des Restore-Plans bzw. das Setzen der Metadaten beendet.
+fn track_nodes<'a, I>(
+    nodes: I,
+    cancel: &'a CancellationToken,
+    on_node: impl Fn(&Path, &Node) + 'a,
+) -> impl Iterator<Item = RusticResult<(PathBuf, Node)>> + 'a
+where
+    I: Iterator<Item = RusticResult<(PathBuf, Node)>> + 'a,
+{
+    nodes.map(move |item| {
+        if cancel.is_cancelled() {
+            return Err(RusticError::new(ErrorKind::Other, "Restore wurde abgebrochen"));
+        }
+        if let Ok((path, node)) = &item {
+            on_node(path, node);
+        }
+        item
+    })
+}
+
+/// Ausgewählter Pfad eines selektiven Restores
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RestoreSelection {
+    /// Pfad im Snapshot (relativ zur Wurzel)
+    source: PathBuf,
+    /// Pfad im Ziel (relativ zum Zielverzeichnis)
+    target: PathBuf,
+}
+
+/// Normalisiert die Auswahl eines Restores und bestimmt die Zielpfade.
+///
+/// Leere und doppelte Einträge sowie Pfade innerhalb eines anderen ausgewählten
+/// Verzeichnisses werden entfernt. Die Auswahl ist nach Zielpfad sortiert, wie es der
+/// Node-Streamer von rustic_core erwartet.
+///
+/// # Arguments
+/// * `paths` - Ausgewählte Pfade im Snapshot (z.B. `/home/user/docs`)
+/// * `layout` - Anordnung der Auswahl im Ziel
+///
+/// # Returns
+/// `None`, wenn nichts oder die Wurzel ausgewählt ist (kompletter Snapshot)
+///
+/// # Errors
+/// `RestoreFailed`, wenn bei `RestoreLayout::Relative` zwei Auswahlen denselben Namen haben
+fn plan_selections<'a>(
+    paths: impl IntoIterator<Item = &'a str>,
+    layout: RestoreLayout,
+) -> Result<Option<Vec<RestoreSelection>>, RusticGuiError> {
+    let mut sources = BTreeSet::new();
+    for path in paths {
+        let source: PathBuf =
+            path.split('/').filter(|part| !part.is_empty() && *part != ".").collect();
+        if source.as_os_str().is_empty() {
+            if !path.trim().is_empty() {
+                return Ok(None);
+            }
+            continue;
+        }
+        sources.insert(source);
+    }
+
+    // Sortierung nach Pfadkomponenten: Unterpfade folgen direkt auf ihr Verzeichnis
+    let mut selected: Vec<PathBuf> = Vec::new();
+    for source in sources {
+        if !selected.last().is_some_and(|dir| source.starts_with(dir)) {
+            selected.push(source);
+        }
+    }
+    if selected.is_empty() {
+        return Ok(None);
+    }
+
+    let mut selections = Vec::with_capacity(selected.len());
+    let mut targets = BTreeSet::new();
+    for source in selected {
+        let target = match layout {
+            RestoreLayout::Absolute => source.clone(),
+            RestoreLayout::Relative => source.file_name().map(PathBuf::from).unwrap_or_default(),
+        };
+        if !targets.insert(target.clone()) {
+            return Err(RusticGuiError::RestoreFailed {
+                reason: format!(
+                    "Mehrere ausgewählte Pfade würden nach '{}' wiederhergestellt",
+                    target.display()
+                ),
+            });
+        }
+        selections.push(RestoreSelection { source, target });
+    }
+    selections.sort_by(|a, b| a.target.cmp(&b.target));
+
+    Ok(Some(selections))
+}
+
+type NodeStream<'a> = Box<dyn Iterator<Item = RusticResult<(PathBuf, Node)>> + 'a>;
+
+/// Erstellt den Node-Streamer für einen selektiven Restore in einem Durchlauf.
+///
+/// Jede Auswahl wird mit ihrem Zielpfad gemeldet, Verzeichnisse gefolgt von ihrem
+/// rekursiven Inhalt. Bei `RestoreLayout::Absolute` werden zusätzlich die übergeordneten
+/// Verzeichnisse gemeldet, damit sie mit ihren ursprünglichen Metadaten entstehen.
+///
+/// # Errors
+/// `PathNotFound`, wenn ein ausgewählter Pfad nicht im Snapshot existiert
+fn selection_nodes<'a, P: ProgressBars, S: IndexedTree>(
+    repo: &'a Repository<P, S>,
+    snapshot: &SnapshotFile,
+    selections: &[RestoreSelection],
+    layout: RestoreLayout,
+    ls_opts: &LsOptions,
+) -> Result<NodeStream<'a>, RusticGuiError> {
+    let node_at = |path: &Path| {
+        repo.node_from_snapshot_and_path(snapshot, &path.to_string_lossy())
+            .map_err(|_| RusticGuiError::PathNotFound { path: format!("/{}", path.display()) })
+    };
+
+    // Einträge der obersten Ebene nach Zielpfad sortiert: (Knoten, ob ausgewählt)
+    let mut entries: BTreeMap<PathBuf, (Node, bool)> = BTreeMap::new();
+    for selection in selections {
+        if layout == RestoreLayout::Absolute {
+            for ancestor in selection.source.ancestors().skip(1) {
+                if !ancestor.as_os_str().is_empty() && !entries.contains_key(ancestor) {
+                    entries.insert(ancestor.to_path_buf(), (node_at(ancestor)?, false));
                 }
-            };
+            }
+        }
+        entries.insert(selection.target.clone(), (node_at(&selection.source)?, true));
+    }
+
+    let mut streams: Vec<NodeStream<'a>> = Vec::with_capacity(entries.len());
+    for (target, (node, selected)) in entries {
+        let contents: Option<NodeStream<'a>> = if selected && node.is_dir() {
+            let prefix = target.clone();
+            let nodes = repo
+                .ls(&node, ls_opts)
+                .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?;
+            Some(Box::new(nodes.map(move |item| item.map(|(path, n)| (prefix.join(path), n)))))
+        } else {
+            None
+        };
+        streams.push(Box::new(
+            std::iter::once(Ok((target, node))).chain(contents.into_iter().flatten()),
+        ));
+    }
 
-            child_nodes.push(node);
+    Ok(Box::new(streams.into_iter().flatten()))
+}
+
+/// Klassifiziert die Knoten eines Restores gegenüber dem vorhandenen Ziel.
+///
+/// Wird beim Erstellen des Restore-Plans für jeden Knoten aufgerufen. Übersprungene Pfade
+/// werden gemerkt, damit der zweite Durchlauf (Metadaten) dieselben Knoten auslässt, auch
+/// wenn die Dateiinhalte dann bereits geschrieben sind.
+struct RestorePlanner {
+    target: PathBuf,
+    policy: OverwritePolicy,
+    /// Ob alle Knotenpfade für die Lösch-Vorschau gesammelt werden
+    track_paths: bool,
+    state: Mutex<RestorePlannerState>,
+}
+
+#[derive(Default)]
+struct RestorePlannerState {
+    plan: RestorePlanDto,
+    skipped: HashSet<PathBuf>,
+    paths: HashSet<PathBuf>,
+}
+
+impl RestorePlanner {
+    fn new(target: &Path, policy: OverwritePolicy, track_paths: bool) -> Self {
+        Self {
+            target: target.to_path_buf(),
+            policy,
+            track_paths,
+            state: Mutex::new(RestorePlannerState::default()),
+        }
+    }
+
+    /// Nimmt einen Knoten in den Plan auf
+    ///
+    /// # Returns
+    /// `false`, wenn der Knoten wegen der Overwrite-Policy übersprungen wird
+    fn admit(&self, path: &Path, node: &Node) -> bool {
+        let existing = std::fs::symlink_metadata(self.target.join(path)).ok();
+        let display = path.display().to_string();
+        let mut state = self.state.lock();
+        if self.track_paths {
+            state.paths.insert(path.to_path_buf());
+        }
+
+        if node.is_dir() {
+            if existing.is_none() {
+                state.plan.dirs_to_create += 1;
+            }
+            return true;
+        }
+        let Some(existing) = existing else {
+            if node.is_file() {
+                state.plan.files_to_create.push(display);
+            }
+            return true;
+        };
+
+        if !overwrites_existing(self.policy, &existing, node) {
+            state.skipped.insert(path.to_path_buf());
+            state.plan.files_skipped.push(display);
+            return false;
+        }
+        if node.is_file() {
+            let unchanged = self.policy != OverwritePolicy::Always
+                && existing.len() == node.meta.size
+                && modified_time(&existing) == node.meta.mtime;
+            if unchanged {
+                state.plan.files_unchanged += 1;
+            } else {
+                state.plan.files_to_update.push(display);
+            }
         }
+        true
+    }
+
+    /// Prüft, ob ein Knoten beim Erstellen des Plans übersprungen wurde
+    fn is_skipped(&self, path: &Path) -> bool {
+        self.state.lock().skipped.contains(path)
+    }
 
-        FileTreeNode {
-            name: path.split('/').last().unwrap_or("/").to_string(),
-            path: path.to_string(),
-            is_directory: true,
-            size: Some(0),
-            modified: Some(snapshot.time.to_rfc3339()),
-            children: Some(child_nodes),
+    /// Sammelt die Einträge im Ziel, die nicht im Snapshot enthalten sind
+    fn extra_paths(&self) -> Vec<String> {
+        fn walk(root: &Path, dir: &Path, known: &HashSet<PathBuf>, extra: &mut Vec<String>) {
+            let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+                return;
+            };
+            let mut names: Vec<_> = entries.filter_map(|e| e.ok()).map(|e| e.file_name()).collect();
+            names.sort();
+            for name in names {
+                let path = dir.join(name);
+                if !known.contains(&path) {
+                    extra.push(path.display().to_string());
+                } else if root.join(&path).symlink_metadata().is_ok_and(|m| m.is_dir()) {
+                    walk(root, &path, known, extra);
+                }
+            }
         }
+
+        let mut extra = Vec::new();
+        walk(&self.target, Path::new(""), &self.state.lock().paths, &mut extra);
+        extra
     }
 
-    let root = build_tree("/", &path_map, &snapshot);
+    /// Übernimmt die Größen aus dem Plan von rustic_core und liefert den fertigen Plan
+    fn finish(self, restore_size: u64, matched_size: u64, dry_run: bool) -> RestorePlanDto {
+        let mut plan = self.state.into_inner().plan;
+        plan.bytes_to_write = restore_size;
+        plan.bytes_unchanged = matched_size;
+        plan.dry_run = dry_run;
+        plan
+    }
+}
+
+/// Änderungszeit einer vorhandenen Datei im Format von rustic_core
+fn modified_time(meta: &std::fs::Metadata) -> Option<DateTime<Local>> {
+    meta.modified().ok().map(DateTime::<Local>::from)
+}
 
-    Ok(root)
+/// Prüft, ob ein vorhandener Eintrag gemäß Policy überschrieben wird
+fn overwrites_existing(policy: OverwritePolicy, existing: &std::fs::Metadata, node: &Node) -> bool {
+    match policy {
+        OverwritePolicy::Always | OverwritePolicy::IfChanged => true,
+        OverwritePolicy::IfNewer => match (node.meta.mtime, modified_time(existing)) {
+            (Some(snapshot), Some(current)) => snapshot > current,
+            _ => false,
+        },
+        OverwritePolicy::Never => false,
+    }
 }
 
 /// Stellt Dateien aus einem Snapshot wieder her.
@@ -150,10 +595,19 @@ pub async fn get_file_tree(
 /// - 2-step: prepare_restore() → restore()
 /// - LocalDestination mit create=true, flatten=!node.is_dir()
 /// - RestoreOptions für Konfiguration
-/// - Progress-Events während Restore
+/// - Progress über `GuiProgressBars` und die Node-Streamer
 ///
 /// Referenz: https://github.com/rustic-rs/rustic/blob/main/src/commands/restore.rs
 ///
+/// Vorhandene Dateien werden gemäß `options.overwrite` behandelt, zusätzliche Dateien im
+/// Ziel nur mit `options.delete` beim Restore des kompletten Snapshots gelöscht. Bei
+/// `options.dry_run` wird nur der Plan erstellt und nichts geschrieben.
+///
+/// Blockiert bis zum Ende des Restores und sollte daher in `spawn_blocking` laufen.
+/// Die Dateiinhalte werden in Abschnitten (höchstens `RESTORE_CHUNK_FILES` Dateien bzw.
+/// `RESTORE_CHUNK_BYTES`) geschrieben; `cancel_token` wird zwischen den Abschnitten und in
+/// den Node-Streamern geprüft. Bereits geschriebene Dateien bleiben im Ziel erhalten.
+///
 /// # Arguments
 /// * `repository_path` - Pfad zum Repository
 /// * `password` - Repository-Passwort
@@ -161,38 +615,52 @@ pub async fn get_file_tree(
 /// * `files` - Liste der wiederherzustellenden Dateien/Pfade (leer = alle)
 /// * `target_path` - Zielverzeichnis für die Wiederherstellung
 /// * `options` - Restore-Optionen (Overwrite, Permissions, etc.)
-/// * `app_handle` - Optional: Tauri AppHandle für Progress-Events
+/// * `cancel_token` - Token zum Abbrechen des Restores
+/// * `on_progress` - Callback für Fortschritts-Updates
 ///
 /// # Returns
-/// Erfolg bei vollständiger Wiederherstellung
-pub async fn restore_files(
+/// Restore-Plan (bei Dry-Run ohne Änderungen am Ziel)
+///
+/// # Errors
+/// `RusticGuiError::RestoreCancelled`, wenn der Restore abgebrochen wurde
+#[allow(clippy::too_many_arguments)]
+pub fn restore_files<F>(
     repository_path: &str,
     password: &str,
     snapshot_id: &str,
     files: Vec<String>,
     target_path: &str,
     options: &RestoreOptionsDto,
-    app_handle: Option<AppHandle>,
-) -> Result<(), RusticGuiError> {
+    cancel_token: CancellationToken,
+    on_progress: F,
+) -> Result<RestorePlanDto, RusticGuiError>
+where
+    F: Fn(RestoreProgress) + Send + Sync + 'static,
+{
     info!(
         repo = repository_path,
         snapshot = snapshot_id,
         files = files.len(),
         target = target_path,
+        dry_run = options.dry_run,
         "Starte Restore"
     );
 
-    // Progress-Event: Started
-    if let Some(app) = app_handle.as_ref() {
-        let _ = app.emit(
-            "restore-started",
-            serde_json::json!({
-                "snapshot_id": snapshot_id,
-                "target_path": target_path,
-            }),
-        );
+    if cancel_token.is_cancelled() {
+        return Err(RusticGuiError::RestoreCancelled);
     }
 
+    let tracker = Arc::new(RestoreProgressTracker::new(on_progress));
+
+    // ProgressBars: nur der Byte-Fortschritt beim Schreiben der Inhalte wird gemeldet
+    let tracker_pb = tracker.clone();
+    let progress_bars = GuiProgressBars::new(move |update| {
+        if update.kind == ProgressKind::Bytes && update.prefix == RESTORE_CONTENTS_PREFIX {
+            tracker_pb.contents(tracker_pb.chunk_offset.load(Ordering::Relaxed) + update.position);
+        }
+    })
+    .with_cancellation(cancel_token.clone());
+
     let mut repo_opts = RepositoryOptions::default();
     repo_opts.password = Some(password.to_string());
     let mut backend_opts = BackendOptions::default();
@@ -200,7 +668,8 @@ pub async fn restore_files(
     let backends = backend_opts.to_backends().map_err(|e| {
         RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
     })?;
-    let repo = Repository::<NoProgressBars, _>::new(&repo_opts, &backends)
+
+    let repo = Repository::new_with_progress(&repo_opts, &backends, progress_bars)
         .map_err(|e| {
             error!(?e, "Fehler beim Initialisieren des Repositories");
             RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
@@ -226,133 +695,351 @@ pub async fn restore_files(
         .next()
         .ok_or_else(|| RusticGuiError::SnapshotNotFound { id: snapshot_id.to_string() })?;
 
-    // Zielverzeichnis erstellen falls nicht vorhanden
+    // Zielverzeichnis erstellen falls nicht vorhanden (nicht bei Dry-Run)
     let target = std::path::PathBuf::from(target_path);
-    if !target.exists() {
+    if !target.exists() && !options.dry_run {
         std::fs::create_dir_all(&target).map_err(|e| {
             error!(?e, "Zielverzeichnis erstellen fehlgeschlagen");
             RusticGuiError::Internal(format!("Zielverzeichnis erstellen fehlgeschlagen: {e}"))
         })?;
     }
 
-    // Tree des Snapshots laden (früh, um is_dir() zu prüfen)
-    let tree = repo
-        .node_from_snapshot_path("", |sn: &SnapshotFile| sn.id == snapshot.id)
-        .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?;
+    // Auswahl bestimmen (leer = kompletter Snapshot)
+    let selections =
+        plan_selections(files.iter().chain(&options.paths).map(String::as_str), options.layout)?;
 
-    // Destination erstellen. create=true, flatten=!node.is_dir() (wie in rustic CLI)
+    // Destination erstellen (Dry-Run legt nichts an); das Ziel ist immer ein Verzeichnis
     let dest =
-        LocalDestination::new(target.to_str().unwrap(), true, !tree.is_dir()).map_err(|e| {
+        LocalDestination::new(target.to_str().unwrap(), !options.dry_run, false).map_err(|e| {
             error!(?e, "LocalDestination erstellen fehlgeschlagen");
             RusticGuiError::Internal(format!("Destination ungültig: {e}"))
         })?;
 
-    // Restore-Optionen konfigurieren
+    // Zusätzliche Dateien im Ziel nur beim Restore des kompletten Snapshots löschen,
+    // sonst würde alles außerhalb der Auswahl entfernt. Übersprungene Dateien würden
+    // ebenfalls als zusätzlich gelten, daher nur mit überschreibender Policy.
+    let overwrites =
+        matches!(options.overwrite, OverwritePolicy::Always | OverwritePolicy::IfChanged);
+    let delete = options.delete && selections.is_none() && overwrites;
+    if options.delete && !delete {
+        warn!("Zusätzliche Dateien im Ziel werden bei dieser Auswahl/Policy nicht gelöscht");
+    }
     let restore_opts = RestoreOptions::default()
-        .delete(options.overwrite)
+        .delete(delete)
+        .verify_existing(options.overwrite == OverwritePolicy::Always)
         .no_ownership(!options.restore_permissions);
+    let planner = RestorePlanner::new(&target, options.overwrite, delete && options.dry_run);
 
-    // Progress-Event: Vorbereitung
-    if let Some(app) = app_handle.as_ref() {
-        let _ = app.emit(
-            "restore-progress",
-            &RestoreProgress {
-                base: crate::types::ProgressInfo {
-                    current: 0,
-                    total: 1,
-                    message: Some("Restore wird vorbereitet...".to_string()),
-                    percentage: Some(0.0),
-                },
-                files_restored: 0,
-                bytes_restored: 0,
-                current_file: None,
-            },
-        );
-    }
-
-    // Iterator für node_streamer erstellen
     // Wichtig: recursive muss true sein für vollständigen Restore
     let mut ls_opts = LsOptions::default();
     ls_opts.recursive = true;
-    let node_streamer = repo
-        .ls(&tree, &ls_opts)
-        .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?;
 
-    // Restore-Plan erstellen (Schritt 1)
+    // Node-Streamer erstellen; wird für Plan und Restore je einmal benötigt
+    let node_streamer = || -> Result<NodeStream<'_>, RusticGuiError> {
+        match &selections {
+            Some(selections) => {
+                selection_nodes(&repo, &snapshot, selections, options.layout, &ls_opts)
+            }
+            None => {
+                let root = repo
+                    .node_from_snapshot_path("", |sn: &SnapshotFile| sn.id == snapshot.id)
+                    .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?;
+                let nodes = repo
+                    .ls(&root, &ls_opts)
+                    .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?;
+                Ok(Box::new(nodes))
+            }
+        }
+    };
+
+    // Restore-Plan erstellen (Schritt 1), dabei Knoten klassifizieren und die Dateiliste
+    // für den Fortschritt sammeln
     info!("Erstelle Restore-Plan...");
+    let tracker_collect = tracker.clone();
+    let admitted = node_streamer()?.filter(|item| match item {
+        Ok((path, node)) => planner.admit(path, node),
+        Err(_) => true,
+    });
     let plan = repo
-        .prepare_restore(&restore_opts, node_streamer, &dest, false)
-        .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?;
-
-    // Total-Files aus Plan extrahieren (vor move)
-    let stats = plan.stats.clone();
-    let total_files = stats.files.restore + stats.files.unchanged + stats.files.verified;
-
-    // Progress-Event: Plan erstellt
-    if let Some(app) = app_handle.as_ref() {
-        let _ = app.emit(
-            "restore-progress",
-            &RestoreProgress {
-                base: crate::types::ProgressInfo {
-                    current: 0,
-                    total: total_files,
-                    message: Some(format!("Restore von {} Dateien...", total_files)),
-                    percentage: Some(10.0),
-                },
-                files_restored: 0,
-                bytes_restored: 0,
-                current_file: None,
-            },
+        .prepare_restore(
+            &restore_opts,
+            track_nodes(admitted, &cancel_token, move |path, node| {
+                tracker_collect.collect(path, node)
+            }),
+            &dest,
+            options.dry_run,
+        )
+        .map_err(|e| {
+            if cancel_token.is_cancelled() {
+                RusticGuiError::RestoreCancelled
+            } else {
+                RusticGuiError::RestoreFailed { reason: e.to_string() }
+            }
+        })?;
+
+    if options.dry_run {
+        let paths_to_delete = if delete { planner.extra_paths() } else { Vec::new() };
+        let mut result = planner.finish(plan.restore_size, plan.matched_size, true);
+        result.paths_to_delete = paths_to_delete;
+        info!(
+            create = result.files_to_create.len(),
+            update = result.files_to_update.len(),
+            skip = result.files_skipped.len(),
+            bytes = result.bytes_to_write,
+            "Dry-Run: Restore-Plan erstellt"
         );
+        return Ok(result);
     }
 
-    // Node-Iterator für restore erstellen (erneuter ls-Aufruf)
-    let node_streamer_restore = repo
-        .ls(&tree, &ls_opts)
-        .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?;
-
-    // Restore ausführen (Schritt 2)
-    info!("Führe Restore aus ({} Dateien)...", total_files);
-
-    // Simulated Progress während Restore
-    // FIXME: rustic_core bietet keinen Progress-Callback für restore() - muss simuliert werden
-    if let Some(app) = app_handle.as_ref() {
-        let app_clone = app.clone();
-        tokio::spawn(async move {
-            for i in 1..=10 {
-                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-                let _ = app_clone.emit(
-                    "restore-progress",
-                    &RestoreProgress {
-                        base: crate::types::ProgressInfo {
-                            current: (total_files * i / 10).min(total_files),
-                            total: total_files,
-                            message: Some(format!("Restore läuft ({}/10)...", i)),
-                            percentage: Some((i * 10) as f32),
-                        },
-                        files_restored: (total_files * i / 10).min(total_files),
-                        bytes_restored: 0,
-                        current_file: None,
-                    },
-                );
-            }
-        });
+    tracker.restore_size.store(plan.restore_size, Ordering::Relaxed);
+    let total_files = tracker.total_files();
+    tracker.contents(0);
+    let (restore_size, matched_size) = (plan.restore_size, plan.matched_size);
+    // Der Gesamtplan dient nur den Summen; geschrieben wird abschnittsweise
+    drop(plan);
+
+    let cancelled_or_failed = |e: Box<RusticError>| {
+        if cancel_token.is_cancelled() {
+            warn!(target = target_path, "Restore abgebrochen, Ziel ist unvollständig");
+            RusticGuiError::RestoreCancelled
+        } else {
+            error!(?e, "Restore fehlgeschlagen");
+            RusticGuiError::RestoreFailed { reason: e.to_string() }
+        }
+    };
+
+    // Dateiinhalte abschnittsweise schreiben (Schritt 2). Jeder Abschnitt erhält einen
+    // eigenen Plan; Verzeichnisse und zu löschende Einträge hat Schritt 1 bereits erledigt.
+    info!("Führe Restore aus ({} Dateien, {} Bytes)...", total_files, restore_size);
+    let chunk_opts = restore_opts.delete(false);
+    let restore_chunk = |chunk: &mut Vec<(PathBuf, Node)>| -> Result<(), RusticGuiError> {
+        if cancel_token.is_cancelled() {
+            warn!(target = target_path, "Restore abgebrochen, Ziel ist unvollständig");
+            return Err(RusticGuiError::RestoreCancelled);
+        }
+        let chunk_plan = repo
+            .prepare_restore(&chunk_opts, chunk.drain(..).map(Ok), &dest, false)
+            .map_err(cancelled_or_failed)?;
+        let written = chunk_plan.restore_size;
+        repo.restore(chunk_plan, &chunk_opts, std::iter::empty(), &dest)
+            .map_err(cancelled_or_failed)?;
+        tracker.chunk_offset.fetch_add(written, Ordering::Relaxed);
+        Ok(())
+    };
+    let mut chunk = Vec::new();
+    let mut chunk_bytes = 0;
+    for item in node_streamer()? {
+        let (path, node) = item.map_err(cancelled_or_failed)?;
+        if node.is_dir() || planner.is_skipped(&path) {
+            continue;
+        }
+        chunk_bytes += node.meta.size;
+        chunk.push((path, node));
+        if chunk.len() >= RESTORE_CHUNK_FILES || chunk_bytes >= RESTORE_CHUNK_BYTES {
+            restore_chunk(&mut chunk)?;
+            chunk_bytes = 0;
+        }
     }
+    if !chunk.is_empty() {
+        restore_chunk(&mut chunk)?;
+    }
+
+    // Metadaten setzen (Schritt 3): übersprungene Knoten auslassen und Zeitstempel nur auf
+    // Wunsch setzen
+    let restore_timestamps = options.restore_timestamps;
+    let node_streamer_metadata = node_streamer()?
+        .filter(|item| !item.as_ref().is_ok_and(|(path, _)| planner.is_skipped(path)))
+        .map(move |item| {
+            item.map(|(path, mut node)| {
+                if !restore_timestamps {
+                    node.meta.mtime = None;
+                    node.meta.atime = None;
+                }
+                (path, node)
+            })
+        });
+    let tracker_metadata = tracker.clone();
+    repo.restore(
+        RestorePlan::default(),
+        &restore_opts,
+        track_nodes(node_streamer_metadata, &cancel_token, move |path, _| {
+            tracker_metadata.metadata(path)
+        }),
+        &dest,
+    )
+    .map_err(cancelled_or_failed)?;
 
-    repo.restore(plan, &restore_opts, node_streamer_restore, &dest)
-        .map_err(|e| RusticGuiError::RestoreFailed { reason: e.to_string() })?;
+    (tracker.on_progress)(tracker.finished());
 
-    // Progress-Event: Completed
-    if let Some(app) = app_handle.as_ref() {
-        let _ = app.emit(
-            "restore-completed",
-            serde_json::json!({
-                "snapshot_id": snapshot_id,
-                "files_restored": total_files,
-            }),
+    info!("Restore erfolgreich abgeschlossen ({} Dateien)", total_files);
+    Ok(planner.finish(restore_size, matched_size, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustic_core::repofile::Metadata;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_node_to_file_tree_node() {
+        let meta = Metadata {
+            mode: Some(0o100644),
+            uid: Some(1000),
+            user: Some("alice".to_string()),
+            gid: Some(1000),
+            size: 4096,
+            ..Default::default()
+        };
+        let file = Node::new_node(OsStr::new("notes.txt"), NodeType::File, meta);
+        let dto = node_to_file_tree_node(&file, "/home/alice/notes.txt");
+
+        assert_eq!(dto.name, "notes.txt");
+        assert_eq!(dto.node_type, FileNodeType::File);
+        assert!(!dto.is_directory);
+        assert_eq!(dto.size, Some(4096));
+        assert_eq!(dto.mode, Some(0o100644));
+        assert_eq!(dto.owner.as_deref(), Some("alice"));
+        assert_eq!(dto.group.as_deref(), Some("1000"));
+        assert!(dto.children.is_none());
+    }
+
+    #[test]
+    fn test_symlink_and_dir_nodes() {
+        let link = Node::new_node(
+            OsStr::new("current"),
+            NodeType::from_link(std::path::Path::new("releases/v2")),
+            Metadata::default(),
         );
+        let dto = node_to_file_tree_node(&link, "/srv/current");
+        assert_eq!(dto.node_type, FileNodeType::Symlink);
+        assert_eq!(dto.link_target.as_deref(), Some("releases/v2"));
+
+        let dir = Node::new_node(OsStr::new("srv"), NodeType::Dir, Metadata::default());
+        let dto = node_to_file_tree_node(&dir, "/srv");
+        assert!(dto.is_directory);
+        assert_eq!(dto.node_type, FileNodeType::Dir);
+        assert_eq!(dto.size, None);
     }
 
-    info!("Restore erfolgreich abgeschlossen ({} Dateien)", total_files);
-    Ok(())
+    #[test]
+    fn test_restore_progress_from_bytes() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_cb = events.clone();
+        let tracker = RestoreProgressTracker::new(move |p| events_cb.lock().push(p));
+
+        for (name, size) in [("a.txt", 100), ("b.txt", 300), ("c.txt", 600)] {
+            let meta = Metadata { size, ..Default::default() };
+            let node = Node::new_node(OsStr::new(name), NodeType::File, meta);
+            tracker.collect(Path::new(&format!("data/{name}")), &node);
+        }
+        let dir = Node::new_node(OsStr::new("data"), NodeType::Dir, Metadata::default());
+        tracker.collect(Path::new("data"), &dir);
+        assert_eq!(tracker.total_files(), 3);
+
+        // Nur die Hälfte der Daten muss geschrieben werden (Rest unverändert)
+        tracker.restore_size.store(500, Ordering::Relaxed);
+        tracker.contents(250);
+        let progress = events.lock().last().cloned().unwrap();
+        assert_eq!(progress.files_restored, 2);
+        assert_eq!(progress.bytes_restored, 250);
+        assert_eq!(progress.current_file.as_deref(), Some("/data/c.txt"));
+        assert_eq!(progress.base.percentage, Some(50.0));
+
+        let finished = tracker.finished();
+        assert_eq!(finished.files_restored, 3);
+        assert_eq!(finished.bytes_restored, 500);
+    }
+
+    #[test]
+    fn test_track_nodes_stops_after_cancel() {
+        let token = CancellationToken::new();
+        let nodes = (0..3).map(|i| {
+            let name = format!("f{i}");
+            let node = Node::new_node(OsStr::new(&name), NodeType::File, Metadata::default());
+            Ok((PathBuf::from(name), node))
+        });
+        let mut tracked = track_nodes(nodes, &token, |_, _| {});
+
+        assert!(tracked.next().unwrap().is_ok());
+        token.cancel();
+        assert!(tracked.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_plan_selections_absolute() {
+        let selections = plan_selections(
+            ["/home/user/docs", "/home/user/docs/a.txt", "/etc/hosts", "", "/home/user/docs/"],
+            RestoreLayout::Absolute,
+        )
+        .unwrap()
+        .unwrap();
+
+        let targets: Vec<_> = selections.iter().map(|s| s.target.clone()).collect();
+        assert_eq!(targets, vec![PathBuf::from("etc/hosts"), PathBuf::from("home/user/docs")]);
+        assert_eq!(selections[1].source, PathBuf::from("home/user/docs"));
+    }
+
+    #[test]
+    fn test_plan_selections_relative() {
+        let selections =
+            plan_selections(["/srv/www", "/etc/hosts"], RestoreLayout::Relative).unwrap().unwrap();
+        assert_eq!(selections[0].target, PathBuf::from("hosts"));
+        assert_eq!(selections[0].source, PathBuf::from("etc/hosts"));
+        assert_eq!(selections[1].target, PathBuf::from("www"));
+
+        // Gleiche Namen kollidieren im Ziel
+        assert!(plan_selections(["/a/x", "/b/x"], RestoreLayout::Relative).is_err());
+    }
+
+    #[test]
+    fn test_plan_selections_whole_snapshot() {
+        assert!(plan_selections([], RestoreLayout::Absolute).unwrap().is_none());
+        assert!(plan_selections(["/", "/etc"], RestoreLayout::Absolute).unwrap().is_none());
+    }
+
+    fn file_node(name: &str, size: u64, mtime: Option<DateTime<Local>>) -> Node {
+        let meta = Metadata { size, mtime, ..Default::default() };
+        Node::new_node(OsStr::new(name), NodeType::File, meta)
+    }
+
+    #[test]
+    fn test_restore_planner_policies() {
+        let target = tempfile::TempDir::new().unwrap();
+        std::fs::write(target.path().join("existing.txt"), b"old").unwrap();
+        std::fs::write(target.path().join("extra.txt"), b"extra").unwrap();
+        let existing_mtime =
+            modified_time(&std::fs::metadata(target.path().join("existing.txt")).unwrap()).unwrap();
+        let newer = Some(existing_mtime + chrono::Duration::hours(1));
+        let older = Some(existing_mtime - chrono::Duration::hours(1));
+
+        let never = RestorePlanner::new(target.path(), OverwritePolicy::Never, false);
+        assert!(!never.admit(Path::new("existing.txt"), &file_node("existing.txt", 5, newer)));
+        assert!(never.admit(Path::new("new.txt"), &file_node("new.txt", 5, newer)));
+        assert!(never.is_skipped(Path::new("existing.txt")));
+
+        let if_newer = RestorePlanner::new(target.path(), OverwritePolicy::IfNewer, false);
+        assert!(if_newer.admit(Path::new("existing.txt"), &file_node("existing.txt", 5, newer)));
+        let if_newer_old = RestorePlanner::new(target.path(), OverwritePolicy::IfNewer, false);
+        assert!(
+            !if_newer_old.admit(Path::new("existing.txt"), &file_node("existing.txt", 5, older))
+        );
+
+        let if_changed = RestorePlanner::new(target.path(), OverwritePolicy::IfChanged, true);
+        let same = file_node("existing.txt", 3, Some(existing_mtime));
+        assert!(if_changed.admit(Path::new("existing.txt"), &same));
+        assert!(if_changed.admit(Path::new("new.txt"), &file_node("new.txt", 1, None)));
+        assert_eq!(if_changed.extra_paths(), vec!["extra.txt".to_string()]);
+
+        let plan = if_changed.finish(1, 3, true);
+        assert_eq!(plan.files_unchanged, 1);
+        assert_eq!(plan.files_to_create, vec!["new.txt".to_string()]);
+        assert!(plan.files_to_update.is_empty());
+        assert_eq!(plan.bytes_to_write, 1);
+        assert!(plan.dry_run);
+
+        let always = RestorePlanner::new(target.path(), OverwritePolicy::Always, false);
+        assert!(always.admit(Path::new("existing.txt"), &same));
+        assert_eq!(always.finish(0, 0, false).files_to_update, vec!["existing.txt".to_string()]);
+    }
 }
@@ -1,21 +1,258 @@
 use crate::error::RusticGuiError;
-use crate::types::{RetentionPolicy, SnapshotDto};
+use crate::types::{
+    DiffChangeClass, DiffEntryDto, DiffEntryKind, DirectoryDiffDto, FileHistoryDto, FileMatchDto,
+    FileVersionDto, FindPatternKind, FindQuery, FindResultDto, RetentionPolicy, SnapshotDiffDto,
+    SnapshotDiffOptions, SnapshotDto, SnapshotPageDto, SnapshotQuery, SnapshotSortKey,
+};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime};
 use rustic_backend::BackendOptions;
-use rustic_core::{Id, Repository, RepositoryOptions, repofile::SnapshotId};
+use rustic_core::{
+    Id, IndexedTree, LsOptions, Repository, RepositoryOptions, RusticResult, TreeId,
+    repofile::{Node, SnapshotFile, SnapshotId},
+};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
 use tracing::{error, info};
 
+/// Verknüpfung der Tags in `SnapshotFilter::tags`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMatchMode {
+    /// Mindestens einer der Tags muss vorhanden sein
+    #[default]
+    Any,
+    /// Alle Tags müssen vorhanden sein
+    All,
+}
+
 /// Filter-Optionen für Snapshot-Listing
-#[derive(Debug, Clone, Deserialize, Serialize)]
+///
+/// Alle gesetzten Kriterien müssen zutreffen (UND-Verknüpfung).
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct SnapshotFilter {
-    /// Filter nach Tags (OR-Logic: wenn irgendeiner passt)
+    /// Filter nach Tags (Verknüpfung über `tag_mode`)
     pub tags: Option<Vec<String>>,
+    /// Verknüpfung von `tags` (Standard: OR)
+    #[serde(default)]
+    pub tag_mode: TagMatchMode,
+    /// Tag-Ausdruck: mindestens eine Gruppe muss vollständig passen (OR über AND-Gruppen)
+    #[serde(default)]
+    pub tag_groups: Option<Vec<Vec<String>>>,
     /// Filter nach Hostname
     pub hostname: Option<String>,
-    /// Filter nach Zeitraum (von)
+    /// Filter nach einem von mehreren Hostnamen
+    #[serde(default)]
+    pub hostnames: Option<Vec<String>>,
+    /// Filter nach einem von mehreren Benutzernamen
+    #[serde(default)]
+    pub usernames: Option<Vec<String>>,
+    /// Mindestens ein gesicherter Pfad enthält diesen Text
+    #[serde(default)]
+    pub path_contains: Option<String>,
+    /// Filter nach Zeitraum (von), RFC 3339 oder `YYYY-MM-DD`
     pub time_from: Option<String>,
-    /// Filter nach Zeitraum (bis)
+    /// Filter nach Zeitraum (bis), RFC 3339 oder `YYYY-MM-DD` (ganzer Tag inklusive)
     pub time_to: Option<String>,
+    /// Snapshot-ID beginnt mit diesem Präfix
+    #[serde(default)]
+    pub id_prefix: Option<String>,
+}
+
+/// Standardgröße einer Seite der Snapshot-Liste
+const DEFAULT_SNAPSHOT_PAGE_SIZE: u64 = 500;
+
+/// Geprüfter `SnapshotFilter` mit bereits geparsten Zeitgrenzen
+pub(crate) struct SnapshotMatcher<'a> {
+    filter: &'a SnapshotFilter,
+    time_from: Option<DateTime<Local>>,
+    time_to: Option<DateTime<Local>>,
+    id_prefix: Option<String>,
+}
+
+impl<'a> SnapshotMatcher<'a> {
+    /// # Errors
+    /// `InvalidConfig`, wenn eine Zeitangabe weder RFC 3339 noch `YYYY-MM-DD` ist
+    pub(crate) fn new(filter: &'a SnapshotFilter) -> Result<Self, RusticGuiError> {
+        Ok(Self {
+            filter,
+            time_from: filter
+                .time_from
+                .as_deref()
+                .map(|v| parse_filter_time(v, "time_from", false))
+                .transpose()?,
+            time_to: filter
+                .time_to
+                .as_deref()
+                .map(|v| parse_filter_time(v, "time_to", true))
+                .transpose()?,
+            id_prefix: filter.id_prefix.as_ref().map(|prefix| prefix.to_ascii_lowercase()),
+        })
+    }
+
+    /// Prüft einen Snapshot gegen alle gesetzten Kriterien
+    pub(crate) fn matches(&self, snap: &SnapshotFile) -> bool {
+        let filter = self.filter;
+        let has_tag = |tag: &String| snap.tags.contains(tag);
+        if let Some(tags) = filter.tags.as_ref().filter(|tags| !tags.is_empty()) {
+            let matched = match filter.tag_mode {
+                TagMatchMode::Any => tags.iter().any(has_tag),
+                TagMatchMode::All => tags.iter().all(has_tag),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(groups) = filter.tag_groups.as_ref().filter(|groups| !groups.is_empty())
+            && !groups.iter().any(|group| group.iter().all(has_tag))
+        {
+            return false;
+        }
+        if filter.hostname.as_ref().is_some_and(|hostname| snap.hostname != *hostname) {
+            return false;
+        }
+        let listed = |list: &Option<Vec<String>>, value: &String| {
+            list.as_ref().filter(|list| !list.is_empty()).is_none_or(|list| list.contains(value))
+        };
+        if !listed(&filter.hostnames, &snap.hostname) || !listed(&filter.usernames, &snap.username)
+        {
+            return false;
+        }
+        if let Some(needle) = filter.path_contains.as_ref().filter(|needle| !needle.is_empty())
+            && !snap.paths.iter().any(|path| path.contains(needle.as_str()))
+        {
+            return false;
+        }
+        if self.id_prefix.as_ref().is_some_and(|prefix| !snap.id.to_string().starts_with(prefix)) {
+            return false;
+        }
+        if self.time_from.is_some_and(|from| snap.time < from) {
+            return false;
+        }
+        self.time_to.is_none_or(|to| snap.time <= to)
+    }
+}
+
+/// Parst eine Zeitgrenze des Filters.
+///
+/// Reine Datumsangaben gelten in lokaler Zeit ab Tagesbeginn bzw. (für `end_of_day`)
+/// bis zum Tagesende.
+fn parse_filter_time(
+    value: &str,
+    field: &str,
+    end_of_day: bool,
+) -> Result<DateTime<Local>, RusticGuiError> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Local));
+    }
+    let invalid = || RusticGuiError::InvalidConfig { field: field.to_string() };
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| invalid())?;
+    let local = |time: NaiveDateTime| time.and_local_timezone(Local);
+    if end_of_day {
+        date.and_hms_nano_opt(23, 59, 59, 999_999_999).and_then(|t| local(t).latest())
+    } else {
+        date.and_hms_opt(0, 0, 0).and_then(|t| local(t).earliest())
+    }
+    .ok_or_else(invalid)
+}
+
+/// Konvertiert einen Snapshot in das DTO für das Frontend
+fn snapshot_to_dto(snap: &SnapshotFile, repository_id: &str) -> SnapshotDto {
+    SnapshotDto {
+        id: snap.id.to_string(),
+        time: snap.time.to_rfc3339(),
+        hostname: snap.hostname.to_string(),
+        tags: snap.tags.iter().map(|t| t.to_string()).collect(),
+        paths: snap.paths.iter().map(|p| p.to_string()).collect(),
+        file_count: snap.summary.as_ref().map(|s| s.total_files_processed).unwrap_or(0),
+        total_size: snap.summary.as_ref().map(|s| s.total_bytes_processed).unwrap_or(0),
+        repository_id: repository_id.to_string(),
+        username: Some(snap.username.clone()),
+        summary: snap.summary.as_ref().map(|s| crate::types::SnapshotSummary {
+            files_count: Some(s.total_files_processed),
+            dirs_count: Some(s.total_dirs_processed),
+            data_size: Some(s.total_bytes_processed),
+        }),
+    }
+}
+
+/// Sortiert Snapshots nach dem Schlüssel der Query (bei Gleichstand nach Zeit und ID)
+fn sort_snapshots(snaps: &mut [SnapshotFile], sort: SnapshotSortKey, ascending: bool) {
+    let summary = |snap: &SnapshotFile,
+                   value: fn(&rustic_core::repofile::SnapshotSummary) -> u64| {
+        snap.summary.as_ref().map_or(0, value)
+    };
+    snaps.sort_by(|a, b| {
+        let ordering = match sort {
+            SnapshotSortKey::Time => Ordering::Equal,
+            SnapshotSortKey::Hostname => a.hostname.cmp(&b.hostname),
+            SnapshotSortKey::Size => summary(a, |s| s.total_bytes_processed)
+                .cmp(&summary(b, |s| s.total_bytes_processed)),
+            SnapshotSortKey::Files => summary(a, |s| s.total_files_processed)
+                .cmp(&summary(b, |s| s.total_files_processed)),
+            SnapshotSortKey::Id => a.id.cmp(&b.id),
+        }
+        .then_with(|| a.time.cmp(&b.time))
+        .then_with(|| a.id.cmp(&b.id));
+        if ascending { ordering } else { ordering.reverse() }
+    });
+}
+
+/// Filtert, sortiert und schneidet die Seite der Query aus
+fn page_snapshots(
+    mut snaps: Vec<SnapshotFile>,
+    matcher: Option<&SnapshotMatcher<'_>>,
+    query: &SnapshotQuery,
+    repository_id: &str,
+) -> SnapshotPageDto {
+    if let Some(matcher) = matcher {
+        snaps.retain(|snap| matcher.matches(snap));
+    }
+    sort_snapshots(&mut snaps, query.sort, query.ascending);
+
+    let total = snaps.len() as u64;
+    let limit = query.limit.unwrap_or(DEFAULT_SNAPSHOT_PAGE_SIZE);
+    let snapshots: Vec<SnapshotDto> = snaps
+        .iter()
+        .skip(usize::try_from(query.offset).unwrap_or(usize::MAX))
+        .take(usize::try_from(limit).unwrap_or(usize::MAX))
+        .map(|snap| snapshot_to_dto(snap, repository_id))
+        .collect();
+    SnapshotPageDto {
+        has_more: query.offset.saturating_add(snapshots.len() as u64) < total,
+        snapshots,
+        total,
+        offset: query.offset,
+    }
+}
+
+/// Lädt alle Snapshot-Dateien eines Repositories (ohne Index)
+fn load_snapshots(
+    repository_path: &str,
+    password: &str,
+) -> Result<Vec<SnapshotFile>, RusticGuiError> {
+    let mut repo_opts = RepositoryOptions::default();
+    repo_opts.password = Some(password.to_string());
+    let mut backend_opts = BackendOptions::default();
+    backend_opts.repository = Some(repository_path.to_string());
+    let backends = backend_opts.to_backends().map_err(|e| {
+        RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+    })?;
+    let repo = Repository::new(&repo_opts, &backends)
+        .map_err(|e| {
+            error!(?e, "Fehler beim Initialisieren des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .open()
+        .map_err(|e| {
+            error!(?e, "Fehler beim Öffnen des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?;
+    repo.get_all_snapshots().map_err(|e| {
+        RusticGuiError::Internal(format!("Snapshots konnten nicht geladen werden: {e}"))
+    })
 }
 
 /// Wendet eine Retention-Policy an und löscht Snapshots gemäß Policy.
@@ -154,24 +391,7 @@ pub async fn get_snapshot(
         .into_iter()
         .next()
         .ok_or_else(|| RusticGuiError::SnapshotNotFound { id: snapshot_id.to_string() })?;
-    let repo_id = format!("repo-{}", repository_path);
-    let dto = SnapshotDto {
-        id: snap.id.to_string(),
-        time: snap.time.to_rfc3339(),
-        hostname: snap.hostname.to_string(),
-        tags: snap.tags.iter().map(|t| t.to_string()).collect(),
-        paths: snap.paths.iter().map(|p| p.to_string()).collect(),
-        file_count: snap.summary.as_ref().map(|s| s.total_files_processed).unwrap_or(0),
-        total_size: snap.summary.as_ref().map(|s| s.total_bytes_processed).unwrap_or(0),
-        repository_id: repo_id,
-        username: Some(snap.username.clone()),
-        summary: snap.summary.as_ref().map(|s| crate::types::SnapshotSummary {
-            files_count: Some(s.total_files_processed),
-            dirs_count: Some(s.total_dirs_processed),
-            data_size: Some(s.total_bytes_processed),
-        }),
-    };
-    Ok(dto)
+    Ok(snapshot_to_dto(&snap, &format!("repo-{}", repository_path)))
 }
 
 /// Listet alle Snapshots eines Repositories auf.
@@ -187,6 +407,105 @@ pub async fn list_snapshots(
     password: &str,
 ) -> Result<Vec<SnapshotDto>, RusticGuiError> {
     info!(repo = repository_path, "Lese Snapshots aus Repository");
+    let mut snaps = load_snapshots(repository_path, password)?;
+    snaps.sort_by_key(|snap| std::cmp::Reverse(snap.time));
+    let repo_id = format!("repo-{}", repository_path);
+    Ok(snaps.iter().map(|snap| snapshot_to_dto(snap, &repo_id)).collect())
+}
+
+/// Listet Snapshots mit optionalen Filtern.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `filter` - Optionale Filter-Optionen
+///
+/// # Returns
+/// Vektor von SnapshotDto, neueste zuerst
+///
+/// # Errors
+/// `InvalidConfig`, wenn eine Zeitangabe im Filter ungültig ist
+pub async fn list_snapshots_filtered(
+    repository_path: &str,
+    password: &str,
+    filter: Option<SnapshotFilter>,
+) -> Result<Vec<SnapshotDto>, RusticGuiError> {
+    info!(repo = repository_path, "Lese Snapshots mit Filter aus Repository");
+    let query = SnapshotQuery { limit: Some(u64::MAX), ..Default::default() };
+    Ok(query_snapshots(repository_path, password, filter.as_ref(), &query)?.snapshots)
+}
+
+/// Filtert, sortiert und paginiert die Snapshots eines Repositories.
+///
+/// Gefiltert wird direkt auf den Snapshot-Dateien; DTOs werden nur für die angeforderte
+/// Seite erzeugt.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `filter` - Optionale Filter-Optionen
+/// * `query` - Sortierung und Paging
+///
+/// # Returns
+/// Seite der Snapshots mit Gesamtanzahl der Treffer
+///
+/// # Errors
+/// `InvalidConfig`, wenn eine Zeitangabe im Filter ungültig ist
+pub fn query_snapshots(
+    repository_path: &str,
+    password: &str,
+    filter: Option<&SnapshotFilter>,
+    query: &SnapshotQuery,
+) -> Result<SnapshotPageDto, RusticGuiError> {
+    info!(repo = repository_path, sort = ?query.sort, offset = query.offset, "Frage Snapshots ab");
+    let matcher = filter.map(SnapshotMatcher::new).transpose()?;
+    let snaps = load_snapshots(repository_path, password)?;
+    Ok(page_snapshots(snaps, matcher.as_ref(), query, &format!("repo-{}", repository_path)))
+}
+
+/// Standardgröße einer Diff-Seite
+const DEFAULT_DIFF_PAGE_SIZE: u64 = 1000;
+
+/// Standardtiefe für die Verzeichnis-Rollups
+const DEFAULT_ROLLUP_DEPTH: u32 = 2;
+
+/// Knoten eines Snapshots mit Pfad, sortiert wie `Path::cmp`
+pub(crate) type DiffNodes<'a> = Box<dyn Iterator<Item = RusticResult<(PathBuf, Node)>> + 'a>;
+
+/// Vergleicht zwei Snapshots per Merge-Join über die sortierten Trees.
+///
+/// Beide Trees werden gleichzeitig gestreamt; im Speicher liegen nur die Einträge der
+/// angeforderten Seite und die Rollups bis `rollup_depth`, sodass auch Snapshots mit
+/// Millionen Dateien verglichen werden können.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `snapshot_id_a` - Älterer Snapshot
+/// * `snapshot_id_b` - Neuerer Snapshot
+/// * `options` - Präfix-Filter, Paging und Rollup-Tiefe
+///
+/// # Returns
+/// Seite der Diff-Einträge mit Gesamtstatistik und Verzeichnis-Rollups
+///
+/// # Errors
+/// `SnapshotNotFound`, wenn ein Snapshot nicht existiert; `PathNotFound`, wenn der
+/// Präfix in keinem der beiden Snapshots existiert
+pub fn diff_snapshots(
+    repository_path: &str,
+    password: &str,
+    snapshot_id_a: &str,
+    snapshot_id_b: &str,
+    options: &SnapshotDiffOptions,
+) -> Result<SnapshotDiffDto, RusticGuiError> {
+    info!(
+        repo = repository_path,
+        a = snapshot_id_a,
+        b = snapshot_id_b,
+        ?options,
+        "Vergleiche Snapshots"
+    );
+
     let mut repo_opts = RepositoryOptions::default();
     repo_opts.password = Some(password.to_string());
     let mut backend_opts = BackendOptions::default();
@@ -203,77 +522,928 @@ pub async fn list_snapshots(
         .map_err(|e| {
             error!(?e, "Fehler beim Öffnen des Repositories");
             RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .to_indexed_ids()
+        .map_err(|e| {
+            error!(?e, "Repository-Indexierung fehlgeschlagen");
+            RusticGuiError::Internal(format!("Repository-Indexierung fehlgeschlagen: {e}"))
         })?;
-    let snaps = repo.get_all_snapshots().map_err(|e| {
-        RusticGuiError::Internal(format!("Snapshots konnten nicht geladen werden: {e}"))
-    })?;
-    let repo_id = format!("repo-{}", repository_path);
-    let mut snapshots: Vec<SnapshotDto> = snaps
-        .into_iter()
-        .map(|snap| SnapshotDto {
-            id: snap.id.to_string(),
-            time: snap.time.to_rfc3339(),
-            hostname: snap.hostname.to_string(),
-            tags: snap.tags.iter().map(|t| t.to_string()).collect(),
-            paths: snap.paths.iter().map(|p| p.to_string()).collect(),
-            file_count: snap.summary.as_ref().map(|s| s.total_files_processed).unwrap_or(0),
-            total_size: snap.summary.as_ref().map(|s| s.total_bytes_processed).unwrap_or(0),
-            repository_id: repo_id.clone(),
-            username: Some(snap.username.clone()),
-            summary: snap.summary.as_ref().map(|s| crate::types::SnapshotSummary {
-                files_count: Some(s.total_files_processed),
-                dirs_count: Some(s.total_dirs_processed),
-                data_size: Some(s.total_bytes_processed),
-            }),
+
+    let prefix = options.path_prefix.as_deref().unwrap_or("/").trim_matches('/');
+    let ls_opts = LsOptions::default();
+    let nodes = |snapshot_id: &str| -> Result<Option<DiffNodes<'_>>, RusticGuiError> {
+        let snapshot = repo
+            .get_snapshot_from_str(snapshot_id, |_| true)
+            .map_err(|_| RusticGuiError::SnapshotNotFound { id: snapshot_id.to_string() })?;
+        let Ok(node) = repo.node_from_snapshot_and_path(&snapshot, prefix) else {
+            return Ok(None);
+        };
+
+        // Der Präfix-Eintrag selbst gehört zum Diff (z.B. ein neu angelegtes Verzeichnis)
+        let base = Path::new("/").join(prefix);
+        let root: Option<RusticResult<(PathBuf, Node)>> =
+            (!prefix.is_empty()).then(|| Ok((base.clone(), node.clone())));
+        if !node.is_dir() {
+            return Ok(Some(Box::new(root.into_iter())));
+        }
+        let children = repo
+            .ls(&node, &ls_opts)
+            .map_err(|e| {
+                RusticGuiError::Internal(format!("Tree konnte nicht gelesen werden: {e}"))
+            })?
+            .map(move |item| item.map(|(path, n)| (base.join(path), n)));
+        Ok(Some(Box::new(root.into_iter().chain(children))))
+    };
+
+    let (nodes_a, nodes_b) = match (nodes(snapshot_id_a)?, nodes(snapshot_id_b)?) {
+        (None, None) => return Err(RusticGuiError::PathNotFound { path: format!("/{prefix}") }),
+        (a, b) => (
+            a.unwrap_or_else(|| Box::new(std::iter::empty())),
+            b.unwrap_or_else(|| Box::new(std::iter::empty())),
+        ),
+    };
+
+    let mut diff = diff_nodes(nodes_a, nodes_b, options, change_classes)
+        .map_err(|e| RusticGuiError::Internal(format!("Tree konnte nicht gelesen werden: {e}")))?;
+    diff.snapshot_a = snapshot_id_a.to_string();
+    diff.snapshot_b = snapshot_id_b.to_string();
+
+    info!(
+        added = diff.stats.added,
+        removed = diff.stats.removed,
+        changed = diff.stats.changed,
+        metadata_only = diff.stats.metadata_only,
+        size_delta = diff.stats.size_delta,
+        "Snapshot-Diff berechnet"
+    );
+    Ok(diff)
+}
+
+/// Führt zwei nach Pfad sortierte Knoten-Streams zusammen und berechnet den Diff.
+///
+/// `compare` bestimmt die Änderungsklassen für Pfade, die in beiden Streams vorkommen.
+/// Einträge außerhalb von `options.path_prefix` werden übergangen.
+pub(crate) fn diff_nodes<'a>(
+    nodes_a: DiffNodes<'a>,
+    nodes_b: DiffNodes<'a>,
+    options: &SnapshotDiffOptions,
+    compare: fn(&Node, &Node) -> Vec<DiffChangeClass>,
+) -> RusticResult<SnapshotDiffDto> {
+    let prefix = options.path_prefix.as_deref().map(|p| Path::new("/").join(p.trim_matches('/')));
+    let limit = options.limit.unwrap_or(DEFAULT_DIFF_PAGE_SIZE);
+    let rollup_depth = options.rollup_depth.unwrap_or(DEFAULT_ROLLUP_DEPTH) as usize;
+
+    let mut result = SnapshotDiffDto { offset: options.offset, ..Default::default() };
+    let mut directories: BTreeMap<PathBuf, DirectoryDiffDto> = BTreeMap::new();
+    let mut a = nodes_a.peekable();
+    let mut b = nodes_b.peekable();
+
+    loop {
+        // Fehler aus den Streams sofort weitergeben
+        if let Some(Err(_)) = a.peek() {
+            return Err(a.next().unwrap().unwrap_err());
+        }
+        if let Some(Err(_)) = b.peek() {
+            return Err(b.next().unwrap().unwrap_err());
+        }
+        let order = match (a.peek(), b.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(Ok((path_a, _))), Some(Ok((path_b, _)))) => path_a.cmp(path_b),
+            _ => unreachable!("Fehler wurden oben behandelt"),
+        };
+        let (path, before, after) = match order {
+            Ordering::Less => {
+                let (path, node) = a.next().unwrap()?;
+                (path, Some(node), None)
+            }
+            Ordering::Greater => {
+                let (path, node) = b.next().unwrap()?;
+                (path, None, Some(node))
+            }
+            Ordering::Equal => {
+                let (path, node_a) = a.next().unwrap()?;
+                let (_, node_b) = b.next().unwrap()?;
+                (path, Some(node_a), Some(node_b))
+            }
+        };
+
+        if prefix.as_ref().is_some_and(|prefix| !path.starts_with(prefix)) {
+            continue;
+        }
+        let Some(entry) = diff_entry(&path, before.as_ref(), after.as_ref(), options, compare)
+        else {
+            continue;
+        };
+
+        let stats = &mut result.stats;
+        stats.size_delta += entry.size_delta;
+        match entry.kind {
+            DiffEntryKind::Added => {
+                stats.added += 1;
+                stats.bytes_added += entry.size_after.unwrap_or(0);
+            }
+            DiffEntryKind::Removed => {
+                stats.removed += 1;
+                stats.bytes_removed += entry.size_before.unwrap_or(0);
+            }
+            DiffEntryKind::Changed if is_metadata_only(&entry.changes) => stats.metadata_only += 1,
+            DiffEntryKind::Changed => stats.changed += 1,
+        }
+
+        for dir in path.ancestors().skip(1) {
+            let depth = dir.components().filter(|c| matches!(c, Component::Normal(_))).count();
+            if depth == 0 || depth > rollup_depth {
+                continue;
+            }
+            let rollup = directories.entry(dir.to_path_buf()).or_insert_with(|| DirectoryDiffDto {
+                path: dir.to_string_lossy().to_string(),
+                ..Default::default()
+            });
+            match entry.kind {
+                DiffEntryKind::Added => rollup.added += 1,
+                DiffEntryKind::Removed => rollup.removed += 1,
+                DiffEntryKind::Changed => rollup.changed += 1,
+            }
+            rollup.size_delta += entry.size_delta;
+        }
+
+        let index = result.total_entries;
+        result.total_entries += 1;
+        if index >= options.offset && index - options.offset < limit {
+            result.entries.push(entry);
+        }
+    }
+
+    result.has_more = result.total_entries > options.offset.saturating_add(limit);
+    result.directories = directories.into_values().collect();
+    Ok(result)
+}
+
+/// Vergleicht einen Eintrag aus beiden Snapshots.
+///
+/// # Returns
+/// None, wenn sich nichts (bzw. bei `ignore_metadata` nur Metadaten) geändert hat
+fn diff_entry(
+    path: &Path,
+    before: Option<&Node>,
+    after: Option<&Node>,
+    options: &SnapshotDiffOptions,
+    compare: fn(&Node, &Node) -> Vec<DiffChangeClass>,
+) -> Option<DiffEntryDto> {
+    let size = |node: Option<&Node>| node.filter(|n| !n.is_dir()).map(|n| n.meta.size);
+    let (size_before, size_after) = (size(before), size(after));
+
+    let (kind, changes) = match (before, after) {
+        (Some(_), None) => (DiffEntryKind::Removed, Vec::new()),
+        (None, Some(_)) => (DiffEntryKind::Added, Vec::new()),
+        (Some(a), Some(b)) => {
+            let mut changes = compare(a, b);
+            if options.ignore_metadata && is_metadata_only(&changes) {
+                changes.clear();
+            }
+            if changes.is_empty() {
+                return None;
+            }
+            (DiffEntryKind::Changed, changes)
+        }
+        (None, None) => return None,
+    };
+
+    Some(DiffEntryDto {
+        path: path.to_string_lossy().to_string(),
+        kind,
+        changes,
+        is_directory: after.or(before).is_some_and(Node::is_dir),
+        size_before,
+        size_after,
+        size_delta: size_after.unwrap_or(0) as i64 - size_before.unwrap_or(0) as i64,
+    })
+}
+
+/// Bestimmt, welche Eigenschaften sich zwischen zwei Knoten desselben Pfades geändert haben
+pub(crate) fn change_classes(a: &Node, b: &Node) -> Vec<DiffChangeClass> {
+    if std::mem::discriminant(&a.node_type) != std::mem::discriminant(&b.node_type) {
+        return vec![DiffChangeClass::Type];
+    }
+
+    let mut changes = Vec::new();
+    if a.content != b.content || (!a.is_file() && a.node_type != b.node_type) {
+        changes.push(DiffChangeClass::Content);
+    }
+    if !a.is_dir() && a.meta.size != b.meta.size {
+        changes.push(DiffChangeClass::Size);
+    }
+    if a.meta.mtime != b.meta.mtime {
+        changes.push(DiffChangeClass::Mtime);
+    }
+    if a.meta.mode != b.meta.mode {
+        changes.push(DiffChangeClass::Permissions);
+    }
+    if (a.meta.uid, a.meta.gid, &a.meta.user, &a.meta.group)
+        != (b.meta.uid, b.meta.gid, &b.meta.user, &b.meta.group)
+    {
+        changes.push(DiffChangeClass::Owner);
+    }
+    changes
+}
+
+/// Nur Änderungszeit, Rechte oder Besitzer geändert (kein Inhalt, keine Größe, kein Typ)
+fn is_metadata_only(changes: &[DiffChangeClass]) -> bool {
+    !changes.is_empty()
+        && changes.iter().all(|c| {
+            matches!(
+                c,
+                DiffChangeClass::Mtime | DiffChangeClass::Permissions | DiffChangeClass::Owner
+            )
         })
-        .collect();
-    snapshots.sort_by(|a, b| b.time.cmp(&a.time));
-    Ok(snapshots)
 }
 
-/// Listet Snapshots mit optionalen Filtern.
+/// Standardlimit für Suchtreffer
+const DEFAULT_FIND_LIMIT: u64 = 10_000;
+
+/// Gefundene Datei innerhalb eines Trees
+#[derive(Clone)]
+struct FoundFile {
+    path: PathBuf,
+    size: u64,
+    mtime: Option<DateTime<Local>>,
+    version: String,
+}
+
+/// Such-Muster für Pfade
+enum PathPattern {
+    Glob(globset::GlobMatcher),
+    Regex(regex::Regex),
+}
+
+/// Prüft Pfad, Größe und Änderungszeit von Dateien gegen eine `FindQuery`
+struct FindMatcher {
+    pattern: PathPattern,
+    /// Muster ohne `/` gelten nur für den Dateinamen
+    name_only: bool,
+    /// Glob-Muster mit führendem `/` werden gegen den absoluten Pfad geprüft
+    absolute: bool,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    modified_after: Option<DateTime<FixedOffset>>,
+    modified_before: Option<DateTime<FixedOffset>>,
+}
+
+impl FindMatcher {
+    /// # Errors
+    /// `InvalidConfig`, wenn Muster oder Zeitangaben ungültig sind
+    fn new(query: &FindQuery) -> Result<Self, RusticGuiError> {
+        let invalid = |field: &str| RusticGuiError::InvalidConfig { field: field.to_string() };
+        if query.pattern.is_empty() {
+            return Err(invalid("pattern"));
+        }
+
+        let pattern = match query.kind {
+            FindPatternKind::Glob => PathPattern::Glob(
+                globset::GlobBuilder::new(&query.pattern)
+                    .case_insensitive(query.case_insensitive)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|_| invalid("pattern"))?
+                    .compile_matcher(),
+            ),
+            FindPatternKind::Regex => PathPattern::Regex(
+                regex::RegexBuilder::new(&query.pattern)
+                    .case_insensitive(query.case_insensitive)
+                    .build()
+                    .map_err(|_| invalid("pattern"))?,
+            ),
+        };
+        let time = |value: &Option<String>, field: &str| {
+            value
+                .as_deref()
+                .map(|v| DateTime::parse_from_rfc3339(v).map_err(|_| invalid(field)))
+                .transpose()
+        };
+
+        Ok(Self {
+            pattern,
+            name_only: !query.pattern.contains('/'),
+            absolute: query.pattern.starts_with('/'),
+            min_size: query.min_size,
+            max_size: query.max_size,
+            modified_after: time(&query.modified_after, "modified_after")?,
+            modified_before: time(&query.modified_before, "modified_before")?,
+        })
+    }
+
+    /// Prüft eine Datei mit ihrem absoluten Pfad im Snapshot
+    fn matches(&self, path: &Path, node: &Node) -> bool {
+        let size = node.meta.size;
+        if self.min_size.is_some_and(|min| size < min)
+            || self.max_size.is_some_and(|max| size > max)
+        {
+            return false;
+        }
+        if self.modified_after.is_some() || self.modified_before.is_some() {
+            let Some(mtime) = node.meta.mtime else {
+                return false;
+            };
+            if self.modified_after.is_some_and(|after| mtime < after)
+                || self.modified_before.is_some_and(|before| mtime > before)
+            {
+                return false;
+            }
+        }
+
+        let candidate = if self.name_only {
+            Path::new(node.name().as_os_str()).to_path_buf()
+        } else if self.absolute || matches!(self.pattern, PathPattern::Regex(_)) {
+            path.to_path_buf()
+        } else {
+            path.strip_prefix("/").unwrap_or(path).to_path_buf()
+        };
+        match &self.pattern {
+            PathPattern::Glob(glob) => glob.is_match(&candidate),
+            PathPattern::Regex(regex) => regex.is_match(&candidate.to_string_lossy()),
+        }
+    }
+}
+
+/// Kennung des Inhalts einer Datei (gleiche Blob-Liste = gleiche Version)
+///
+/// SHA-256 über die IDs der Daten-Blobs; damit ist die Kennung stabil und eindeutig.
+fn content_version(node: &Node) -> String {
+    let mut hasher = Sha256::new();
+    for id in node.content.iter().flatten() {
+        hasher.update(id.to_hex().as_bytes());
+    }
+    hasher.finalize().iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Zustand einer Suche über mehrere Snapshots
+struct TreeSearch {
+    /// Treffer vollständig durchsuchter Teilbäume je (Tree-ID, Pfad); unveränderte
+    /// Verzeichnisse haben in allen Snapshots dieselbe Tree-ID und werden daher nur einmal
+    /// durchsucht
+    searched: HashMap<(TreeId, PathBuf), Vec<FoundFile>>,
+    /// Treffer des aktuellen Snapshots
+    found: Vec<FoundFile>,
+    /// Anzahl noch erlaubter Treffer
+    remaining: u64,
+    /// Limit erreicht, die Suche wurde beendet
+    truncated: bool,
+    trees_scanned: u64,
+}
+
+/// Durchsucht einen Tree rekursiv.
+///
+/// Treffer werden in `search.found` gesammelt. Bereits durchsuchte Teilbäume werden nicht
+/// erneut gelesen, sondern ihre Treffer übernommen. Ist das Limit erreicht, wird nicht
+/// weiter abgestiegen.
+fn search_tree<P, S: IndexedTree>(
+    repo: &Repository<P, S>,
+    tree_id: TreeId,
+    path: &Path,
+    matcher: &FindMatcher,
+    search: &mut TreeSearch,
+) -> RusticResult<()> {
+    let key = (tree_id, path.to_path_buf());
+    if let Some(matches) = search.searched.get(&key) {
+        let take = usize::try_from(search.remaining).unwrap_or(usize::MAX).min(matches.len());
+        search.found.extend_from_slice(&matches[..take]);
+        search.remaining -= take as u64;
+        search.truncated |= take < matches.len();
+        return Ok(());
+    }
+
+    let tree = repo.get_tree(&tree_id)?;
+    search.trees_scanned += 1;
+
+    let found_before = search.found.len();
+    for node in &tree.nodes {
+        if search.truncated {
+            return Ok(());
+        }
+        let node_path = path.join(node.name());
+        if let Some(subtree) = node.subtree {
+            search_tree(repo, subtree, &node_path, matcher, search)?;
+        } else if node.is_file() && matcher.matches(&node_path, node) {
+            if search.remaining == 0 {
+                search.truncated = true;
+                return Ok(());
+            }
+            search.remaining -= 1;
+            search.found.push(FoundFile {
+                path: node_path,
+                size: node.meta.size,
+                mtime: node.meta.mtime,
+                version: content_version(node),
+            });
+        }
+    }
+
+    if !search.truncated {
+        let _ = search.searched.insert(key, search.found[found_before..].to_vec());
+    }
+    Ok(())
+}
+
+/// Sucht Dateien über alle (bzw. gefilterten) Snapshots.
+///
+/// Jeder Snapshot, in dem eine passende Datei vorkommt, liefert einen Treffer mit Größe,
+/// Änderungszeit und Inhaltskennung. So lässt sich beantworten, wann eine Datei zuletzt
+/// existierte und welche Version sie hatte.
 ///
 /// # Arguments
 /// * `repository_path` - Pfad zum Repository
 /// * `password` - Repository-Passwort
-/// * `filter` - Optionale Filter-Optionen
+/// * `query` - Muster (Glob/Regex) sowie Größen- und Zeitfilter
+/// * `filter` - Optionaler Snapshot-Filter (Tags, Host, Zeitraum)
 ///
 /// # Returns
-/// Vektor von SnapshotDto
-pub async fn list_snapshots_filtered(
+/// Treffer, neueste Snapshots zuerst
+///
+/// # Errors
+/// `InvalidConfig`, wenn das Muster oder eine Zeitangabe ungültig ist
+pub fn find_in_snapshots(
     repository_path: &str,
     password: &str,
-    filter: Option<SnapshotFilter>,
-) -> Result<Vec<SnapshotDto>, RusticGuiError> {
-    info!(repo = repository_path, "Lese Snapshots mit Filter aus Repository");
+    query: &FindQuery,
+    filter: Option<&SnapshotFilter>,
+) -> Result<FindResultDto, RusticGuiError> {
+    info!(repo = repository_path, pattern = %query.pattern, "Suche Dateien in Snapshots");
+    let matcher = FindMatcher::new(query)?;
+    let snapshot_matcher = filter.map(SnapshotMatcher::new).transpose()?;
+    let limit = query.limit.unwrap_or(DEFAULT_FIND_LIMIT);
 
-    // Erst alle Snapshots laden
-    let mut snapshots = list_snapshots(repository_path, password).await?;
+    let mut repo_opts = RepositoryOptions::default();
+    repo_opts.password = Some(password.to_string());
+    let mut backend_opts = BackendOptions::default();
+    backend_opts.repository = Some(repository_path.to_string());
+    let backends = backend_opts.to_backends().map_err(|e| {
+        RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+    })?;
+    let repo = Repository::new(&repo_opts, &backends)
+        .map_err(|e| {
+            error!(?e, "Fehler beim Initialisieren des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .open()
+        .map_err(|e| {
+            error!(?e, "Fehler beim Öffnen des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .to_indexed_ids()
+        .map_err(|e| {
+            error!(?e, "Repository-Indexierung fehlgeschlagen");
+            RusticGuiError::Internal(format!("Repository-Indexierung fehlgeschlagen: {e}"))
+        })?;
 
-    // Filter anwenden falls vorhanden
-    if let Some(f) = filter {
-        // Tag-Filter (OR-Logic: wenn irgendeiner der Tags passt)
-        if let Some(filter_tags) = f.tags {
-            if !filter_tags.is_empty() {
-                snapshots.retain(|snap| snap.tags.iter().any(|tag| filter_tags.contains(tag)));
-            }
-        }
+    let mut snaps = repo.get_all_snapshots().map_err(|e| {
+        RusticGuiError::Internal(format!("Snapshots konnten nicht geladen werden: {e}"))
+    })?;
+    if let Some(matcher) = &snapshot_matcher {
+        snaps.retain(|snap| matcher.matches(snap));
+    }
+    snaps.sort_by_key(|snap| std::cmp::Reverse(snap.time));
+
+    let mut result = FindResultDto::default();
+    let mut search = TreeSearch {
+        searched: HashMap::new(),
+        found: Vec::new(),
+        remaining: limit,
+        truncated: false,
+        trees_scanned: 0,
+    };
+    for snap in &snaps {
+        result.snapshots_searched += 1;
+        search_tree(&repo, snap.tree, Path::new("/"), &matcher, &mut search).map_err(|e| {
+            RusticGuiError::Internal(format!("Tree konnte nicht gelesen werden: {e}"))
+        })?;
 
-        // Hostname-Filter
-        if let Some(filter_hostname) = f.hostname {
-            snapshots.retain(|snap| snap.hostname == filter_hostname);
+        result.matches.extend(search.found.drain(..).map(|file| FileMatchDto {
+            snapshot_id: snap.id.to_string(),
+            snapshot_time: snap.time.to_rfc3339(),
+            hostname: snap.hostname.clone(),
+            path: file.path.to_string_lossy().to_string(),
+            size: file.size,
+            modified: file.mtime.map(|t| t.to_rfc3339()),
+            version: file.version,
+        }));
+        if search.truncated {
+            break;
         }
+    }
+    result.truncated = search.truncated;
+    result.trees_scanned = search.trees_scanned;
+
+    info!(
+        matches = result.matches.len(),
+        snapshots = result.snapshots_searched,
+        trees = result.trees_scanned,
+        "Suche abgeschlossen"
+    );
+    Ok(result)
+}
+
+/// Ermittelt die Versionen einer Datei über alle (bzw. gefilterten) Snapshots.
+///
+/// Die Snapshots werden chronologisch durchlaufen; aufeinanderfolgende Snapshots mit
+/// demselben Inhalt werden zu einer Version zusammengefasst. Snapshots ohne die Datei
+/// (z.B. von anderen Jobs oder Hosts) unterbrechen eine Version nicht. Snapshots mit
+/// identischem Root-Tree werden nur einmal gelesen.
+///
+/// # Arguments
+/// * `repository_path` - Pfad zum Repository
+/// * `password` - Repository-Passwort
+/// * `path` - Pfad der Datei im Snapshot
+/// * `filter` - Optionaler Snapshot-Filter (Tags, Host, Zeitraum)
+///
+/// # Returns
+/// Versionen der Datei, neueste zuerst
+pub fn file_history(
+    repository_path: &str,
+    password: &str,
+    path: &str,
+    filter: Option<&SnapshotFilter>,
+) -> Result<FileHistoryDto, RusticGuiError> {
+    info!(repo = repository_path, path, "Lade Versionsgeschichte");
+    let snapshot_matcher = filter.map(SnapshotMatcher::new).transpose()?;
+
+    let mut repo_opts = RepositoryOptions::default();
+    repo_opts.password = Some(password.to_string());
+    let mut backend_opts = BackendOptions::default();
+    backend_opts.repository = Some(repository_path.to_string());
+    let backends = backend_opts.to_backends().map_err(|e| {
+        RusticGuiError::Internal(format!("Backend konnte nicht initialisiert werden: {e}"))
+    })?;
+    let repo = Repository::new(&repo_opts, &backends)
+        .map_err(|e| {
+            error!(?e, "Fehler beim Initialisieren des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .open()
+        .map_err(|e| {
+            error!(?e, "Fehler beim Öffnen des Repositories");
+            RusticGuiError::RepositoryNotFound { path: repository_path.to_string() }
+        })?
+        .to_indexed_ids()
+        .map_err(|e| {
+            error!(?e, "Repository-Indexierung fehlgeschlagen");
+            RusticGuiError::Internal(format!("Repository-Indexierung fehlgeschlagen: {e}"))
+        })?;
+
+    let mut snaps = repo.get_all_snapshots().map_err(|e| {
+        RusticGuiError::Internal(format!("Snapshots konnten nicht geladen werden: {e}"))
+    })?;
+    if let Some(matcher) = &snapshot_matcher {
+        snaps.retain(|snap| matcher.matches(snap));
+    }
+    snaps.sort_by_key(|snap| snap.time);
+
+    let relative = path.trim_matches('/');
+    let mut history = FileHistoryDto { path: format!("/{relative}"), ..Default::default() };
+    let mut nodes: HashMap<TreeId, Option<Node>> = HashMap::new();
+    for snap in &snaps {
+        history.snapshots_searched += 1;
+        let node = nodes
+            .entry(snap.tree)
+            .or_insert_with(|| {
+                repo.node_from_snapshot_and_path(snap, relative).ok().filter(Node::is_file)
+            })
+            .clone();
+        let Some(node) = node else {
+            continue;
+        };
+        history.snapshots_containing += 1;
+        add_version(&mut history.versions, snap, &node);
+    }
+    history.versions.reverse();
 
-        // Zeitraum-Filter (von)
-        if let Some(time_from) = f.time_from {
-            snapshots.retain(|snap| snap.time >= time_from);
+    info!(
+        versions = history.versions.len(),
+        snapshots = history.snapshots_containing,
+        "Versionsgeschichte geladen"
+    );
+    Ok(history)
+}
+
+/// Ordnet einen Snapshot der letzten Version zu oder beginnt eine neue Version
+fn add_version(versions: &mut Vec<FileVersionDto>, snap: &SnapshotFile, node: &Node) {
+    let version = content_version(node);
+    let snapshot_id = snap.id.to_string();
+    let snapshot_time = snap.time.to_rfc3339();
+
+    match versions.last_mut() {
+        Some(last) if last.version == version => {
+            last.last_snapshot_id = snapshot_id;
+            last.last_snapshot_time = snapshot_time;
+            last.snapshot_count += 1;
         }
+        _ => versions.push(FileVersionDto {
+            version,
+            size: node.meta.size,
+            modified: node.meta.mtime.map(|t| t.to_rfc3339()),
+            first_snapshot_id: snapshot_id.clone(),
+            first_snapshot_time: snapshot_time.clone(),
+            last_snapshot_id: snapshot_id,
+            last_snapshot_time: snapshot_time,
+            snapshot_count: 1,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rustic_core::repofile::{Metadata, NodeType};
+    use std::ffi::OsStr;
+
+    fn file(path: &str, size: u64) -> RusticResult<(PathBuf, Node)> {
+        let meta = Metadata { size, ..Default::default() };
+        Ok((PathBuf::from(path), Node::new_node(OsStr::new("f"), NodeType::File, meta)))
+    }
+
+    fn dir(path: &str) -> RusticResult<(PathBuf, Node)> {
+        Ok((
+            PathBuf::from(path),
+            Node::new_node(OsStr::new("d"), NodeType::Dir, Metadata::default()),
+        ))
+    }
+
+    fn diff(
+        a: Vec<RusticResult<(PathBuf, Node)>>,
+        b: Vec<RusticResult<(PathBuf, Node)>>,
+        options: &SnapshotDiffOptions,
+    ) -> SnapshotDiffDto {
+        diff_nodes(Box::new(a.into_iter()), Box::new(b.into_iter()), options, change_classes)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_diff_merge_join_and_rollups() {
+        let a =
+            vec![dir("/docs"), file("/docs/a.txt", 10), file("/docs/b.txt", 5), file("/old", 7)];
+        let b = vec![
+            dir("/docs"),
+            file("/docs/a.txt", 12),
+            file("/docs/b.txt", 5),
+            dir("/docs/new"),
+            file("/docs/new/c.txt", 3),
+        ];
+        let result = diff(a, b, &SnapshotDiffOptions::default());
+
+        let entries: Vec<_> = result.entries.iter().map(|e| (e.path.as_str(), e.kind)).collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("/docs/a.txt", DiffEntryKind::Changed),
+                ("/docs/new", DiffEntryKind::Added),
+                ("/docs/new/c.txt", DiffEntryKind::Added),
+                ("/old", DiffEntryKind::Removed),
+            ]
+        );
+        assert_eq!(result.entries[0].changes, vec![DiffChangeClass::Size]);
+        assert_eq!(result.entries[0].size_delta, 2);
+        assert_eq!(result.stats.added, 2);
+        assert_eq!(result.stats.removed, 1);
+        assert_eq!(result.stats.changed, 1);
+        assert_eq!(result.stats.size_delta, 2 + 3 - 7);
+
+        let docs = &result.directories[0];
+        assert_eq!(docs.path, "/docs");
+        assert_eq!((docs.added, docs.removed, docs.changed, docs.size_delta), (2, 0, 1, 5));
+        assert_eq!(result.directories[1].path, "/docs/new");
+        assert_eq!(result.directories.len(), 2);
+    }
+
+    #[test]
+    fn test_diff_metadata_classes_and_paging() {
+        let touched = |path: &str| {
+            let meta = Metadata {
+                size: 1,
+                mtime: Some(chrono::Local.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+                mode: Some(0o600),
+                ..Default::default()
+            };
+            Ok((PathBuf::from(path), Node::new_node(OsStr::new("f"), NodeType::File, meta)))
+        };
+        let a = || vec![file("/a", 1), file("/b", 1), file("/c", 1), file("/d", 1)];
+        let b = || vec![touched("/a"), file("/b", 2), touched("/c"), dir("/d")];
+
+        let options = SnapshotDiffOptions { offset: 1, limit: Some(2), ..Default::default() };
+        let result = diff(a(), b(), &options);
+        assert_eq!(result.total_entries, 4);
+        assert!(result.has_more);
+        let paths: Vec<_> = result.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["/b", "/c"]);
+        assert_eq!(
+            result.entries[1].changes,
+            vec![DiffChangeClass::Mtime, DiffChangeClass::Permissions]
+        );
+        assert_eq!(result.stats.metadata_only, 2);
+        assert_eq!(result.stats.changed, 2);
+
+        let options = SnapshotDiffOptions { ignore_metadata: true, ..Default::default() };
+        let result = diff(a(), b(), &options);
+        let paths: Vec<_> = result.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["/b", "/d"]);
+        assert_eq!(result.entries[1].changes, vec![DiffChangeClass::Type]);
+    }
+
+    #[test]
+    fn test_find_matcher_patterns_and_filters() {
+        let node = |name: &str, size: u64| {
+            let meta = Metadata {
+                size,
+                mtime: Some(chrono::Local.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap()),
+                ..Default::default()
+            };
+            Node::new_node(OsStr::new(name), NodeType::File, meta)
+        };
+        let matcher = |query: FindQuery| FindMatcher::new(&query).unwrap();
+        let path = Path::new("/home/user/docs/Report.PDF");
+        let report = node("Report.PDF", 2048);
 
-        // Zeitraum-Filter (bis)
-        if let Some(time_to) = f.time_to {
-            snapshots.retain(|snap| snap.time <= time_to);
+        // Ohne "/" nur Dateiname, mit "/" der Pfad
+        let glob = |pattern: &str| FindQuery { pattern: pattern.to_string(), ..Default::default() };
+        assert!(!matcher(glob("*.pdf")).matches(path, &report));
+        assert!(
+            matcher(FindQuery { case_insensitive: true, ..glob("*.pdf") }).matches(path, &report)
+        );
+        assert!(matcher(glob("**/docs/*.PDF")).matches(path, &report));
+        assert!(matcher(glob("/home/*/docs/*")).matches(path, &report));
+        assert!(!matcher(glob("home/*.PDF")).matches(path, &report));
+
+        let regex = FindQuery {
+            pattern: r"^/home/.+/Report\.PDF$".to_string(),
+            kind: FindPatternKind::Regex,
+            ..Default::default()
+        };
+        assert!(matcher(regex).matches(path, &report));
+
+        let sized = FindQuery { min_size: Some(4096), ..glob("*") };
+        assert!(!matcher(sized).matches(path, &report));
+        let dated = FindQuery { modified_after: Some("2024-07-01T00:00:00Z".into()), ..glob("*") };
+        assert!(!matcher(dated).matches(path, &report));
+        let dated = FindQuery { modified_before: Some("2024-07-01T00:00:00Z".into()), ..glob("*") };
+        assert!(matcher(dated).matches(path, &report));
+
+        assert!(FindMatcher::new(&glob("")).is_err());
+        assert!(
+            FindMatcher::new(&FindQuery { modified_after: Some("gestern".into()), ..glob("*") })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_add_version_collapses_unchanged_content() {
+        let content_a = Some(vec![rustic_core::DataId::from(Id::random())]);
+        let content_b = Some(vec![rustic_core::DataId::from(Id::random())]);
+        let node = |content: &Option<Vec<rustic_core::DataId>>, size: u64| {
+            let mut node = Node::new_node(
+                OsStr::new("f"),
+                NodeType::File,
+                Metadata { size, ..Default::default() },
+            );
+            node.content = content.clone();
+            node
+        };
+        let snap = |day: u32| SnapshotFile {
+            id: SnapshotId::from(Id::random()),
+            time: chrono::Local.with_ymd_and_hms(2024, 3, day, 0, 0, 0).unwrap(),
+            ..Default::default()
+        };
+
+        let snaps = [snap(1), snap(2), snap(3), snap(4)];
+        let mut versions = Vec::new();
+        add_version(&mut versions, &snaps[0], &node(&content_a, 10));
+        add_version(&mut versions, &snaps[1], &node(&content_a, 10));
+        add_version(&mut versions, &snaps[2], &node(&content_b, 12));
+        add_version(&mut versions, &snaps[3], &node(&content_a, 10));
+
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0].snapshot_count, 2);
+        assert_eq!(versions[0].first_snapshot_id, snaps[0].id.to_string());
+        assert_eq!(versions[0].last_snapshot_id, snaps[1].id.to_string());
+        assert_eq!(versions[1].size, 12);
+        // Rückkehr zum alten Inhalt ist eine neue Version mit gleicher Kennung
+        assert_eq!(versions[2].version, versions[0].version);
+    }
+
+    #[test]
+    fn test_content_version_uses_blob_ids() {
+        let id = Id::random();
+        let mut node = Node::new_node(OsStr::new("f"), NodeType::File, Metadata::default());
+        node.content = Some(vec![rustic_core::DataId::from(id)]);
+        let version = content_version(&node);
+        assert_eq!(version.len(), 64);
+        assert_eq!(version, content_version(&node.clone()));
+
+        node.content = Some(vec![rustic_core::DataId::from(id), rustic_core::DataId::from(id)]);
+        assert_ne!(content_version(&node), version);
+    }
+
+    fn snapshot(day: u32, hostname: &str, tags: &str, size: u64) -> SnapshotFile {
+        let mut summary = rustic_core::repofile::SnapshotSummary::default();
+        summary.total_bytes_processed = size;
+        SnapshotFile {
+            id: SnapshotId::from(Id::random()),
+            time: chrono::Local.with_ymd_and_hms(2024, 5, day, 12, 0, 0).unwrap(),
+            hostname: hostname.to_string(),
+            username: "anna".to_string(),
+            tags: tags.parse().unwrap(),
+            paths: "/home/anna,/etc".parse().unwrap(),
+            summary: Some(summary),
+            ..Default::default()
         }
     }
 
-    Ok(snapshots)
+    #[test]
+    fn test_snapshot_matcher() {
+        let snap = snapshot(10, "laptop", "daily,home", 100);
+        let matches =
+            |filter: SnapshotFilter| SnapshotMatcher::new(&filter).unwrap().matches(&snap);
+        let tags = |tags: &[&str]| Some(tags.iter().map(|t| t.to_string()).collect::<Vec<_>>());
+
+        assert!(matches(SnapshotFilter::default()));
+        assert!(matches(SnapshotFilter { tags: tags(&["daily", "weekly"]), ..Default::default() }));
+        assert!(!matches(SnapshotFilter {
+            tags: tags(&["daily", "weekly"]),
+            tag_mode: TagMatchMode::All,
+            ..Default::default()
+        }));
+        assert!(matches(SnapshotFilter {
+            tag_groups: Some(vec![tags(&["weekly"]).unwrap(), tags(&["daily", "home"]).unwrap()]),
+            ..Default::default()
+        }));
+        assert!(!matches(SnapshotFilter {
+            tag_groups: Some(vec![tags(&["daily", "weekly"]).unwrap()]),
+            ..Default::default()
+        }));
+
+        assert!(matches(SnapshotFilter {
+            hostnames: tags(&["server", "laptop"]),
+            ..Default::default()
+        }));
+        assert!(!matches(SnapshotFilter { usernames: tags(&["bernd"]), ..Default::default() }));
+        assert!(matches(SnapshotFilter {
+            path_contains: Some("anna".into()),
+            ..Default::default()
+        }));
+        let prefix = snap.id.to_string()[..8].to_ascii_uppercase();
+        assert!(matches(SnapshotFilter { id_prefix: Some(prefix), ..Default::default() }));
+
+        // Reine Datumsangaben schließen den ganzen Tag ein
+        assert!(matches(SnapshotFilter {
+            time_from: Some("2024-05-10".into()),
+            time_to: Some("2024-05-10".into()),
+            ..Default::default()
+        }));
+        assert!(!matches(SnapshotFilter {
+            time_from: Some("2024-05-11".into()),
+            ..Default::default()
+        }));
+        assert!(
+            SnapshotMatcher::new(&SnapshotFilter {
+                time_to: Some("10.05.2024".into()),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_page_snapshots_sorts_and_pages() {
+        let snaps = vec![
+            snapshot(1, "b", "", 300),
+            snapshot(2, "a", "", 100),
+            snapshot(3, "c", "keep", 200),
+        ];
+        let ids: Vec<String> = snaps.iter().map(|s| s.id.to_string()).collect();
+        let page = |query: SnapshotQuery| page_snapshots(snaps.clone(), None, &query, "repo");
+
+        let newest = page(SnapshotQuery { limit: Some(2), ..Default::default() });
+        assert_eq!(newest.total, 3);
+        assert!(newest.has_more);
+        assert_eq!(newest.snapshots[0].id, ids[2]);
+        assert_eq!(newest.snapshots[1].id, ids[1]);
+
+        let rest = page(SnapshotQuery { offset: 2, limit: Some(2), ..Default::default() });
+        assert_eq!(rest.snapshots.len(), 1);
+        assert!(!rest.has_more);
+
+        let by_size = page(SnapshotQuery {
+            sort: SnapshotSortKey::Size,
+            ascending: true,
+            ..Default::default()
+        });
+        let sizes: Vec<u64> = by_size.snapshots.iter().map(|s| s.total_size).collect();
+        assert_eq!(sizes, vec![100, 200, 300]);
+
+        let filter = SnapshotFilter { tags: Some(vec!["keep".into()]), ..Default::default() };
+        let matcher = SnapshotMatcher::new(&filter).unwrap();
+        let kept = page_snapshots(snaps.clone(), Some(&matcher), &SnapshotQuery::default(), "repo");
+        assert_eq!(kept.total, 1);
+        assert_eq!(kept.snapshots[0].hostname, "c");
+    }
 }
@@ -0,0 +1,171 @@
+//! Nachholen verpasster geplanter Läufe
+//!
+//! Der Cron-Scheduler feuert nur, solange die App läuft. Nach App-Start oder dem Aufwachen
+//! aus dem Ruhezustand wird hier anhand des letzten Laufs entschieden, ob ein verpasster
+//! Termin gemäß der Catch-up-Policy des Jobs nachgeholt werden soll.
+
+use super::previous_fire_time;
+use crate::types::CatchUpPolicy;
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Toleranz, ab der eine Differenz zwischen Wanduhr und monotoner Uhr als Ruhezustand gilt
+const WAKE_TOLERANCE: Duration = Duration::from_secs(120);
+
+/// Prüft, ob ein geplanter Lauf verpasst wurde und nachgeholt werden soll
+///
+/// # Arguments
+/// * `cron_expr` - Cron-Expression des Jobs
+/// * `last_run` - Startzeitpunkt des letzten Laufs (None, wenn der Job nie lief)
+/// * `schedule_since` - Zeitpunkt, seit dem der Zeitplan gilt (Referenz ohne bisherigen Lauf)
+/// * `now` - Aktueller Zeitpunkt
+/// * `policy` - Catch-up-Policy des Jobs
+///
+/// # Returns
+/// Den verpassten Termin, falls er nachgeholt werden soll. Ohne Lauf und ohne
+/// `schedule_since` existiert kein Referenzzeitpunkt; dann wird nichts nachgeholt.
+pub fn missed_run(
+    cron_expr: &str,
+    last_run: Option<DateTime<Utc>>,
+    schedule_since: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    policy: CatchUpPolicy,
+) -> Option<DateTime<Utc>> {
+    let last_run = last_run.or(schedule_since)?;
+    let missed = match previous_fire_time(cron_expr, now) {
+        Ok(previous) => previous,
+        Err(e) => {
+            tracing::warn!("Letzter Termin für '{}' nicht ermittelbar: {}", cron_expr, e);
+            return None;
+        }
+    };
+    if last_run >= missed {
+        return None;
+    }
+
+    match policy {
+        CatchUpPolicy::Skip => None,
+        CatchUpPolicy::RunOnce => Some(missed),
+        CatchUpPolicy::RunIfOlderThan { max_age_hours } => {
+            let max_age = chrono::Duration::hours(i64::try_from(max_age_hours).unwrap_or(i64::MAX));
+            (now - last_run > max_age).then_some(missed)
+        }
+    }
+}
+
+/// Erkennt das Aufwachen aus dem Ruhezustand und ruft dann `on_wake` auf.
+///
+/// Die monotone Uhr steht während des Ruhezustands still, die Wanduhr nicht. Läuft die
+/// Wanduhr zwischen zwei Prüfungen deutlich weiter als die monotone Uhr, war das System
+/// im Ruhezustand. Die Funktion läuft endlos und sollte als eigener Task gestartet werden.
+///
+/// # Arguments
+/// * `interval` - Prüfintervall
+/// * `on_wake` - Callback nach erkanntem Aufwachen
+pub async fn watch_for_wake<F, Fut>(interval: Duration, on_wake: F)
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut last_wall = SystemTime::now();
+    let mut last_mono = Instant::now();
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let wall_elapsed = SystemTime::now().duration_since(last_wall).unwrap_or_default();
+        let mono_elapsed = last_mono.elapsed();
+        last_wall = SystemTime::now();
+        last_mono = Instant::now();
+
+        if wall_elapsed > mono_elapsed + WAKE_TOLERANCE {
+            tracing::info!(
+                slept_secs = (wall_elapsed - mono_elapsed).as_secs(),
+                "Aufwachen aus dem Ruhezustand erkannt"
+            );
+            on_wake().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts).unwrap().to_utc()
+    }
+
+    const DAILY: &str = "0 0 2 * * *";
+
+    #[test]
+    fn test_no_missed_run_when_last_run_is_recent() {
+        let now = at("2025-03-10T08:00:00Z");
+        let last = Some(at("2025-03-10T02:00:01Z"));
+        assert_eq!(missed_run(DAILY, last, None, now, CatchUpPolicy::RunOnce), None);
+    }
+
+    #[test]
+    fn test_missed_run_policies() {
+        let now = at("2025-03-10T08:00:00Z");
+        let last = Some(at("2025-03-09T02:00:00Z"));
+        let missed = at("2025-03-10T02:00:00Z");
+
+        assert_eq!(missed_run(DAILY, last, None, now, CatchUpPolicy::RunOnce), Some(missed));
+        assert_eq!(missed_run(DAILY, last, None, now, CatchUpPolicy::Skip), None);
+        assert_eq!(
+            missed_run(DAILY, last, None, now, CatchUpPolicy::RunIfOlderThan { max_age_hours: 24 }),
+            Some(missed)
+        );
+        assert_eq!(
+            missed_run(DAILY, last, None, now, CatchUpPolicy::RunIfOlderThan { max_age_hours: 48 }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_never_run_job_uses_schedule_time() {
+        let now = at("2025-03-10T08:00:00Z");
+        let missed = at("2025-03-10T02:00:00Z");
+
+        // Zeitplan vor dem verpassten Termin gesetzt: nachholen
+        let since = Some(at("2025-03-09T12:00:00Z"));
+        assert_eq!(missed_run(DAILY, None, since, now, CatchUpPolicy::RunOnce), Some(missed));
+        assert_eq!(
+            missed_run(
+                DAILY,
+                None,
+                since,
+                now,
+                CatchUpPolicy::RunIfOlderThan { max_age_hours: 12 }
+            ),
+            Some(missed)
+        );
+        assert_eq!(
+            missed_run(
+                DAILY,
+                None,
+                since,
+                now,
+                CatchUpPolicy::RunIfOlderThan { max_age_hours: 24 }
+            ),
+            None
+        );
+
+        // Zeitplan erst nach dem letzten Termin gesetzt: nichts verpasst
+        let since = Some(at("2025-03-10T03:00:00Z"));
+        assert_eq!(missed_run(DAILY, None, since, now, CatchUpPolicy::RunOnce), None);
+
+        // Ein vorhandener Lauf hat Vorrang vor dem Zeitpunkt der Planung
+        let last = Some(at("2025-03-10T02:00:01Z"));
+        let since = Some(at("2025-03-01T00:00:00Z"));
+        assert_eq!(missed_run(DAILY, last, since, now, CatchUpPolicy::RunOnce), None);
+    }
+
+    #[test]
+    fn test_job_without_reference_is_not_caught_up() {
+        let now = at("2025-03-10T08:00:00Z");
+        assert_eq!(missed_run(DAILY, None, None, now, CatchUpPolicy::RunOnce), None);
+    }
+}
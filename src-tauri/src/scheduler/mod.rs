@@ -3,19 +3,63 @@
 /// Dieser Modul implementiert automatisierte Backup-Jobs mit Cron-Scheduling
 /// basierend auf tokio-cron-scheduler.
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use croner::Cron;
+use croner::parser::{CronParser, Seconds};
 use futures::future::BoxFuture;
 use std::collections::HashMap;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use uuid::Uuid;
 
+pub mod catch_up;
+pub mod queue;
+
+pub use catch_up::{missed_run, watch_for_wake};
+pub use queue::{JobPriority, JobQueue, QueueEntry, QueueError, QueueTicket};
+
+/// Parst eine Cron-Expression mit denselben Regeln wie tokio-cron-scheduler
+///
+/// # Errors
+/// Gibt einen Fehler zurück wenn die Expression ungültig ist
+pub fn parse_cron(cron_expr: &str) -> Result<Cron> {
+    CronParser::builder()
+        .seconds(Seconds::Required)
+        .dom_and_dow(true)
+        .build()
+        .parse(cron_expr)
+        .map_err(|e| anyhow::anyhow!("Ungültige Cron-Expression '{}': {}", cron_expr, e))
+}
+
+/// Berechnet den nächsten Ausführungszeitpunkt nach `after` (UTC, wie der Scheduler)
+///
+/// # Errors
+/// Gibt einen Fehler zurück wenn die Expression ungültig ist oder keinen Zeitpunkt liefert
+pub fn next_fire_time(cron_expr: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    Ok(parse_cron(cron_expr)?.find_next_occurrence(&after, false)?)
+}
+
+/// Berechnet den letzten Ausführungszeitpunkt vor `before` (UTC, wie der Scheduler)
+///
+/// # Errors
+/// Gibt einen Fehler zurück wenn die Expression ungültig ist oder keinen Zeitpunkt liefert
+pub fn previous_fire_time(cron_expr: &str, before: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    Ok(parse_cron(cron_expr)?.find_previous_occurrence(&before, true)?)
+}
+
 /// Backup-Scheduler für zeitgesteuerte Jobs
 ///
 /// Verwaltet geplante Backup-Jobs und deren Ausführung.
 pub struct BackupScheduler {
     /// Tokio-Cron-Scheduler-Instanz
     scheduler: JobScheduler,
-    /// Mapping von Job-IDs zu Scheduler-UUIDs
-    jobs: HashMap<String, Uuid>,
+    /// Mapping von Job-IDs zu Scheduler-UUIDs und Cron-Expressions
+    jobs: HashMap<String, ScheduledJob>,
+}
+
+/// Registrierung eines Jobs im Scheduler
+struct ScheduledJob {
+    uuid: Uuid,
+    cron_expr: String,
 }
 
 impl BackupScheduler {
@@ -69,7 +113,7 @@ impl BackupScheduler {
 
         // Füge Job zum Scheduler hinzu
         let uuid = self.scheduler.add(job).await?;
-        self.jobs.insert(job_id.clone(), uuid);
+        self.jobs.insert(job_id.clone(), ScheduledJob { uuid, cron_expr: cron_expr.to_string() });
 
         tracing::info!("Job '{}' geplant mit Cron-Expression: {}", job_id, cron_expr);
 
@@ -87,8 +131,8 @@ impl BackupScheduler {
     /// # Errors
     /// Gibt einen Fehler zurück wenn Job nicht gefunden oder Entfernung fehlschlägt
     pub async fn remove_job(&mut self, job_id: &str) -> Result<()> {
-        if let Some(uuid) = self.jobs.remove(job_id) {
-            self.scheduler.remove(&uuid).await?;
+        if let Some(job) = self.jobs.remove(job_id) {
+            self.scheduler.remove(&job.uuid).await?;
             tracing::info!("Job '{}' entfernt", job_id);
             Ok(())
         } else {
@@ -115,6 +159,32 @@ impl BackupScheduler {
         self.jobs.contains_key(job_id)
     }
 
+    /// Ermittelt den nächsten Ausführungszeitpunkt eines geplanten Jobs
+    ///
+    /// # Arguments
+    /// * `job_id` - ID des Jobs
+    ///
+    /// # Returns
+    /// Nächster Ausführungszeitpunkt oder None, wenn der Job nicht geplant ist
+    pub fn next_run(&self, job_id: &str) -> Option<DateTime<Utc>> {
+        let job = self.jobs.get(job_id)?;
+        match next_fire_time(&job.cron_expr, Utc::now()) {
+            Ok(next) => Some(next),
+            Err(e) => {
+                tracing::warn!("Nächster Lauf für Job '{}' nicht ermittelbar: {}", job_id, e);
+                None
+            }
+        }
+    }
+
+    /// Liefert die nächsten Ausführungszeitpunkte aller geplanten Jobs
+    ///
+    /// # Returns
+    /// Mapping von Job-ID zu nächstem Ausführungszeitpunkt
+    pub fn next_runs(&self) -> HashMap<String, DateTime<Utc>> {
+        self.jobs.keys().filter_map(|id| Some((id.clone(), self.next_run(id)?))).collect()
+    }
+
     /// Stoppt den Scheduler
     ///
     /// # Returns
@@ -133,6 +203,19 @@ mod tests {
     use std::sync::atomic::{AtomicUsize, Ordering};
     use tokio::time::{Duration, sleep};
 
+    #[test]
+    fn test_fire_times() {
+        let now = DateTime::parse_from_rfc3339("2025-03-10T12:34:56Z").unwrap().to_utc();
+
+        let next = next_fire_time("0 0 2 * * *", now).unwrap();
+        assert_eq!(next.to_rfc3339(), "2025-03-11T02:00:00+00:00");
+
+        let previous = previous_fire_time("0 0 2 * * *", now).unwrap();
+        assert_eq!(previous.to_rfc3339(), "2025-03-10T02:00:00+00:00");
+
+        assert!(next_fire_time("invalid", now).is_err());
+    }
+
     #[tokio::test]
     async fn test_scheduler_creation() {
         let scheduler = BackupScheduler::new().await;
@@ -236,4 +319,20 @@ mod tests {
 
         assert!(!scheduler.has_job("test"));
     }
+
+    #[tokio::test]
+    async fn test_next_run() {
+        let mut scheduler = BackupScheduler::new().await.unwrap();
+        assert!(scheduler.next_run("hourly").is_none());
+
+        scheduler
+            .schedule_job("hourly".to_string(), "0 0 * * * *", || Box::pin(async {}))
+            .await
+            .unwrap();
+
+        let next = scheduler.next_run("hourly").expect("nächster Lauf erwartet");
+        assert!(next > Utc::now());
+        assert!(next <= Utc::now() + chrono::Duration::hours(1));
+        assert_eq!(scheduler.next_runs().get("hourly"), Some(&next));
+    }
 }
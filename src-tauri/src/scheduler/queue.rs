@@ -0,0 +1,399 @@
+/// Warteschlange für Backup-Läufe
+///
+/// Begrenzt die Anzahl gleichzeitiger Backups global (`AppSettings::max_concurrent_backups`)
+/// und pro Repository. Läufe, die nicht sofort starten dürfen, warten nach Priorität und
+/// Eingangsreihenfolge. Derselbe Job steht höchstens einmal in der Warteschlange und läuft
+/// nie parallel zu sich selbst.
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// Priorität eines Laufs in der Warteschlange
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPriority {
+    /// Nachgeholte Läufe
+    Low,
+    /// Geplante Läufe
+    #[default]
+    Normal,
+    /// Manuell gestartete Läufe
+    High,
+}
+
+/// Zustand eines Eintrags in der Warteschlange
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueEntryState {
+    /// Wartet auf einen freien Platz
+    Queued,
+    /// Läuft aktuell
+    Running,
+}
+
+/// Eintrag in der Job-Warteschlange (an Frontend gesendet)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueEntry {
+    /// Job-ID
+    pub job_id: String,
+    /// Schlüssel des Repositories (für das Limit pro Repository)
+    pub repository: String,
+    /// Priorität
+    pub priority: JobPriority,
+    /// Aktueller Zustand
+    pub state: QueueEntryState,
+    /// Zeitpunkt der Einreihung (ISO 8601)
+    pub enqueued_at: String,
+    /// Startzeitpunkt (ISO 8601, None solange der Lauf wartet)
+    pub started_at: Option<String>,
+    /// Laufende Nummer für FIFO innerhalb einer Priorität
+    #[serde(skip)]
+    seq: u64,
+}
+
+/// Fehler beim Einreihen eines Laufs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueError {
+    /// Der Job wartet bereits in der Warteschlange
+    AlreadyQueued,
+    /// Der wartende Lauf wurde aus der Warteschlange entfernt
+    Removed,
+}
+
+impl std::fmt::Display for QueueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueueError::AlreadyQueued => write!(f, "Job wartet bereits in der Warteschlange"),
+            QueueError::Removed => write!(f, "Job wurde aus der Warteschlange entfernt"),
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
+type QueueListener = Arc<dyn Fn(&[QueueEntry]) + Send + Sync>;
+
+struct QueueInner {
+    entries: Vec<QueueEntry>,
+    max_concurrent: usize,
+    max_per_repository: usize,
+    next_seq: u64,
+}
+
+impl QueueInner {
+    /// Startet alle wartenden Einträge, für die Platz ist (nach Priorität, dann FIFO)
+    fn dispatch(&mut self) -> bool {
+        let mut waiting: Vec<usize> = (0..self.entries.len())
+            .filter(|&i| self.entries[i].state == QueueEntryState::Queued)
+            .collect();
+        waiting.sort_by_key(|&i| {
+            let entry = &self.entries[i];
+            (std::cmp::Reverse(entry.priority), entry.seq)
+        });
+
+        let mut changed = false;
+        for i in waiting {
+            let running: Vec<&QueueEntry> =
+                self.entries.iter().filter(|e| e.state == QueueEntryState::Running).collect();
+            let entry = &self.entries[i];
+            let repo_running = running.iter().filter(|e| e.repository == entry.repository).count();
+            let job_running = running.iter().any(|e| e.job_id == entry.job_id);
+
+            if running.len() < self.max_concurrent
+                && repo_running < self.max_per_repository
+                && !job_running
+            {
+                let entry = &mut self.entries[i];
+                entry.state = QueueEntryState::Running;
+                entry.started_at = Some(chrono::Utc::now().to_rfc3339());
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Einträge für das Frontend: laufende zuerst, dann wartende in Startreihenfolge
+    fn snapshot(&self) -> Vec<QueueEntry> {
+        let mut entries = self.entries.clone();
+        entries.sort_by_key(|e| {
+            (e.state == QueueEntryState::Queued, std::cmp::Reverse(e.priority), e.seq)
+        });
+        entries
+    }
+}
+
+/// Job-Warteschlange mit globalem Limit und Limit pro Repository
+pub struct JobQueue {
+    inner: Mutex<QueueInner>,
+    notify: Notify,
+    listener: Mutex<Option<QueueListener>>,
+}
+
+impl JobQueue {
+    /// Erstellt eine neue Warteschlange (Limits werden auf mindestens 1 angehoben)
+    pub fn new(max_concurrent: usize, max_per_repository: usize) -> Arc<Self> {
+        Arc::new(Self {
+            inner: Mutex::new(QueueInner {
+                entries: Vec::new(),
+                max_concurrent: max_concurrent.max(1),
+                max_per_repository: max_per_repository.max(1),
+                next_seq: 0,
+            }),
+            notify: Notify::new(),
+            listener: Mutex::new(None),
+        })
+    }
+
+    /// Setzt den Callback, der bei jeder Änderung der Warteschlange aufgerufen wird
+    pub fn set_listener<F>(&self, listener: F)
+    where
+        F: Fn(&[QueueEntry]) + Send + Sync + 'static,
+    {
+        *self.listener.lock() = Some(Arc::new(listener));
+    }
+
+    /// Aktualisiert die Limits und startet ggf. wartende Läufe
+    pub fn set_limits(&self, max_concurrent: usize, max_per_repository: usize) {
+        let changed = {
+            let mut inner = self.inner.lock();
+            inner.max_concurrent = max_concurrent.max(1);
+            inner.max_per_repository = max_per_repository.max(1);
+            inner.dispatch()
+        };
+        if changed {
+            self.changed();
+        }
+    }
+
+    /// Liefert alle laufenden und wartenden Einträge
+    pub fn entries(&self) -> Vec<QueueEntry> {
+        self.inner.lock().snapshot()
+    }
+
+    /// Prüft, ob der Job bereits wartend in der Warteschlange steht
+    pub fn is_queued(&self, job_id: &str) -> bool {
+        self.inner
+            .lock()
+            .entries
+            .iter()
+            .any(|e| e.job_id == job_id && e.state == QueueEntryState::Queued)
+    }
+
+    /// Reiht einen Lauf ein und wartet, bis er starten darf.
+    ///
+    /// Steht der Job bereits wartend in der Warteschlange, wird seine Priorität ggf.
+    /// angehoben und `QueueError::AlreadyQueued` zurückgegeben.
+    ///
+    /// # Arguments
+    /// * `job_id` - ID des Jobs
+    /// * `repository` - Schlüssel des Repositories
+    /// * `priority` - Priorität des Laufs
+    ///
+    /// # Returns
+    /// Ticket, das den Platz bis zum Drop belegt. Wird das Future vorher verworfen, wird
+    /// der wartende Eintrag wieder entfernt.
+    ///
+    /// # Errors
+    /// `AlreadyQueued` bei doppelter Einreihung, `Removed` wenn der wartende Lauf über
+    /// `remove_queued` entfernt wurde
+    pub async fn acquire(
+        self: &Arc<Self>,
+        job_id: &str,
+        repository: &str,
+        priority: JobPriority,
+    ) -> Result<QueueTicket, QueueError> {
+        let seq = {
+            let mut inner = self.inner.lock();
+            if let Some(existing) = inner
+                .entries
+                .iter_mut()
+                .find(|e| e.job_id == job_id && e.state == QueueEntryState::Queued)
+            {
+                existing.priority = existing.priority.max(priority);
+                drop(inner);
+                tracing::info!(job_id = %job_id, "Job wartet bereits, Einreihung übersprungen");
+                self.changed();
+                return Err(QueueError::AlreadyQueued);
+            }
+
+            let seq = inner.next_seq;
+            inner.next_seq += 1;
+            inner.entries.push(QueueEntry {
+                job_id: job_id.to_string(),
+                repository: repository.to_string(),
+                priority,
+                state: QueueEntryState::Queued,
+                enqueued_at: chrono::Utc::now().to_rfc3339(),
+                started_at: None,
+                seq,
+            });
+            inner.dispatch();
+            seq
+        };
+        // Das Ticket entfernt den Eintrag auch dann, wenn das Future vor dem Start verworfen wird
+        let ticket = QueueTicket { queue: self.clone(), seq };
+        self.changed();
+
+        loop {
+            let notified = self.notify.notified();
+            {
+                let inner = self.inner.lock();
+                match inner.entries.iter().find(|e| e.seq == seq) {
+                    Some(entry) if entry.state == QueueEntryState::Running => {
+                        tracing::debug!(job_id = %job_id, "Job aus Warteschlange gestartet");
+                        return Ok(ticket);
+                    }
+                    Some(_) => {}
+                    None => return Err(QueueError::Removed),
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// Entfernt einen wartenden (noch nicht gestarteten) Lauf
+    ///
+    /// # Returns
+    /// true wenn ein wartender Eintrag entfernt wurde
+    pub fn remove_queued(&self, job_id: &str) -> bool {
+        let removed = {
+            let mut inner = self.inner.lock();
+            let before = inner.entries.len();
+            inner.entries.retain(|e| !(e.job_id == job_id && e.state == QueueEntryState::Queued));
+            before != inner.entries.len()
+        };
+        if removed {
+            self.changed();
+        }
+        removed
+    }
+
+    fn release(&self, seq: u64) {
+        {
+            let mut inner = self.inner.lock();
+            inner.entries.retain(|e| e.seq != seq);
+            inner.dispatch();
+        }
+        self.changed();
+    }
+
+    /// Weckt wartende Läufe und benachrichtigt den Listener
+    fn changed(&self) {
+        self.notify.notify_waiters();
+        let listener = self.listener.lock().clone();
+        if let Some(listener) = listener {
+            listener(&self.entries());
+        }
+    }
+}
+
+/// Belegter Platz in der Warteschlange; wird beim Drop freigegeben
+pub struct QueueTicket {
+    queue: Arc<JobQueue>,
+    seq: u64,
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        self.queue.release(self.seq);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    #[tokio::test]
+    async fn test_global_limit_serializes_runs() {
+        let queue = JobQueue::new(1, 1);
+        let first = queue.acquire("job-a", "repo-1", JobPriority::Normal).await.unwrap();
+
+        let queue_clone = queue.clone();
+        let waiting = tokio::spawn(async move {
+            queue_clone.acquire("job-b", "repo-2", JobPriority::Normal).await.map(|_| ())
+        });
+        settle().await;
+
+        let entries = queue.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].job_id, "job-b");
+        assert_eq!(entries[1].state, QueueEntryState::Queued);
+
+        drop(first);
+        assert!(waiting.await.unwrap().is_ok());
+        assert!(queue.entries().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_repository_limit_and_priority() {
+        let queue = JobQueue::new(3, 1);
+        let running = queue.acquire("job-a", "repo-1", JobPriority::Normal).await.unwrap();
+
+        // Anderes Repository startet sofort
+        let other = queue.acquire("job-x", "repo-2", JobPriority::Normal).await.unwrap();
+
+        let q1 = queue.clone();
+        let low =
+            tokio::spawn(async move { q1.acquire("job-b", "repo-1", JobPriority::Low).await });
+        settle().await;
+        let q2 = queue.clone();
+        let high =
+            tokio::spawn(async move { q2.acquire("job-c", "repo-1", JobPriority::High).await });
+        settle().await;
+
+        drop(running);
+        let high_ticket = high.await.unwrap().unwrap();
+        settle().await;
+        assert!(!low.is_finished(), "Niedrige Priorität muss weiter warten");
+
+        drop(high_ticket);
+        assert!(low.await.unwrap().is_ok());
+        drop(other);
+    }
+
+    #[tokio::test]
+    async fn test_deduplication_and_removal() {
+        let queue = JobQueue::new(1, 1);
+        let _running = queue.acquire("job-a", "repo-1", JobPriority::Normal).await.unwrap();
+
+        let q = queue.clone();
+        let waiting =
+            tokio::spawn(async move { q.acquire("job-b", "repo-1", JobPriority::Low).await });
+        settle().await;
+
+        let duplicate = queue.acquire("job-b", "repo-1", JobPriority::High).await;
+        assert!(matches!(duplicate, Err(QueueError::AlreadyQueued)));
+        assert_eq!(queue.entries()[1].priority, JobPriority::High);
+
+        assert!(queue.is_queued("job-b"));
+        assert!(queue.remove_queued("job-b"));
+        assert!(matches!(waiting.await.unwrap(), Err(QueueError::Removed)));
+        assert!(!queue.remove_queued("job-b"));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_acquire_leaves_no_entry() {
+        let queue = JobQueue::new(1, 1);
+        let running = queue.acquire("job-a", "repo-1", JobPriority::Normal).await.unwrap();
+
+        let q = queue.clone();
+        let waiting =
+            tokio::spawn(async move { q.acquire("job-b", "repo-1", JobPriority::Normal).await });
+        settle().await;
+        assert!(queue.is_queued("job-b"));
+
+        waiting.abort();
+        let _ = waiting.await;
+        assert!(!queue.is_queued("job-b"));
+        assert_eq!(queue.entries().len(), 1);
+
+        drop(running);
+        assert!(queue.entries().is_empty());
+    }
+}
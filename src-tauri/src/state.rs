@@ -1,5 +1,5 @@
 use crate::config::AppConfig;
-use crate::scheduler::BackupScheduler;
+use crate::scheduler::{BackupScheduler, JobQueue};
 use crate::storage::{PortableStore, PortableStoreStatus};
 use parking_lot::Mutex;
 use rustic_backend::BackendOptions;
@@ -20,6 +20,14 @@ struct CachedRepository {
 /// Cache-Timeout: 5 Minuten
 const CACHE_TIMEOUT: Duration = Duration::from_secs(300);
 
+/// Zwischengespeicherte Repository-Statistiken
+///
+/// Gültig, solange sich der Fingerprint (Index- und Snapshot-Dateien) nicht ändert.
+struct CachedStats {
+    fingerprint: u64,
+    stats: crate::types::RepositoryStatsDto,
+}
+
 /// Globaler Application-State.
 ///
 /// Wird über Tauri's `.manage()` geteilt und ist in allen Commands verfügbar.
@@ -33,6 +41,9 @@ pub struct AppState {
     /// Repositories werden für 5 Minuten gecacht um wiederholte Opens zu vermeiden
     repository_cache: Arc<Mutex<HashMap<String, CachedRepository>>>,
 
+    /// Cache für Repository-Statistiken (ID -> Statistiken mit Fingerprint)
+    stats_cache: Arc<Mutex<HashMap<String, CachedStats>>>,
+
     /// Cancellation-Tokens für laufende Backups
     /// Key: Job-ID, Value: CancellationToken
     pub cancellation_tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
@@ -40,6 +51,9 @@ pub struct AppState {
     /// Job-Scheduler für zeitgesteuerte Backups (M3)
     pub scheduler: Arc<AsyncMutex<Option<BackupScheduler>>>,
 
+    /// Warteschlange, die gleichzeitige Backups global und pro Repository begrenzt
+    pub job_queue: Arc<JobQueue>,
+
     /// App-Konfiguration (TOML)
     pub config: Arc<Mutex<AppConfig>>,
 
@@ -64,12 +78,18 @@ impl AppState {
         });
 
         let portable_store = Arc::new(Mutex::new(portable_store));
+        let job_queue = JobQueue::new(
+            config.settings.max_concurrent_backups,
+            config.settings.max_concurrent_backups_per_repository,
+        );
 
         Ok(Self {
             current_repository_id: Arc::new(Mutex::new(None)),
             repository_cache: Arc::new(Mutex::new(HashMap::new())),
+            stats_cache: Arc::new(Mutex::new(HashMap::new())),
             cancellation_tokens: Arc::new(Mutex::new(HashMap::new())),
             scheduler: Arc::new(AsyncMutex::new(None)), // Wird async initialisiert
+            job_queue,
             config: Arc::new(Mutex::new(config)),
             portable_store,
         })
@@ -148,50 +168,63 @@ impl AppState {
         }
 
         // 2. Nicht im Cache - Repository öffnen
-        let (repo_config, password) = {
-            let config = self.config.lock();
-            let repo_config = config.get_repository(repository_id).cloned().ok_or_else(|| {
-                crate::error::RusticGuiError::Internal(format!(
-                    "Repository {} nicht in Config gefunden",
-                    repository_id
-                ))
-            })?;
+        let (repo_config, password) = self.repository_credentials(repository_id)?;
+
+        self.open_repository_internal(repository_id, repo_config, password)
+    }
+
+    /// Liefert Konfiguration und Passwort (aus dem Keychain) eines Repositories
+    ///
+    /// Für Operationen, die das Repository selbst öffnen müssen (z.B. mit eigenen
+    /// `RepositoryOptions`), statt die gecachte Instanz zu verwenden.
+    ///
+    /// # Errors
+    /// - Internal: Repository-ID existiert nicht in Config
+    /// - PasswordMissing: Kein Passwort im Keychain gespeichert
+    pub fn repository_credentials(
+        &self,
+        repository_id: &str,
+    ) -> crate::error::Result<(crate::config::RepositoryConfig, String)> {
+        let config = self.config.lock();
+        let repo_config = config.get_repository(repository_id).cloned().ok_or_else(|| {
+            crate::error::RusticGuiError::Internal(format!(
+                "Repository {} nicht in Config gefunden",
+                repository_id
+            ))
+        })?;
 
-            // 3. Passwort aus Keychain holen (falls gespeichert)
-            let password = if repo_config.password_stored {
-                match crate::keychain::load_password(repository_id) {
-                    Ok(value) => value,
-                    Err(err @ crate::error::RusticGuiError::PasswordMissing { .. }) => {
-                        drop(config);
-                        {
-                            let mut cfg = self.config.lock();
-                            if cfg.set_repository_password_stored(repository_id, false) {
-                                drop(cfg);
-                                if let Err(save_err) = self.save_config() {
-                                    tracing::warn!(
-                                        "Konfiguration konnte nach Passwort-Update nicht gespeichert werden: {}",
-                                        save_err
-                                    );
-                                }
+        // Passwort aus Keychain holen (falls gespeichert)
+        let password = if repo_config.password_stored {
+            match crate::keychain::load_password(repository_id) {
+                Ok(value) => value,
+                Err(err @ crate::error::RusticGuiError::PasswordMissing { .. }) => {
+                    drop(config);
+                    {
+                        let mut cfg = self.config.lock();
+                        if cfg.set_repository_password_stored(repository_id, false) {
+                            drop(cfg);
+                            if let Err(save_err) = self.save_config() {
+                                tracing::warn!(
+                                    "Konfiguration konnte nach Passwort-Update nicht gespeichert werden: {}",
+                                    save_err
+                                );
                             }
                         }
-                        return Err(err);
-                    }
-                    Err(err) => {
-                        return Err(err);
                     }
+                    return Err(err);
                 }
-            } else {
-                drop(config);
-                return Err(crate::error::RusticGuiError::PasswordMissing {
-                    repo_id: repository_id.to_string(),
-                });
-            };
-
-            (repo_config, password)
+                Err(err) => {
+                    return Err(err);
+                }
+            }
+        } else {
+            drop(config);
+            return Err(crate::error::RusticGuiError::PasswordMissing {
+                repo_id: repository_id.to_string(),
+            });
         };
 
-        self.open_repository_internal(repository_id, repo_config, password)
+        Ok((repo_config, password))
     }
 
     /// Öffnet ein Repository mit übergebenem Passwort und legt es im Cache ab.
@@ -277,6 +310,36 @@ impl AppState {
         f(&*repository)
     }
 
+    /// Registriert ein Cancellation-Token für ein startendes Backup.
+    ///
+    /// # Arguments
+    /// * `job_id` - ID des Backup-Jobs
+    ///
+    /// # Returns
+    /// Das neue Token, über das `cancel_backup` das Backup abbrechen kann
+    ///
+    /// # Errors
+    /// `BackupFailed`, wenn für den Job bereits ein Backup läuft
+    pub fn register_cancellation_token(
+        &self,
+        job_id: &str,
+    ) -> crate::error::Result<CancellationToken> {
+        let mut tokens = self.cancellation_tokens.lock();
+        if tokens.contains_key(job_id) {
+            return Err(crate::error::RusticGuiError::BackupFailed {
+                reason: format!("Für Job '{}' läuft bereits ein Backup", job_id),
+            });
+        }
+        let token = CancellationToken::new();
+        tokens.insert(job_id.to_string(), token.clone());
+        Ok(token)
+    }
+
+    /// Entfernt das Cancellation-Token eines beendeten Backups.
+    pub fn release_cancellation_token(&self, job_id: &str) {
+        self.cancellation_tokens.lock().remove(job_id);
+    }
+
     /// Helper: Speichert Config auf Disk.
     pub fn save_config(&self) -> crate::error::Result<()> {
         let config = self.config.lock().clone();
@@ -296,6 +359,40 @@ impl AppState {
         tracing::debug!("Repository-Cache für {} invalidiert", repository_id);
     }
 
+    /// Liefert gecachte Statistiken, falls sie zum aktuellen Fingerprint passen
+    ///
+    /// # Arguments
+    /// * `repository_id` - ID des Repositories
+    /// * `fingerprint` - Aktueller Fingerprint (siehe `repository_stats_fingerprint`)
+    pub fn cached_repository_stats(
+        &self,
+        repository_id: &str,
+        fingerprint: u64,
+    ) -> Option<crate::types::RepositoryStatsDto> {
+        self.stats_cache
+            .lock()
+            .get(repository_id)
+            .filter(|cached| cached.fingerprint == fingerprint)
+            .map(|cached| cached.stats.clone())
+    }
+
+    /// Legt berechnete Statistiken im Cache ab
+    ///
+    /// # Arguments
+    /// * `repository_id` - ID des Repositories
+    /// * `fingerprint` - Fingerprint, zu dem die Statistiken berechnet wurden
+    /// * `stats` - Berechnete Statistiken
+    pub fn cache_repository_stats(
+        &self,
+        repository_id: &str,
+        fingerprint: u64,
+        stats: crate::types::RepositoryStatsDto,
+    ) {
+        self.stats_cache
+            .lock()
+            .insert(repository_id.to_string(), CachedStats { fingerprint, stats });
+    }
+
     /// Liefert Statusinformationen zum portablen Speicher.
     pub fn portable_status(&self) -> PortableStoreStatus {
         self.portable_store.lock().status()
@@ -379,6 +476,49 @@ mod tests {
         assert!(matches!(result, Err(crate::error::RusticGuiError::Internal(_))));
     }
 
+    #[test]
+    fn test_cancellation_token_registration() {
+        let _guard = PortableEnvGuard::new();
+        let state = AppState::new().unwrap();
+
+        let token = state.register_cancellation_token("job-1").unwrap();
+        assert!(state.register_cancellation_token("job-1").is_err());
+
+        state.cancellation_tokens.lock().get("job-1").unwrap().cancel();
+        assert!(token.is_cancelled());
+
+        state.release_cancellation_token("job-1");
+        assert!(state.cancellation_tokens.lock().is_empty());
+        assert!(state.register_cancellation_token("job-1").is_ok());
+    }
+
+    #[test]
+    fn test_repository_stats_cache() {
+        let _guard = PortableEnvGuard::new();
+        let state = AppState::new().unwrap();
+        let stats = crate::types::RepositoryStatsDto {
+            snapshot_count: 3,
+            index_count: 1,
+            pack_count: 2,
+            total_size: 4096,
+            data_size: 2048,
+            compression_ratio: 0.5,
+            deduplication_ratio: 0.25,
+            unique_blobs: 10,
+            snapshot_data_size: 0,
+            tree_blobs: Default::default(),
+            data_blobs: Default::default(),
+            computed_at: String::new(),
+        };
+
+        assert!(state.cached_repository_stats("repo-1", 7).is_none());
+        state.cache_repository_stats("repo-1", 7, stats);
+        assert_eq!(state.cached_repository_stats("repo-1", 7).unwrap().snapshot_count, 3);
+        // Geänderter Inhalt (neuer Snapshot/Index) -> Cache ungültig
+        assert!(state.cached_repository_stats("repo-1", 8).is_none());
+        assert!(state.cached_repository_stats("repo-2", 7).is_none());
+    }
+
     #[test]
     fn test_save_config_placeholder() {
         let _guard = PortableEnvGuard::new();
@@ -57,6 +57,81 @@ pub enum RepositoryStatus {
     Locked,
 }
 
+/// Lock-Datei eines Repositories (von restic angelegt)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockInfoDto {
+    /// ID der Lock-Datei
+    pub id: String,
+    /// Host, der das Lock hält
+    pub hostname: Option<String>,
+    /// Benutzer, der das Lock hält
+    pub username: Option<String>,
+    /// Prozess-ID auf dem Host
+    pub pid: Option<i64>,
+    /// Exklusives Lock (z.B. prune)
+    pub exclusive: bool,
+    /// Zeitpunkt des Locks (RFC3339; bei unlesbaren Locks die Änderungszeit der Datei)
+    pub time: String,
+    /// Alter in Sekunden
+    pub age_seconds: u64,
+    /// Lock stammt von diesem Rechner
+    pub local_host: bool,
+    /// Ob der Prozess noch läuft (nur für Locks dieses Rechners bekannt)
+    pub process_alive: Option<bool>,
+    /// Lock gilt als veraltet und kann entfernt werden
+    pub stale: bool,
+    /// Fehler beim Lesen der Lock-Datei
+    pub error: Option<String>,
+}
+
+/// Lock-Dateien eines Repositories
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepositoryLocksDto {
+    /// Ob Locks für das Backend gelesen werden können (nur lokale Repositories)
+    pub supported: bool,
+    pub locks: Vec<LockInfoDto>,
+}
+
+/// Grund, warum ein Lock nicht entfernt wurde
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LockSkipReason {
+    /// Prozess läuft noch auf diesem Rechner (oder Status unbekannt)
+    ProcessAlive,
+    /// Lock ist jünger als die Altersgrenze
+    TooRecent,
+    /// Lock-Datei existiert nicht
+    NotFound,
+    /// Lock-Datei nicht lesbar und nicht ausdrücklich ausgewählt
+    Unreadable,
+}
+
+/// Nicht entferntes Lock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedLockDto {
+    pub id: String,
+    pub reason: LockSkipReason,
+}
+
+/// Ergebnis von `force_unlock_repository`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnlockResultDto {
+    /// Entfernte Lock-Dateien
+    pub removed: Vec<String>,
+    /// Nicht entfernte Lock-Dateien mit Grund
+    pub skipped: Vec<SkippedLockDto>,
+}
+
+/// Ergebnis des Health-Checks eines Repositories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryHealthDto {
+    pub status: RepositoryStatus,
+    /// Lock-Dateien (bei `Locked`)
+    pub locks: RepositoryLocksDto,
+    /// Fehlermeldung (bei `Unavailable`) bzw. Hinweis (bei `Warning`)
+    pub message: Option<String>,
+}
+
 /// DTO für Snapshot-Informationen
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SnapshotDto {
@@ -115,15 +190,87 @@ pub struct BackupJobDto {
     pub next_run: Option<String>, // ISO 8601
     /// Retention-Policy
     pub retention: Option<RetentionPolicy>,
+    /// Verhalten bei verpassten geplanten Läufen
+    #[serde(default)]
+    pub catch_up: Option<CatchUpPolicy>,
+    /// Wartungsschritte nach erfolgreichem Backup (Retention, Prune)
+    #[serde(default)]
+    pub post_backup: Option<PostBackupOptions>,
     /// Gespeichertes Passwort für geplante Jobs (wird mit Config verschlüsselt)
     pub password: Option<String>,
 }
 
+/// Änderungen an einem Backup-Job (`update_backup_job`); nicht gesetzte Felder bleiben
+/// unverändert
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupJobUpdate {
+    /// Neuer Anzeigename
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Neue Quellpfade
+    #[serde(default)]
+    pub source_paths: Option<Vec<String>>,
+    /// Neue Ausschlussmuster
+    #[serde(default)]
+    pub exclude_patterns: Option<Vec<String>>,
+    /// Neue Tags
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Neue Cron-Expression (leerer String entfernt den Zeitplan)
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// Neue Retention-Policy
+    #[serde(default)]
+    pub retention: Option<RetentionPolicy>,
+    /// Ob der Job aktiviert ist
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Verhalten bei verpassten geplanten Läufen
+    #[serde(default)]
+    pub catch_up: Option<CatchUpPolicy>,
+    /// Wartungsschritte nach erfolgreichem Backup
+    #[serde(default)]
+    pub post_backup: Option<PostBackupOptions>,
+}
+
+/// Verhalten bei verpassten geplanten Läufen (z.B. Rechner im Ruhezustand)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CatchUpPolicy {
+    /// Verpasste Läufe werden übersprungen
+    Skip,
+    /// Verpasste Läufe werden einmalig nachgeholt
+    #[default]
+    RunOnce,
+    /// Nur nachholen, wenn der letzte Lauf älter als `max_age_hours` Stunden ist
+    RunIfOlderThan {
+        /// Maximales Alter des letzten Laufs in Stunden
+        max_age_hours: u64,
+    },
+}
+
+/// Wartungsschritte, die nach einem erfolgreichen Backup eines Jobs ausgeführt werden
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct PostBackupOptions {
+    /// Retention-Policy des Jobs auf dessen Snapshots anwenden
+    #[serde(default)]
+    pub apply_retention: bool,
+    /// Prune höchstens alle N Tage ausführen (None = nie)
+    #[serde(default)]
+    pub prune_interval_days: Option<u32>,
+}
+
 /// Retention-Policy für Snapshots
+///
+/// Die Policy wird pro Snapshot-Gruppe (siehe `group_by`) angewendet, nicht über das
+/// gesamte Repository.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetentionPolicy {
     /// Behalte die letzten N Snapshots
     pub keep_last: Option<u32>,
+    /// Behalte stündliche Snapshots für N Stunden
+    #[serde(default)]
+    pub keep_hourly: Option<u32>,
     /// Behalte tägliche Snapshots für N Tage
     pub keep_daily: Option<u32>,
     /// Behalte wöchentliche Snapshots für N Wochen
@@ -132,20 +279,58 @@ pub struct RetentionPolicy {
     pub keep_monthly: Option<u32>,
     /// Behalte jährliche Snapshots für N Jahre
     pub keep_yearly: Option<u32>,
+    /// Behalte alle Snapshots innerhalb dieser Dauer vor dem neuesten (z.B. "7d", "2w 3d")
+    #[serde(default)]
+    pub keep_within: Option<String>,
+    /// Behalte Snapshots mit diesen Tags (jeder Eintrag ist eine kommagetrennte Tag-Liste,
+    /// die vollständig vorhanden sein muss)
+    #[serde(default)]
+    pub keep_tags: Vec<String>,
+    /// Behalte Snapshots mit diesen IDs (oder ID-Präfixen)
+    #[serde(default)]
+    pub keep_ids: Vec<String>,
+    /// Gruppierung der Snapshots, innerhalb der die Policy angewendet wird
+    #[serde(default)]
+    pub group_by: RetentionGroupBy,
 }
 
 impl Default for RetentionPolicy {
     fn default() -> Self {
         Self {
             keep_last: Some(10),
+            keep_hourly: None,
             keep_daily: Some(7),
             keep_weekly: Some(4),
             keep_monthly: Some(12),
             keep_yearly: Some(2),
+            keep_within: None,
+            keep_tags: Vec::new(),
+            keep_ids: Vec::new(),
+            group_by: RetentionGroupBy::default(),
         }
     }
 }
 
+/// Gruppierungskriterien für die Retention-Policy
+///
+/// Standard ist Host + Pfade, damit die Snapshots eines Rechners nie wegen neuerer
+/// Snapshots eines anderen Rechners gelöscht werden.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetentionGroupBy {
+    /// Nach Hostname gruppieren
+    pub hostname: bool,
+    /// Nach gesicherten Pfaden gruppieren
+    pub paths: bool,
+    /// Nach Tags gruppieren
+    pub tags: bool,
+}
+
+impl Default for RetentionGroupBy {
+    fn default() -> Self {
+        Self { hostname: true, paths: true, tags: false }
+    }
+}
+
 /// DTO für Restore-Optionen
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RestoreOptionsDto {
@@ -155,14 +340,136 @@ pub struct RestoreOptionsDto {
     pub target_path: String,
     /// Spezifische Dateien/Pfade zum Wiederherstellen (leer = alles)
     pub paths: Vec<String>,
-    /// Ob vorhandene Dateien überschrieben werden sollen
-    pub overwrite: bool,
+    /// Umgang mit bereits vorhandenen Dateien im Ziel
+    #[serde(default)]
+    pub overwrite: OverwritePolicy,
+    /// Ob zusätzliche Dateien im Ziel gelöscht werden sollen (nur kompletter Snapshot)
+    #[serde(default)]
+    pub delete: bool,
     /// Ob Berechtigungen wiederhergestellt werden sollen
     pub restore_permissions: bool,
     /// Ob Zeitstempel wiederhergestellt werden sollen
     pub restore_timestamps: bool,
     /// Ob der Restore als Dry-Run ausgeführt werden soll
     pub dry_run: bool,
+    /// Anordnung der ausgewählten Pfade im Zielverzeichnis
+    #[serde(default)]
+    pub layout: RestoreLayout,
+}
+
+/// Umgang mit bereits vorhandenen Dateien beim Restore
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OverwritePolicy {
+    /// Immer überschreiben (Inhalt wird vollständig geprüft und angeglichen)
+    Always,
+    /// Nur überschreiben, wenn Größe oder Änderungszeit abweichen
+    #[default]
+    IfChanged,
+    /// Nur überschreiben, wenn die Datei im Snapshot neuer ist
+    IfNewer,
+    /// Vorhandene Dateien nie überschreiben
+    Never,
+}
+
+/// Restore-Plan: was ein Restore im Ziel anlegt, ändert und löscht
+///
+/// Bei einem Dry-Run wird nichts geschrieben; sonst beschreibt der Plan den ausgeführten Restore.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestorePlanDto {
+    /// Ob der Plan aus einem Dry-Run stammt
+    pub dry_run: bool,
+    /// Neu anzulegende Dateien (Pfade relativ zum Ziel)
+    pub files_to_create: Vec<String>,
+    /// Vorhandene Dateien, die überschrieben werden
+    pub files_to_update: Vec<String>,
+    /// Vorhandene Dateien, die wegen der Overwrite-Policy übersprungen werden
+    pub files_skipped: Vec<String>,
+    /// Anzahl unveränderter Dateien (Größe und Änderungszeit stimmen überein)
+    pub files_unchanged: u64,
+    /// Anzahl neu anzulegender Verzeichnisse
+    pub dirs_to_create: u64,
+    /// Zusätzliche Einträge im Ziel, die gelöscht werden (nur im Dry-Run ermittelt)
+    pub paths_to_delete: Vec<String>,
+    /// Zu schreibende Bytes
+    pub bytes_to_write: u64,
+    /// Bytes, die bereits passend im Ziel vorliegen
+    pub bytes_unchanged: u64,
+}
+
+/// Archivformat für den Restore in eine Datei
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    /// Unkomprimiertes tar
+    #[default]
+    Tar,
+    /// tar mit gzip-Kompression
+    TarGz,
+    /// tar mit zstd-Kompression
+    TarZstd,
+    /// zip (deflate)
+    Zip,
+}
+
+/// Ergebnis eines Restores in eine Archivdatei
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveResultDto {
+    /// Pfad der erstellten Archivdatei
+    pub output_path: String,
+    /// Verwendetes Archivformat
+    pub format: ArchiveFormat,
+    /// Anzahl archivierter Dateien
+    pub files: u64,
+    /// Anzahl archivierter Verzeichnisse
+    pub dirs: u64,
+    /// Nicht unterstützte Einträge (Geräte, FIFOs, Sockets), die ausgelassen wurden
+    pub skipped: Vec<String>,
+    /// Summe der archivierten Dateiinhalte in Bytes
+    pub bytes: u64,
+    /// Größe der Archivdatei in Bytes
+    pub archive_size: u64,
+}
+
+/// Vorschau auf den Anfang einer Datei aus einem Snapshot
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FilePreviewDto {
+    /// Pfad der Datei im Snapshot
+    pub path: String,
+    /// Gesamtgröße der Datei in Bytes
+    pub size: u64,
+    /// Änderungszeit (ISO 8601)
+    pub modified: Option<String>,
+    /// Aus der Dateiendung abgeleiteter MIME-Typ (falls bekannt)
+    pub mime_type: Option<String>,
+    /// Inhalt ist (soweit gelesen) gültiger UTF-8-Text ohne NUL-Bytes
+    pub is_text: bool,
+    /// Gelesene Bytes (Base64-kodiert)
+    pub content_base64: String,
+    /// Anzahl gelesener Bytes
+    pub bytes_read: u64,
+    /// Datei ist größer als der gelesene Ausschnitt
+    pub truncated: bool,
+}
+
+/// Ergebnis des Speicherns einer einzelnen Datei aus einem Snapshot
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractedFileDto {
+    /// Pfad der geschriebenen Datei
+    pub output_path: String,
+    /// Geschriebene Bytes
+    pub bytes: u64,
+}
+
+/// Anordnung ausgewählter Pfade im Zielverzeichnis eines Restores
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreLayout {
+    /// Ursprüngliche Pfade unterhalb des Ziels beibehalten (`/home/a.txt` → `<ziel>/home/a.txt`)
+    #[default]
+    Absolute,
+    /// Auswahl direkt im Ziel ablegen (`/home/a.txt` → `<ziel>/a.txt`)
+    Relative,
 }
 
 /// Knoten im Dateibaum für File-Browser
@@ -178,8 +485,48 @@ pub struct FileTreeNode {
     pub size: Option<u64>,
     /// Änderungszeit (ISO 8601)
     pub modified: Option<String>,
-    /// Kinder (None für Dateien, Some(vec![]) für leere Verzeichnisse)
+    /// Kinder (None für Dateien und noch nicht geladene Verzeichnisse,
+    /// Some(vec![]) für leere Verzeichnisse)
     pub children: Option<Vec<FileTreeNode>>,
+    /// Art des Knotens
+    #[serde(default)]
+    pub node_type: FileNodeType,
+    /// Unix-Berechtigungen (inkl. Dateityp-Bits)
+    #[serde(default)]
+    pub mode: Option<u32>,
+    /// Besitzer (Name, sonst UID)
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Gruppe (Name, sonst GID)
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Ziel eines symbolischen Links
+    #[serde(default)]
+    pub link_target: Option<String>,
+    /// Gerätenummer bei Geräte-Dateien
+    #[serde(default)]
+    pub device: Option<u64>,
+}
+
+/// Art eines Knotens im Snapshot
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FileNodeType {
+    /// Reguläre Datei
+    #[default]
+    File,
+    /// Verzeichnis
+    Dir,
+    /// Symbolischer Link
+    Symlink,
+    /// Block-Gerät
+    Dev,
+    /// Zeichen-Gerät
+    Chardev,
+    /// Named Pipe
+    Fifo,
+    /// Unix-Socket
+    Socket,
 }
 
 /// Basis-Progress-Info für langlaufende Operationen
@@ -282,6 +629,285 @@ pub struct DiffStats {
     pub total_size_change: i64,
 }
 
+/// Art eines Eintrags im Snapshot-Diff
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffEntryKind {
+    /// Nur im neueren Snapshot vorhanden
+    Added,
+    /// Nur im älteren Snapshot vorhanden
+    Removed,
+    /// In beiden Snapshots vorhanden, aber verändert
+    Changed,
+}
+
+/// Klasse einer Änderung an einem Eintrag, der in beiden Snapshots existiert
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffChangeClass {
+    /// Art des Eintrags geändert (z.B. Datei → Verzeichnis)
+    Type,
+    /// Inhalt geändert (Datei-Blobs, Symlink-Ziel, Gerätenummer)
+    Content,
+    /// Größe geändert
+    Size,
+    /// Änderungszeit geändert
+    Mtime,
+    /// Berechtigungen geändert
+    Permissions,
+    /// Besitzer oder Gruppe geändert
+    Owner,
+}
+
+/// Ein Eintrag im Snapshot-Diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffEntryDto {
+    /// Pfad im Snapshot
+    pub path: String,
+    /// Art der Änderung
+    pub kind: DiffEntryKind,
+    /// Geänderte Eigenschaften (nur bei `Changed`)
+    pub changes: Vec<DiffChangeClass>,
+    /// Eintrag ist (im neueren Snapshot, sonst im älteren) ein Verzeichnis
+    pub is_directory: bool,
+    /// Größe im älteren Snapshot (nicht bei Verzeichnissen)
+    pub size_before: Option<u64>,
+    /// Größe im neueren Snapshot (nicht bei Verzeichnissen)
+    pub size_after: Option<u64>,
+    /// Größenänderung in Bytes
+    pub size_delta: i64,
+}
+
+/// Aufsummierte Änderungen unterhalb eines Verzeichnisses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DirectoryDiffDto {
+    /// Pfad des Verzeichnisses
+    pub path: String,
+    /// Hinzugefügte Einträge darunter
+    pub added: u64,
+    /// Entfernte Einträge darunter
+    pub removed: u64,
+    /// Geänderte Einträge darunter
+    pub changed: u64,
+    /// Größenänderung darunter in Bytes
+    pub size_delta: i64,
+}
+
+/// Gesamtstatistik eines Snapshot-Diffs (über alle Seiten)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotDiffStats {
+    /// Hinzugefügte Einträge
+    pub added: u64,
+    /// Entfernte Einträge
+    pub removed: u64,
+    /// Einträge mit geändertem Inhalt, Größe oder Typ
+    pub changed: u64,
+    /// Einträge, bei denen sich nur Metadaten geändert haben
+    pub metadata_only: u64,
+    /// Summe der Größen hinzugefügter Dateien
+    pub bytes_added: u64,
+    /// Summe der Größen entfernter Dateien
+    pub bytes_removed: u64,
+    /// Gesamte Größenänderung in Bytes
+    pub size_delta: i64,
+}
+
+/// Optionen für den Snapshot-Diff
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotDiffOptions {
+    /// Nur Einträge unterhalb dieses Pfades vergleichen
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    /// Anzahl zu überspringender Einträge (Paging)
+    #[serde(default)]
+    pub offset: u64,
+    /// Maximale Anzahl Einträge pro Seite (Standard 1000)
+    #[serde(default)]
+    pub limit: Option<u64>,
+    /// Reine Metadaten-Änderungen (Zeit, Rechte, Besitzer) ignorieren
+    #[serde(default)]
+    pub ignore_metadata: bool,
+    /// Verzeichnistiefe, bis zu der Änderungen aufsummiert werden (Standard 2)
+    #[serde(default)]
+    pub rollup_depth: Option<u32>,
+}
+
+/// Ergebnis eines Snapshot-Diffs (eine Seite der Einträge)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotDiffDto {
+    /// ID des älteren Snapshots
+    pub snapshot_a: String,
+    /// ID des neueren Snapshots
+    pub snapshot_b: String,
+    /// Einträge dieser Seite, nach Pfad sortiert
+    pub entries: Vec<DiffEntryDto>,
+    /// Gesamtzahl der Einträge (alle Seiten)
+    pub total_entries: u64,
+    /// Offset dieser Seite
+    pub offset: u64,
+    /// Es gibt weitere Einträge nach dieser Seite
+    pub has_more: bool,
+    /// Gesamtstatistik
+    pub stats: SnapshotDiffStats,
+    /// Aufsummierte Änderungen pro Verzeichnis (bis `rollup_depth`), nach Pfad sortiert
+    pub directories: Vec<DirectoryDiffDto>,
+}
+
+/// Art des Suchmusters für `find_in_snapshots`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FindPatternKind {
+    /// Glob-Muster (`*.pdf`, `**/docs/*.txt`)
+    #[default]
+    Glob,
+    /// Regulärer Ausdruck
+    Regex,
+}
+
+/// Suchanfrage für Dateien über mehrere Snapshots
+///
+/// Muster ohne `/` werden auf den Dateinamen angewendet, sonst auf den vollständigen
+/// Pfad im Snapshot (mit führendem `/`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FindQuery {
+    /// Such-Muster
+    pub pattern: String,
+    /// Glob oder Regex
+    #[serde(default)]
+    pub kind: FindPatternKind,
+    /// Groß-/Kleinschreibung ignorieren
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// Mindestgröße in Bytes
+    #[serde(default)]
+    pub min_size: Option<u64>,
+    /// Maximalgröße in Bytes
+    #[serde(default)]
+    pub max_size: Option<u64>,
+    /// Nur Dateien, die ab diesem Zeitpunkt geändert wurden (RFC 3339)
+    #[serde(default)]
+    pub modified_after: Option<String>,
+    /// Nur Dateien, die bis zu diesem Zeitpunkt geändert wurden (RFC 3339)
+    #[serde(default)]
+    pub modified_before: Option<String>,
+    /// Maximale Anzahl Treffer (Standard 10000)
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+/// Eine gefundene Datei in einem Snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMatchDto {
+    /// ID des Snapshots
+    pub snapshot_id: String,
+    /// Zeitpunkt des Snapshots (ISO 8601)
+    pub snapshot_time: String,
+    /// Hostname des Snapshots
+    pub hostname: String,
+    /// Pfad der Datei im Snapshot
+    pub path: String,
+    /// Größe in Bytes
+    pub size: u64,
+    /// Änderungszeit (ISO 8601)
+    pub modified: Option<String>,
+    /// Kennung des Inhalts; gleiche Werte bedeuten identische Versionen
+    pub version: String,
+}
+
+/// Ergebnis einer Suche über Snapshots
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FindResultDto {
+    /// Treffer, neueste Snapshots zuerst, innerhalb eines Snapshots nach Pfad sortiert
+    pub matches: Vec<FileMatchDto>,
+    /// Anzahl durchsuchter Snapshots
+    pub snapshots_searched: u64,
+    /// Anzahl gelesener Tree-Blobs (identische Trees werden nur einmal gelesen)
+    pub trees_scanned: u64,
+    /// Trefferlimit erreicht, weitere Treffer wurden verworfen
+    pub truncated: bool,
+}
+
+/// Eine Version einer Datei (aufeinanderfolgende Snapshots mit gleichem Inhalt)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileVersionDto {
+    /// Kennung des Inhalts (wie `FileMatchDto::version`)
+    pub version: String,
+    /// Größe in Bytes
+    pub size: u64,
+    /// Änderungszeit im ersten Snapshot dieser Version (ISO 8601)
+    pub modified: Option<String>,
+    /// Erster Snapshot mit dieser Version
+    pub first_snapshot_id: String,
+    /// Zeitpunkt des ersten Snapshots (ISO 8601)
+    pub first_snapshot_time: String,
+    /// Letzter Snapshot mit dieser Version
+    pub last_snapshot_id: String,
+    /// Zeitpunkt des letzten Snapshots (ISO 8601)
+    pub last_snapshot_time: String,
+    /// Anzahl Snapshots mit dieser Version
+    pub snapshot_count: u64,
+}
+
+/// Versionsgeschichte einer Datei über alle Snapshots
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileHistoryDto {
+    /// Pfad der Datei im Snapshot
+    pub path: String,
+    /// Versionen, neueste zuerst
+    pub versions: Vec<FileVersionDto>,
+    /// Anzahl durchsuchter Snapshots
+    pub snapshots_searched: u64,
+    /// Anzahl Snapshots, die die Datei enthalten
+    pub snapshots_containing: u64,
+}
+
+/// Sortierschlüssel für die Snapshot-Liste
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotSortKey {
+    /// Erstellungszeit
+    #[default]
+    Time,
+    /// Hostname
+    Hostname,
+    /// Gesicherte Datenmenge laut Summary
+    Size,
+    /// Anzahl verarbeiteter Dateien laut Summary
+    Files,
+    /// Snapshot-ID
+    Id,
+}
+
+/// Sortierung und Paging für die Snapshot-Liste
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotQuery {
+    /// Sortierschlüssel (Standard: Zeit)
+    #[serde(default)]
+    pub sort: SnapshotSortKey,
+    /// Aufsteigend sortieren (Standard: absteigend, neueste zuerst)
+    #[serde(default)]
+    pub ascending: bool,
+    /// Anzahl zu überspringender Snapshots
+    #[serde(default)]
+    pub offset: u64,
+    /// Maximale Anzahl Snapshots pro Seite (Standard 500)
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+/// Eine Seite der gefilterten und sortierten Snapshot-Liste
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotPageDto {
+    /// Snapshots dieser Seite
+    pub snapshots: Vec<SnapshotDto>,
+    /// Anzahl aller Snapshots, die dem Filter entsprechen
+    pub total: u64,
+    /// Offset dieser Seite
+    pub offset: u64,
+    /// Es gibt weitere Seiten
+    pub has_more: bool,
+}
+
 // ===== M4: Repository-Statistiken =====
 
 /// Detaillierte Repository-Statistiken
@@ -297,12 +923,74 @@ pub struct RepositoryStatsDto {
     pub total_size: u64,
     /// Größe der tatsächlichen Daten in Bytes (ohne Duplikate)
     pub data_size: u64,
-    /// Kompressionsrate (0.0 - 1.0)
+    /// Kompressionsrate (0.0 - 1.0): gespeicherte Größe / Rohgröße der Blobs
     pub compression_ratio: f64,
-    /// Deduplizierungs-Rate (0.0 - 1.0)
+    /// Deduplizierungs-Rate (0.0 - 1.0): Anteil der gesicherten Daten, der nicht erneut
+    /// gespeichert werden musste
     pub deduplication_ratio: f64,
     /// Anzahl eindeutiger Blobs
     pub unique_blobs: u64,
+    /// Summe der in allen Snapshots verarbeiteten Bytes (vor Deduplizierung)
+    #[serde(default)]
+    pub snapshot_data_size: u64,
+    /// Aufschlüsselung der Tree-Blobs (Verzeichnis-Metadaten)
+    #[serde(default)]
+    pub tree_blobs: BlobTypeStatsDto,
+    /// Aufschlüsselung der Data-Blobs (Dateiinhalte)
+    #[serde(default)]
+    pub data_blobs: BlobTypeStatsDto,
+    /// Zeitpunkt der Berechnung (ISO 8601); bei Cache-Treffern älter als die Anfrage
+    #[serde(default)]
+    pub computed_at: String,
+}
+
+/// Speicherbelegung einer Gruppe von Snapshots (ein Snapshot, Host, Tag oder Job)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageUsageDto {
+    /// Snapshot-ID, Hostname, Tag oder Job-ID
+    pub key: String,
+    /// Anzeigename (bei Snapshots der Zeitpunkt, bei Jobs der Job-Name)
+    pub label: String,
+    /// Anzahl Snapshots der Gruppe
+    pub snapshot_count: u64,
+    /// Bytes, die nur von dieser Gruppe referenziert werden (frei nach Forget + Prune)
+    pub unique_size: u64,
+    /// Bytes, die die Gruppe mit anderen Snapshots teilt
+    pub shared_size: u64,
+    /// Insgesamt von der Gruppe referenzierte Bytes
+    pub referenced_size: u64,
+}
+
+/// Speicherbelegung eines Repositories aufgeschlüsselt nach Snapshot, Host, Tag und Job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageAttributionDto {
+    /// Pro Snapshot, neueste zuerst
+    pub snapshots: Vec<StorageUsageDto>,
+    /// Pro Host, größte eigene Belegung zuerst
+    pub hosts: Vec<StorageUsageDto>,
+    /// Pro Tag, größte eigene Belegung zuerst
+    pub tags: Vec<StorageUsageDto>,
+    /// Pro Backup-Job, größte eigene Belegung zuerst
+    pub jobs: Vec<StorageUsageDto>,
+    /// Von mindestens einem Snapshot referenzierte Bytes
+    pub referenced_size: u64,
+    /// Anzahl gelesener Trees
+    pub trees_scanned: u64,
+    /// Referenzierte Blobs ohne Index-Eintrag (Hinweis auf ein beschädigtes Repository)
+    pub missing_blobs: u64,
+}
+
+/// Statistiken für einen Blob-Typ (Tree oder Data) laut Index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BlobTypeStatsDto {
+    /// Anzahl der Blobs
+    pub blob_count: u64,
+    /// Anzahl der Pack-Dateien
+    pub pack_count: u64,
+    /// Gespeicherte Größe (komprimiert und verschlüsselt) in Bytes
+    pub packed_size: u64,
+    /// Rohgröße in Bytes
+    pub data_size: u64,
 }
 
 // ===== M3: Job-Execution-Tracking =====
@@ -337,6 +1025,44 @@ pub struct JobExecution {
     pub files_processed: u64,
     /// Anzahl verarbeiteter Bytes
     pub bytes_processed: u64,
+    /// Laufzeit in Sekunden (None solange der Job läuft)
+    #[serde(default)]
+    pub duration_seconds: Option<u64>,
+    /// Fehlermeldung (bei Fehler)
+    pub error_message: Option<String>,
+    /// Ergebnisse der Wartungsschritte nach dem Backup
+    #[serde(default)]
+    pub stages: Vec<JobStageResult>,
+}
+
+/// Wartungsschritt nach einem Backup
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStage {
+    /// Retention-Policy auf die Snapshots des Jobs anwenden
+    Retention,
+    /// Nicht mehr benötigte Daten aus dem Repository entfernen
+    Prune,
+}
+
+/// Ergebnis eines Wartungsschritts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStageResult {
+    /// Art des Schritts
+    pub stage: JobStage,
+    /// Status (Completed oder Failed)
+    pub status: JobExecutionStatus,
+    /// Startzeitpunkt
+    pub started_at: String, // ISO 8601
+    /// Laufzeit in Sekunden
+    pub duration_seconds: Option<u64>,
+    /// Von der Retention entfernte Snapshot-IDs
+    #[serde(default)]
+    pub snapshots_removed: Vec<String>,
+    /// Durch Prune entfernte Pack-Dateien
+    pub packs_removed: Option<u64>,
+    /// Durch Prune freigegebene Bytes
+    pub bytes_freed: Option<u64>,
     /// Fehlermeldung (bei Fehler)
     pub error_message: Option<String>,
 }
@@ -390,7 +1116,7 @@ mod tests {
 }
 
 /// DTO für Check-Ergebnisse
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CheckResultDto {
     /// Liste von Fehlern (kritisch)
     pub errors: Vec<String>,
@@ -398,6 +1124,252 @@ pub struct CheckResultDto {
     pub warnings: Vec<String>,
     /// Ob das Repository OK ist (keine Fehler)
     pub is_ok: bool,
+    /// Strukturierte Befunde (höchstens 1000, siehe `findings_truncated`)
+    #[serde(default)]
+    pub findings: Vec<CheckFindingDto>,
+    /// Weitere Befunde wurden nur gezählt, nicht aufgelistet
+    #[serde(default)]
+    pub findings_truncated: bool,
+    /// Anzahl Befunde pro Kategorie
+    #[serde(default)]
+    pub summary: CheckSummaryDto,
+    /// Anzahl geprüfter Pack-Dateien (Index gegen Backend)
+    #[serde(default)]
+    pub packs_checked: u64,
+    /// Anzahl geprüfter Snapshots
+    #[serde(default)]
+    pub snapshots_checked: u64,
+    /// Anzahl gelesener Trees
+    #[serde(default)]
+    pub trees_checked: u64,
+    /// Anzahl vollständig gelesener Pack-Dateien (read-data)
+    #[serde(default)]
+    pub packs_read: u64,
+    /// Gelesene Bytes (read-data)
+    #[serde(default)]
+    pub bytes_read: u64,
+}
+
+/// Kategorie eines Befunds der Repository-Prüfung
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckCategory {
+    /// Pack-Datei laut Index vorhanden, fehlt aber im Backend
+    MissingPack,
+    /// Pack-Datei im Backend, aber in keinem Index
+    UnreferencedPack,
+    /// Index-Datei unlesbar oder Angaben passen nicht zur Pack-Datei
+    IndexMismatch,
+    /// Snapshot-Datei unlesbar
+    SnapshotError,
+    /// Tree unlesbar oder verweist auf fehlende Blobs
+    TreeError,
+    /// Pack oder Blob beim Lesen der Daten beschädigt (Entschlüsselung oder Hash)
+    DamagedBlob,
+}
+
+/// Schweregrad eines Befunds
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckSeverity {
+    /// Datenverlust oder Inkonsistenz
+    Error,
+    /// Unkritisch, z.B. durch Prune oder Reparatur des Index behebbar
+    Warning,
+}
+
+/// Ein Befund der Repository-Prüfung
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckFindingDto {
+    pub category: CheckCategory,
+    pub severity: CheckSeverity,
+    /// Betroffene Pack-, Blob- oder Tree-ID
+    pub id: Option<String>,
+    /// Betroffener Snapshot (nur bei Tree-Fehlern)
+    pub snapshot_id: Option<String>,
+    pub message: String,
+}
+
+/// Anzahl Befunde pro Kategorie
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckSummaryDto {
+    pub missing_packs: u64,
+    pub unreferenced_packs: u64,
+    pub index_mismatches: u64,
+    pub snapshot_errors: u64,
+    pub tree_errors: u64,
+    pub damaged_blobs: u64,
+}
+
+/// Teilmenge der Pack-Dateien, die bei read-data gelesen wird
+///
+/// Die Auswahl ist zufällig, sodass wiederholte Prüfungen nach und nach das ganze
+/// Repository abdecken (wie `--read-data-subset` von rustic).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReadDataSubset {
+    /// Alle Pack-Dateien lesen
+    #[default]
+    All,
+    /// Prozentsatz der Pack-Dateien
+    Percentage {
+        /// Anteil in Prozent (0 - 100)
+        percent: f64,
+    },
+    /// Höchstens so viele Bytes lesen
+    Bytes {
+        /// Byte-Budget
+        max_bytes: u64,
+    },
+}
+
+/// Phase der Repository-Prüfung
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckPhase {
+    /// Index-Dateien lesen
+    Index,
+    /// Index gegen Pack-Dateien im Backend abgleichen
+    Packs,
+    /// Trees aller Snapshots prüfen
+    Trees,
+    /// Daten der ausgewählten Pack-Dateien lesen
+    ReadData,
+}
+
+/// Fortschritt der Repository-Prüfung
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckProgress {
+    #[serde(flatten)]
+    pub base: ProgressInfo,
+    pub phase: CheckPhase,
+}
+
+/// Plan (und Ergebnis) der Index-Reparatur
+///
+/// Ohne `read_all` werden nur Index-Einträge korrigiert, die nicht zu den Pack-Dateien im
+/// Backend passen; die Header dieser Packs werden neu gelesen.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepairIndexPlanDto {
+    /// Kennung des Plans; muss beim Ausführen bestätigt werden
+    pub plan_id: String,
+    /// Ob nur geplant wurde (keine Änderungen am Repository)
+    pub dry_run: bool,
+    /// Alle Pack-Header neu lesen (Index komplett neu aufbauen)
+    pub read_all: bool,
+    /// Anzahl Index-Dateien
+    pub index_files: u64,
+    /// Anzahl Index-Dateien, die ersetzt werden
+    pub index_files_changed: u64,
+    /// Packs im Index, die im Backend fehlen (werden aus dem Index entfernt)
+    pub packs_missing: u64,
+    /// Packs, die in mehreren Index-Dateien stehen (Duplikate werden entfernt)
+    pub packs_duplicate: u64,
+    /// Packs, deren Größe nicht zum Index passt
+    pub packs_size_mismatch: u64,
+    /// Packs im Backend, die in keinem Index stehen
+    pub packs_unindexed: u64,
+    /// Packs, deren Header gelesen werden
+    pub packs_to_read: u64,
+}
+
+/// Optionen der Snapshot-Reparatur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairSnapshotsOptionsDto {
+    /// Zu prüfende Snapshots (leer = alle)
+    #[serde(default)]
+    pub snapshot_ids: Vec<String>,
+    /// Beschädigte Original-Snapshots (und Snapshots ohne lesbaren Root-Tree) löschen
+    #[serde(default)]
+    pub delete: bool,
+    /// Suffix für Dateien und Verzeichnisse mit fehlendem Inhalt
+    #[serde(default = "default_repair_suffix")]
+    pub suffix: String,
+    /// Tags für reparierte Snapshots
+    #[serde(default = "default_repair_tags")]
+    pub tags: Vec<String>,
+}
+
+fn default_repair_suffix() -> String {
+    ".repaired".to_string()
+}
+
+fn default_repair_tags() -> Vec<String> {
+    vec!["repaired".to_string()]
+}
+
+impl Default for RepairSnapshotsOptionsDto {
+    fn default() -> Self {
+        Self {
+            snapshot_ids: Vec::new(),
+            delete: false,
+            suffix: default_repair_suffix(),
+            tags: default_repair_tags(),
+        }
+    }
+}
+
+/// Geplante Aktion für einen Snapshot
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotRepairAction {
+    /// Snapshot ist intakt
+    Keep,
+    /// Reparierte Kopie wird gespeichert (Original wird mit `delete` entfernt)
+    Repair,
+    /// Root-Tree nicht lesbar; Snapshot wird entfernt (nur mit `delete`)
+    Remove,
+    /// Root-Tree nicht lesbar; ohne `delete` bleibt der Snapshot unverändert
+    Unrepairable,
+}
+
+/// Befund der Snapshot-Reparatur für einen Snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotRepairDto {
+    pub snapshot_id: String,
+    pub time: String,
+    pub hostname: String,
+    pub paths: Vec<String>,
+    pub action: SnapshotRepairAction,
+    /// Nicht lesbare Trees (Verzeichnisse werden leer mit Suffix gespeichert)
+    pub damaged_trees: u64,
+    /// Dateien mit fehlenden Blobs (werden gekürzt mit Suffix gespeichert)
+    pub damaged_files: u64,
+    /// Dateien, deren Größe korrigiert wird
+    pub corrected_sizes: u64,
+}
+
+/// Plan (und Ergebnis) der Snapshot-Reparatur
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepairSnapshotsPlanDto {
+    /// Kennung des Plans; muss beim Ausführen bestätigt werden
+    pub plan_id: String,
+    /// Ob nur geplant wurde (keine Änderungen am Repository)
+    pub dry_run: bool,
+    /// Anzahl geprüfter Snapshots
+    pub snapshots_checked: u64,
+    /// Snapshots, die repariert oder entfernt werden (intakte Snapshots fehlen)
+    pub snapshots: Vec<SnapshotRepairDto>,
+    /// Anzahl Snapshots, die gelöscht werden (nur mit `delete`)
+    pub snapshots_removed: u64,
+}
+
+/// Stufe einer Reparatur
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairStage {
+    /// Plan wird erstellt (Repository wird nur gelesen)
+    Planning,
+    /// Plan wird ausgeführt
+    Applying,
+}
+
+/// Fortschritt einer Reparatur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairProgress {
+    #[serde(flatten)]
+    pub base: ProgressInfo,
+    pub stage: RepairStage,
 }
 
 /// DTO für Prune-Ergebnisse
@@ -10,6 +10,7 @@ mod integration_tests {
             errors: vec!["Error 1".to_string()],
             warnings: vec!["Warning 1".to_string()],
             is_ok: false,
+            ..Default::default()
         };
 
         assert_eq!(dto.errors.len(), 1);
@@ -82,6 +83,8 @@ mod integration_tests {
             last_run: None,
             next_run: Some("2025-10-27T02:00:00Z".to_string()),
             retention: None,
+            catch_up: None,
+            post_backup: None,
             password: None,
         };
 
@@ -120,16 +123,19 @@ mod integration_tests {
             snapshot_id: "snapshot-1".to_string(),
             target_path: "/tmp/restore".to_string(),
             paths: vec!["file1.txt".to_string(), "dir1".to_string()],
-            overwrite: true,
+            overwrite: OverwritePolicy::IfNewer,
+            delete: false,
             restore_permissions: true,
             restore_timestamps: false,
             dry_run: false,
+            layout: RestoreLayout::Relative,
         };
 
         assert_eq!(options.snapshot_id, "snapshot-1");
         assert_eq!(options.target_path, "/tmp/restore");
         assert_eq!(options.paths.len(), 2);
-        assert!(options.overwrite);
+        assert_eq!(options.overwrite, OverwritePolicy::IfNewer);
+        assert_eq!(options.layout, RestoreLayout::Relative);
     }
 
     #[test]
@@ -203,7 +209,10 @@ mod integration_tests {
                 keep_weekly: None,
                 keep_monthly: None,
                 keep_yearly: None,
+                ..Default::default()
             }),
+            catch_up: None,
+            post_backup: None,
             password: None,
         };
         assert_eq!(job.repository_id, repo.id);
@@ -229,10 +238,12 @@ mod integration_tests {
             snapshot_id: snapshot.id.clone(),
             target_path: "/tmp/restore".to_string(),
             paths: vec![],
-            overwrite: true,
+            overwrite: OverwritePolicy::Always,
+            delete: true,
             restore_permissions: true,
             restore_timestamps: true,
             dry_run: false,
+            layout: RestoreLayout::default(),
         };
         assert_eq!(restore_opts.snapshot_id, snapshot.id);
     }
@@ -242,7 +253,7 @@ mod integration_tests {
         // Test Repository-Wartung Workflow mit DTOs
 
         // 1. Check Result
-        let check_result = CheckResultDto { errors: vec![], warnings: vec![], is_ok: true };
+        let check_result = CheckResultDto { is_ok: true, ..Default::default() };
         assert!(check_result.is_ok);
         assert_eq!(check_result.errors.len(), 0);
 
@@ -292,6 +303,7 @@ mod integration_tests {
             keep_weekly: Some(4),
             keep_monthly: Some(12),
             keep_yearly: Some(2),
+            ..Default::default()
         };
 
         assert_eq!(policy.keep_last, Some(10));
@@ -1,4 +1,10 @@
-import type { BackupJobDto } from '$lib/types';
+import type {
+  BackupJobDto,
+  BackupJobUpdate,
+  QueueEntry,
+  SnapshotDiffDto,
+  SnapshotDiffOptions,
+} from '$lib/types';
 import { invoke } from '@tauri-apps/api/core';
 
 /**
@@ -49,12 +55,13 @@ export async function createBackupJob(job: Partial<BackupJobDto>): Promise<strin
 /**
  * Aktualisiert einen existierenden Backup-Job.
  *
- * @param job - Vollständige Backup-Job-Konfiguration
+ * @param jobId - Backup-Job-ID
+ * @param update - Zu ändernde Felder (nicht gesetzte Felder bleiben unverändert)
  * @returns Promise (void)
  * @throws Error wenn Job nicht existiert oder Update fehlschlägt
  */
-export async function updateBackupJob(job: BackupJobDto): Promise<void> {
-  await invoke('update_backup_job', { job });
+export async function updateBackupJob(jobId: string, update: BackupJobUpdate): Promise<void> {
+  await invoke('update_backup_job', { jobId, update });
 }
 
 /**
@@ -100,3 +107,33 @@ export async function unscheduleBackup(jobId: string): Promise<void> {
 export async function listScheduledBackups(): Promise<string[]> {
   return await invoke<string[]>('list_scheduled_backups');
 }
+
+/**
+ * Listet laufende und wartende Backups der Job-Warteschlange auf.
+ *
+ * @returns Promise mit Array von Warteschlangen-Einträgen (laufende zuerst)
+ * @throws Error wenn Abfrage fehlschlägt
+ */
+export async function listJobQueue(): Promise<QueueEntry[]> {
+  return await invoke<QueueEntry[]>('list_job_queue');
+}
+
+/**
+ * Zeigt, was ein neues Backup des Jobs aufnehmen würde.
+ *
+ * Vergleicht den letzten Snapshot des Jobs mit dem aktuellen Dateisystem
+ * (gleiche Exclude- und .gitignore-Filter wie das Backup).
+ *
+ * @param jobId - ID des Backup-Jobs
+ * @param password - Repository-Passwort, falls nicht im Job gespeichert
+ * @param options - Präfix-Filter, Paging und Rollup-Tiefe
+ * @returns Promise mit neuen (added), geänderten (changed) und gelöschten (removed) Einträgen
+ * @throws Error wenn Job, Repository oder Passwort fehlen
+ */
+export async function diffBackupSource(
+  jobId: string,
+  password?: string,
+  options?: SnapshotDiffOptions
+): Promise<SnapshotDiffDto> {
+  return await invoke<SnapshotDiffDto>('diff_backup_source', { jobId, password, options });
+}
@@ -1,4 +1,11 @@
-import type { RepositoryDto } from '$lib/types';
+import type {
+  CheckResultDto,
+  ReadDataSubset,
+  RepairIndexPlanDto,
+  RepairSnapshotsOptions,
+  RepairSnapshotsPlanDto,
+  RepositoryDto,
+} from '$lib/types';
 import { invoke } from '@tauri-apps/api/core';
 
 /**
@@ -11,6 +18,7 @@ import { invoke } from '@tauri-apps/api/core';
  * - listRepositories
  * - deleteRepository
  * - pruneRepository
+ * - repairIndex / repairSnapshots
  * - changePassword
  *
  * Backend-Commands:
@@ -122,14 +130,16 @@ export async function checkRepositoryV1(path: string, password: string): Promise
 /**
  * Prüft Repository-Integrität (v2 - über Repository-ID im State).
  *
- * Nutzt das bereits geöffnete Repository aus dem Backend-State.
- * Führt Konsistenzprüfung aller Snapshots und Trees durch.
+ * Prüft Index, Pack-Dateien und die Trees aller Snapshots und liefert die Befunde
+ * nach Kategorie. Fortschritt wird über das Event `check-progress` gemeldet.
  *
  * @param repositoryId - Repository-ID aus Config
  * @param trustCache - Cache vertrauen (schneller, weniger sicher)
  * @param readData - Pack-Dateien lesen und verifizieren (gründlich, langsamer)
- * @returns Promise mit Check-Ergebnis (errors/warnings/is_ok)
- * @throws Error wenn Check fehlschlägt
+ * @param readDataSubset - Optional: Nur einen zufälligen Teil der Packs lesen
+ * @returns Promise mit Check-Ergebnis (Befunde, Zusammenfassung, is_ok); ist das
+ *   Repository nicht lesbar, steht der Fehler in `errors`
+ * @throws Error bei unbekanntem Repository oder ungültiger Teilmenge
  *
  * @example
  * ```typescript
@@ -141,23 +151,22 @@ export async function checkRepositoryV1(path: string, password: string): Promise
  *
  * // Gründlicher Check (ohne Cache, mit Daten-Verifikation)
  * const fullCheck = await checkRepository('repo-123', false, true);
+ *
+ * // 10% der Pack-Dateien lesen
+ * await checkRepository('repo-123', false, true, { type: 'percentage', percent: 10 });
  * ```
  */
-export interface CheckResultDto {
-  errors: string[];
-  warnings: string[];
-  is_ok: boolean;
-}
-
 export async function checkRepository(
   repositoryId: string,
   trustCache: boolean = true,
-  readData: boolean = false
+  readData: boolean = false,
+  readDataSubset?: ReadDataSubset
 ): Promise<CheckResultDto> {
   return await invoke<CheckResultDto>('check_repository', {
     repositoryId,
     trustCache,
     readData,
+    readDataSubset,
   });
 }
 
@@ -245,6 +254,62 @@ export async function pruneRepository(
   });
 }
 
+/**
+ * Repariert den Index eines Repositories.
+ *
+ * Ohne `planId` wird nur ein Dry-Run ausgeführt. Zum Ausführen muss die `plan_id` des
+ * Dry-Runs bestätigt werden; hat sich das Repository inzwischen geändert, schlägt der
+ * Aufruf fehl. Fortschritt wird über das Event `repair-progress` gemeldet.
+ *
+ * @param repositoryId - Repository-ID aus Config
+ * @param readAll - Alle Pack-Header lesen (Index komplett neu aufbauen)
+ * @param planId - Bestätigte Plan-ID aus dem Dry-Run (optional)
+ * @returns Promise mit dem (ausgeführten) Plan
+ *
+ * @example
+ * ```typescript
+ * const plan = await repairIndex('repo-123');
+ * if (plan.index_files_changed > 0 && confirm('Index reparieren?')) {
+ *   await repairIndex('repo-123', false, plan.plan_id);
+ * }
+ * ```
+ */
+export async function repairIndex(
+  repositoryId: string,
+  readAll: boolean = false,
+  planId?: string
+): Promise<RepairIndexPlanDto> {
+  return await invoke<RepairIndexPlanDto>('repair_index', {
+    repositoryId,
+    readAll,
+    dryRun: planId === undefined,
+    planId,
+  });
+}
+
+/**
+ * Repariert beschädigte Snapshots.
+ *
+ * Ablauf wie bei `repairIndex`: erst Dry-Run, dann Ausführung mit bestätigter `planId`.
+ *
+ * @param repositoryId - Repository-ID aus Config
+ * @param options - Auswahl der Snapshots, Suffix, Tags, Originale löschen
+ * @param planId - Bestätigte Plan-ID aus dem Dry-Run (optional)
+ * @returns Promise mit dem (ausgeführten) Plan
+ */
+export async function repairSnapshots(
+  repositoryId: string,
+  options: RepairSnapshotsOptions = {},
+  planId?: string
+): Promise<RepairSnapshotsPlanDto> {
+  return await invoke<RepairSnapshotsPlanDto>('repair_snapshots', {
+    repositoryId,
+    options,
+    dryRun: planId === undefined,
+    planId,
+  });
+}
+
 /**
  * Ändert das Repository-Passwort.
  *
@@ -283,12 +348,80 @@ export async function changePassword(
  * Holt detaillierte Statistiken für ein Repository.
  * M4.3: Repository-Statistiken
  *
+ * Das Backend cacht die Statistiken pro Repository, bis sich Index- oder
+ * Snapshot-Dateien ändern.
+ *
  * @param id - Repository-ID
+ * @param refresh - Cache ignorieren und neu berechnen
  * @returns Promise mit Repository-Statistiken
  * @throws Error wenn Abruf fehlschlägt
  */
-export async function getRepositoryStats(id: string): Promise<RepositoryStats> {
-  return await invoke<RepositoryStats>('get_repository_stats', { id });
+export async function getRepositoryStats(id: string, refresh = false): Promise<RepositoryStats> {
+  return await invoke<RepositoryStats>('get_repository_stats', { id, refresh });
+}
+
+/**
+ * Ermittelt die Speicherbelegung pro Snapshot, Host, Tag und Backup-Job.
+ *
+ * Liest alle Trees des Repositories und kann bei großen Repositories dauern.
+ *
+ * @param repositoryPath - Repository-Pfad
+ * @param password - Repository-Passwort
+ * @returns Promise mit eigener und geteilter Belegung jeder Gruppe
+ * @throws Error wenn Index oder Trees nicht gelesen werden können
+ */
+export async function getStorageAttribution(
+  repositoryPath: string,
+  password: string
+): Promise<StorageAttribution> {
+  return await invoke<StorageAttribution>('get_storage_attribution', {
+    repositoryPath,
+    password,
+  });
+}
+
+/**
+ * Speicherbelegung einer Gruppe von Snapshots (Snapshot, Host, Tag oder Job)
+ */
+export interface StorageUsage {
+  /** Snapshot-ID, Hostname, Tag oder Job-ID */
+  key: string;
+  /** Bei Snapshots der Zeitpunkt, bei Jobs der Job-Name */
+  label: string;
+  snapshot_count: number;
+  /** Frei nach Forget der Gruppe und Prune */
+  unique_size: number;
+  /** Mit anderen Snapshots geteilt */
+  shared_size: number;
+  referenced_size: number;
+}
+
+/**
+ * Speicherbelegung aufgeschlüsselt nach Snapshot, Host, Tag und Job
+ */
+export interface StorageAttribution {
+  /** Neueste zuerst */
+  snapshots: StorageUsage[];
+  /** Größte eigene Belegung zuerst (gilt auch für tags und jobs) */
+  hosts: StorageUsage[];
+  tags: StorageUsage[];
+  jobs: StorageUsage[];
+  referenced_size: number;
+  trees_scanned: number;
+  /** Referenzierte Blobs ohne Index-Eintrag */
+  missing_blobs: number;
+}
+
+/**
+ * Statistiken für einen Blob-Typ (Tree oder Data)
+ */
+export interface BlobTypeStats {
+  blob_count: number;
+  pack_count: number;
+  /** Gespeicherte Größe (komprimiert und verschlüsselt) in Bytes */
+  packed_size: number;
+  /** Rohgröße in Bytes */
+  data_size: number;
 }
 
 /**
@@ -300,7 +433,15 @@ export interface RepositoryStats {
   pack_count: number;
   total_size: number;
   data_size: number;
+  /** Gespeicherte Größe / Rohgröße (1.0 = keine Kompression) */
   compression_ratio: number;
+  /** Eingesparter Anteil der in Snapshots verarbeiteten Daten (0.0 - 1.0) */
   deduplication_ratio: number;
   unique_blobs: number;
+  /** Summe der in allen Snapshots verarbeiteten Bytes */
+  snapshot_data_size: number;
+  tree_blobs: BlobTypeStats;
+  data_blobs: BlobTypeStats;
+  /** ISO 8601 */
+  computed_at: string;
 }
@@ -1,6 +1,15 @@
 // src/lib/api/restore.ts
 
-import type { FileTreeNode, RestoreOptionsDto, RestoreProgress } from '$lib/types';
+import type {
+  ArchiveFormat,
+  ArchiveResultDto,
+  ExtractedFileDto,
+  FilePreviewDto,
+  FileTreeNode,
+  RestoreOptionsDto,
+  RestorePlanDto,
+  RestoreProgress,
+} from '$lib/types';
 import { invoke } from '@tauri-apps/api/core';
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
@@ -12,12 +21,17 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event';
  * Kapselt alle Tauri-Commands und Events für Datei-Wiederherstellung.
  *
  * Commands:
- * - getFileTree (TODO.md Zeile 198) ✅ IMPLEMENTIERT (lib.rs:312, stub in rustic/restore.rs:181)
- * - restoreFiles (TODO.md Zeile 195-197) ✅ IMPLEMENTIERT (lib.rs:324, simuliert mit Events)
+ * - getFileTree (TODO.md Zeile 198) ✅ IMPLEMENTIERT (lädt eine Verzeichnisebene aus den Snapshot-Trees)
+ * - restoreFiles (TODO.md Zeile 195-197) ✅ IMPLEMENTIERT (Fortschritt aus rustic_core)
+ * - restoreToArchive ✅ IMPLEMENTIERT (tar, tar.gz, tar.zst, zip)
+ * - previewSnapshotFile / extractSnapshotFile ✅ IMPLEMENTIERT (einzelne Datei ohne Restore)
+ * - cancelRestore ✅ IMPLEMENTIERT
  *
- * Backend-Events:
- * - restore-progress (RestoreEvent in lib.rs:16-24)
+ * Backend-Events (RestoreEvent in commands/restore.rs):
+ * - restore-started
+ * - restore-progress
  * - restore-completed
+ * - restore-cancelled
  * - restore-failed
  */
 
@@ -54,12 +68,14 @@ export async function getFileTree(
  * @param files - Liste der wiederherzustellenden Dateien/Pfade
  * @param targetPath - Zielverzeichnis für die Wiederherstellung
  * @param options - Restore-Optionen
- * @returns Promise mit void (Erfolg)
+ * @param restoreId - Optionale ID für Events und Abbruch via cancelRestore
+ * @returns Promise mit dem Restore-Plan (bei `dry_run` nur geplant, ohne Änderungen)
  * @throws Error wenn Repository nicht geöffnet, Ziel nicht beschreibbar oder Restore fehlschlägt
  *
- * 📡 Fortschritt wird via Tauri-Events kommuniziert:
- * - `restore-progress` (RestoreProgress)
+ * 📡 Fortschritt wird via Tauri-Events kommuniziert (Payload: RestoreEvent mit `restoreId`):
+ * - `restore-progress` (`progress`: RestoreProgress)
  * - `restore-completed`
+ * - `restore-cancelled`
  * - `restore-failed`
  */
 export async function restoreFiles(
@@ -68,8 +84,9 @@ export async function restoreFiles(
   snapshotId: string,
   files: string[],
   targetPath: string,
-  options: Omit<RestoreOptionsDto, 'snapshot_id' | 'target_path' | 'paths'>
-): Promise<void> {
+  options: Omit<RestoreOptionsDto, 'snapshot_id' | 'target_path' | 'paths'>,
+  restoreId?: string
+): Promise<RestorePlanDto> {
   const restoreOptions: RestoreOptionsDto = {
     snapshot_id: snapshotId,
     target_path: targetPath,
@@ -77,16 +94,117 @@ export async function restoreFiles(
     ...options,
   };
 
-  return await invoke('restore_files_v1', {
+  return await invoke<RestorePlanDto>('restore_files_v1', {
     repositoryPath,
     password,
     snapshotId,
     files,
     targetPath,
     options: restoreOptions,
+    restoreId,
   });
 }
 
+/**
+ * Stellt einen Snapshot-Teilbaum als Archivdatei wieder her (tar, tar.gz, tar.zst, zip).
+ *
+ * Sendet dieselben Events wie restoreFiles und ist über cancelRestore abbrechbar.
+ *
+ * @param repositoryPath - Pfad zum Repository
+ * @param password - Repository-Passwort
+ * @param snapshotId - ID des Snapshots
+ * @param path - Pfad im Snapshot (undefined = kompletter Snapshot)
+ * @param outputPath - Zu erstellende Archivdatei
+ * @param format - Archivformat
+ * @param restoreId - Optionale ID für Events und Abbruch
+ * @returns Promise mit Zusammenfassung des erstellten Archivs
+ * @throws Error wenn der Pfad nicht existiert oder das Archiv nicht geschrieben werden kann
+ */
+export async function restoreToArchive(
+  repositoryPath: string,
+  password: string,
+  snapshotId: string,
+  path: string | undefined,
+  outputPath: string,
+  format: ArchiveFormat,
+  restoreId?: string
+): Promise<ArchiveResultDto> {
+  return await invoke<ArchiveResultDto>('restore_to_archive', {
+    repositoryPath,
+    password,
+    snapshotId,
+    path,
+    outputPath,
+    format,
+    restoreId,
+  });
+}
+
+/**
+ * Lädt die ersten Bytes einer Datei aus einem Snapshot (Text-/Bild-Vorschau).
+ *
+ * @param repositoryPath - Pfad zum Repository
+ * @param password - Repository-Passwort
+ * @param snapshotId - ID des Snapshots
+ * @param path - Pfad der Datei im Snapshot
+ * @param maxBytes - Maximal zu lesende Bytes (Standard 64 KiB)
+ * @returns Promise mit Base64-kodiertem Inhalt und Metadaten
+ * @throws Error wenn der Pfad nicht existiert oder keine Datei ist
+ */
+export async function previewSnapshotFile(
+  repositoryPath: string,
+  password: string,
+  snapshotId: string,
+  path: string,
+  maxBytes?: number
+): Promise<FilePreviewDto> {
+  return await invoke<FilePreviewDto>('preview_snapshot_file', {
+    repositoryPath,
+    password,
+    snapshotId,
+    path,
+    maxBytes,
+  });
+}
+
+/**
+ * Speichert eine einzelne Datei aus einem Snapshot, ohne kompletten Restore.
+ *
+ * @param repositoryPath - Pfad zum Repository
+ * @param password - Repository-Passwort
+ * @param snapshotId - ID des Snapshots
+ * @param path - Pfad der Datei im Snapshot
+ * @param outputPath - Zieldatei (undefined = temporäre Datei)
+ * @returns Promise mit Pfad der geschriebenen Datei
+ * @throws Error wenn der Pfad nicht existiert oder nicht geschrieben werden kann
+ */
+export async function extractSnapshotFile(
+  repositoryPath: string,
+  password: string,
+  snapshotId: string,
+  path: string,
+  outputPath?: string
+): Promise<ExtractedFileDto> {
+  return await invoke<ExtractedFileDto>('extract_snapshot_file', {
+    repositoryPath,
+    password,
+    snapshotId,
+    path,
+    outputPath,
+  });
+}
+
+/**
+ * Bricht einen laufenden Restore ab.
+ *
+ * @param restoreId - ID des Restores (wie an restoreFiles übergeben)
+ * @returns Promise mit void (Abbruch ausgelöst)
+ * @throws Error wenn kein Restore mit dieser ID läuft
+ */
+export async function cancelRestore(restoreId: string): Promise<void> {
+  return await invoke('cancel_restore', { restoreId });
+}
+
 /**
  * Hört auf Restore-Progress-Events.
  *
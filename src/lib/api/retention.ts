@@ -14,6 +14,21 @@ export interface RetentionPolicyPreview {
   snapshots_to_keep: string[];
   /** Snapshot-IDs die gelöscht würden */
   snapshots_to_delete: string[];
+  /** Aufschlüsselung pro Snapshot-Gruppe */
+  groups: RetentionGroupPreview[];
+}
+
+/**
+ * Preview einer einzelnen Snapshot-Gruppe
+ */
+export interface RetentionGroupPreview {
+  hostname?: string;
+  /** Pfade, kommagetrennt */
+  paths?: string;
+  /** Tags, kommagetrennt */
+  tags?: string;
+  snapshots_to_keep: string[];
+  snapshots_to_delete: string[];
 }
 
 // Re-export RetentionPolicy für Convenience
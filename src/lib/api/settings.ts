@@ -14,6 +14,7 @@ export interface AppSettings {
   log_level: string; // 'error' | 'warn' | 'info' | 'debug' | 'trace'
   check_updates: boolean;
   max_concurrent_backups: number;
+  max_concurrent_backups_per_repository: number;
   notifications_enabled: boolean;
   language: string; // 'de' | 'en'
   password_storage: string; // 'system_keychain' | 'in_memory'
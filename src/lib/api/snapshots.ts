@@ -1,4 +1,14 @@
-import type { DiffResultDto, SnapshotDto } from '$lib/types/index';
+import type {
+  DiffResultDto,
+  FileHistoryDto,
+  FindQuery,
+  FindResultDto,
+  SnapshotDiffDto,
+  SnapshotDiffOptions,
+  SnapshotDto,
+  SnapshotPageDto,
+  SnapshotQuery,
+} from '$lib/types/index';
 import { invoke } from '@tauri-apps/api/core';
 
 /**
@@ -11,7 +21,11 @@ import { invoke } from '@tauri-apps/api/core';
  * - listSnapshots → src-tauri/src/lib.rs:96 (list_snapshots_command)
  * - getSnapshotInfo → src-tauri/src/lib.rs:84 (get_snapshot_command)
  * - deleteSnapshot → src-tauri/src/lib.rs:73 (delete_snapshot_command)
- * - compareSnapshots → ⏳ auskommentiert in lib.rs:422, stub in commands/snapshot.rs:26
+ * - compareSnapshots → commands/snapshot.rs (compare_snapshots)
+ * - diffSnapshots → commands/snapshot.rs (diff_snapshots, mit Paging und Rollups)
+ * - findInSnapshots → commands/snapshot.rs (find_in_snapshots)
+ * - getFileHistory → commands/snapshot.rs (get_file_history)
+ * - querySnapshots → commands/snapshot.rs (query_snapshots_command, Filter/Sortierung/Paging)
  *
  * ⚠️ Hinweis: Backend-Implementations sind teilweise Stubs (siehe TODOs in Rust-Code)
  *
@@ -72,6 +86,30 @@ export async function compareSnapshots(
   return await invoke<DiffResultDto>('compare_snapshots', { snapshotIdA, snapshotIdB, password });
 }
 
+/**
+ * Detaillierter Snapshot-Vergleich mit Änderungsklassen, Verzeichnis-Rollups und Paging.
+ *
+ * @param snapshotIdA - ID des älteren Snapshots
+ * @param snapshotIdB - ID des neueren Snapshots
+ * @param password - Repository-Passwort
+ * @param options - Präfix-Filter, Paging und Rollup-Tiefe
+ * @returns Promise mit einer Seite des Diffs und der Gesamtstatistik
+ * @throws Error wenn Snapshots oder Präfix nicht gefunden werden
+ */
+export async function diffSnapshots(
+  snapshotIdA: string,
+  snapshotIdB: string,
+  password: string,
+  options?: SnapshotDiffOptions
+): Promise<SnapshotDiffDto> {
+  return await invoke<SnapshotDiffDto>('diff_snapshots', {
+    snapshotIdA,
+    snapshotIdB,
+    password,
+    options,
+  });
+}
+
 /**
  * Fügt Tags zu einem Snapshot hinzu.
  *
@@ -97,17 +135,29 @@ export async function removeSnapshotTags(snapshotId: string, tags: string[]): Pr
 }
 
 /**
- * Snapshot-Filter-Optionen
+ * Snapshot-Filter-Optionen (alle gesetzten Kriterien müssen zutreffen)
  */
 export interface SnapshotFilter {
-  /** Filter nach Tags (OR-Logic: wenn irgendeiner passt) */
+  /** Filter nach Tags (Verknüpfung über tag_mode) */
   tags?: string[];
+  /** 'any' (Standard): einer der Tags, 'all': alle Tags */
+  tag_mode?: 'any' | 'all';
+  /** Tag-Ausdruck: mindestens eine Gruppe muss vollständig passen (OR über AND-Gruppen) */
+  tag_groups?: string[][];
   /** Filter nach Hostname */
   hostname?: string;
-  /** Filter nach Zeitraum (von) - ISO 8601 String */
-  timeFrom?: string;
-  /** Filter nach Zeitraum (bis) - ISO 8601 String */
-  timeTo?: string;
+  /** Filter nach einem von mehreren Hostnamen */
+  hostnames?: string[];
+  /** Filter nach einem von mehreren Benutzernamen */
+  usernames?: string[];
+  /** Mindestens ein gesicherter Pfad enthält diesen Text */
+  path_contains?: string;
+  /** Filter nach Zeitraum (von) - ISO 8601 oder YYYY-MM-DD */
+  time_from?: string;
+  /** Filter nach Zeitraum (bis) - ISO 8601 oder YYYY-MM-DD (ganzer Tag inklusive) */
+  time_to?: string;
+  /** Snapshot-ID beginnt mit diesem Präfix */
+  id_prefix?: string;
 }
 
 /**
@@ -116,17 +166,89 @@ export interface SnapshotFilter {
  * @param repositoryPath - Repository-Pfad
  * @param password - Repository-Passwort
  * @param filter - Optionale Filter-Optionen
- * @returns Promise mit gefilterten Snapshots
- * @throws Error wenn Abfrage fehlschlägt
+ * @returns Promise mit gefilterten Snapshots (neueste zuerst)
+ * @throws Error wenn Abfrage fehlschlägt oder eine Zeitangabe ungültig ist
  */
 export async function listSnapshotsFiltered(
   repositoryPath: string,
   password: string,
   filter?: SnapshotFilter
 ): Promise<SnapshotDto[]> {
-  return await invoke<SnapshotDto[]>('list_snapshots_filtered', {
+  return await invoke<SnapshotDto[]>('list_snapshots_filtered_command', {
+    repositoryPath,
+    password,
+    filter,
+  });
+}
+
+/**
+ * Filtert, sortiert und paginiert Snapshots im Backend.
+ *
+ * @param repositoryPath - Repository-Pfad
+ * @param password - Repository-Passwort
+ * @param query - Sortierung und Paging
+ * @param filter - Optionale Filter-Optionen
+ * @returns Promise mit einer Seite Snapshots und der Gesamtanzahl der Treffer
+ * @throws Error wenn Abfrage fehlschlägt oder eine Zeitangabe ungültig ist
+ */
+export async function querySnapshots(
+  repositoryPath: string,
+  password: string,
+  query: SnapshotQuery = {},
+  filter?: SnapshotFilter
+): Promise<SnapshotPageDto> {
+  return await invoke<SnapshotPageDto>('query_snapshots_command', {
+    repositoryPath,
+    password,
+    filter,
+    query,
+  });
+}
+
+/**
+ * Sucht Dateien über alle (bzw. gefilterten) Snapshots.
+ *
+ * @param repositoryPath - Repository-Pfad
+ * @param password - Repository-Passwort
+ * @param query - Muster (Glob/Regex) sowie Größen- und Zeitfilter
+ * @param filter - Optionaler Snapshot-Filter
+ * @returns Promise mit allen Snapshot-Versionen, in denen passende Dateien vorkommen
+ * @throws Error wenn das Muster ungültig ist oder die Suche fehlschlägt
+ */
+export async function findInSnapshots(
+  repositoryPath: string,
+  password: string,
+  query: FindQuery,
+  filter?: SnapshotFilter
+): Promise<FindResultDto> {
+  return await invoke<FindResultDto>('find_in_snapshots', {
+    repositoryPath,
+    password,
+    query,
+    filter,
+  });
+}
+
+/**
+ * Lädt die Versionsgeschichte einer Datei über alle (bzw. gefilterten) Snapshots.
+ *
+ * @param repositoryPath - Repository-Pfad
+ * @param password - Repository-Passwort
+ * @param path - Pfad der Datei im Snapshot
+ * @param filter - Optionaler Snapshot-Filter
+ * @returns Promise mit den unterschiedlichen Versionen (neueste zuerst)
+ * @throws Error wenn das Repository nicht gelesen werden kann
+ */
+export async function getFileHistory(
+  repositoryPath: string,
+  password: string,
+  path: string,
+  filter?: SnapshotFilter
+): Promise<FileHistoryDto> {
+  return await invoke<FileHistoryDto>('get_file_history', {
     repositoryPath,
     password,
+    path,
     filter,
   });
 }
@@ -1,4 +1,9 @@
-import type { PortableStoreStatus } from '$lib/types';
+import type {
+  PortableStoreStatus,
+  RepositoryHealth,
+  RepositoryLocks,
+  UnlockResult,
+} from '$lib/types';
 import { invoke } from '@tauri-apps/api/core';
 
 /**
@@ -9,3 +14,46 @@ import { invoke } from '@tauri-apps/api/core';
 export async function getPortableStoreStatus(): Promise<PortableStoreStatus> {
   return await invoke<PortableStoreStatus>('get_portable_status');
 }
+
+/**
+ * Prüft ein Repository: Status 'Locked', wenn Lock-Dateien vorliegen.
+ *
+ * @param repositoryId - Repository-ID aus Config
+ * @returns Promise mit Status, Locks und ggf. Fehlermeldung
+ */
+export async function checkRepositoryHealth(repositoryId: string): Promise<RepositoryHealth> {
+  return await invoke<RepositoryHealth>('check_repository_health', { repositoryId });
+}
+
+/**
+ * Listet die Lock-Dateien eines Repositories mit Host, PID und Alter auf.
+ *
+ * @param repositoryId - Repository-ID aus Config
+ * @returns Promise mit den Locks (nur bei lokalen Repositories lesbar)
+ */
+export async function listRepositoryLocks(repositoryId: string): Promise<RepositoryLocks> {
+  return await invoke<RepositoryLocks>('list_repository_locks', { repositoryId });
+}
+
+/**
+ * Entfernt veraltete Locks eines Repositories.
+ *
+ * Locks, die jünger als `minAgeSecs` sind (Standard: 30 Minuten) oder von einem noch
+ * laufenden Prozess dieses Rechners gehalten werden, bleiben erhalten.
+ *
+ * @param repositoryId - Repository-ID aus Config
+ * @param minAgeSecs - Mindestalter der zu entfernenden Locks in Sekunden (optional)
+ * @param lockIds - Nur diese Locks entfernen (optional)
+ * @returns Promise mit entfernten und übersprungenen Locks
+ */
+export async function forceUnlockRepository(
+  repositoryId: string,
+  minAgeSecs?: number,
+  lockIds?: string[]
+): Promise<UnlockResult> {
+  return await invoke<UnlockResult>('force_unlock_repository', {
+    repositoryId,
+    minAgeSecs,
+    lockIds,
+  });
+}
@@ -43,6 +43,64 @@ export type RepositoryType = 'Local' | 'Sftp' | 'S3' | 'Rest' | 'Rclone';
  */
 export type RepositoryStatus = 'Healthy' | 'Warning' | 'Unavailable' | 'Locked';
 
+/**
+ * Lock-Datei eines Repositories (von restic angelegt).
+ */
+export interface LockInfo {
+  /** ID der Lock-Datei */
+  id: string;
+  /** Host, der das Lock hält */
+  hostname: string | null;
+  /** Benutzer, der das Lock hält */
+  username: string | null;
+  /** Prozess-ID auf dem Host */
+  pid: number | null;
+  /** Exklusives Lock (z.B. prune) */
+  exclusive: boolean;
+  /** Zeitpunkt des Locks (RFC3339) */
+  time: string;
+  /** Alter in Sekunden */
+  age_seconds: number;
+  /** Lock stammt von diesem Rechner */
+  local_host: boolean;
+  /** Ob der Prozess noch läuft (nur für Locks dieses Rechners bekannt) */
+  process_alive: boolean | null;
+  /** Lock gilt als veraltet und kann entfernt werden */
+  stale: boolean;
+  /** Fehler beim Lesen der Lock-Datei */
+  error: string | null;
+}
+
+/**
+ * Lock-Dateien eines Repositories.
+ */
+export interface RepositoryLocks {
+  /** Ob Locks für das Backend gelesen werden können (nur lokale Repositories) */
+  supported: boolean;
+  locks: LockInfo[];
+}
+
+/**
+ * Ergebnis von `forceUnlockRepository`.
+ */
+export interface UnlockResult {
+  /** Entfernte Lock-Dateien */
+  removed: string[];
+  /** Nicht entfernte Lock-Dateien mit Grund */
+  skipped: { id: string; reason: 'process_alive' | 'too_recent' | 'not_found' | 'unreadable' }[];
+}
+
+/**
+ * Ergebnis des Health-Checks eines Repositories.
+ */
+export interface RepositoryHealth {
+  status: RepositoryStatus;
+  /** Lock-Dateien (bei 'Locked') */
+  locks: RepositoryLocks;
+  /** Fehlermeldung (bei 'Unavailable') bzw. Hinweis (bei 'Warning', z.B. Locks nicht prüfbar) */
+  message: string | null;
+}
+
 /**
  * Statusinformationen für den portablen Konfigurationsspeicher.
  */
@@ -127,10 +185,40 @@ export interface BackupJobDto {
   next_run?: string;
   /** Retention-Policy für alte Snapshots */
   retention?: RetentionPolicy;
+  /** Verhalten bei verpassten geplanten Läufen */
+  catch_up?: CatchUpPolicy;
+  /** Wartungsschritte nach erfolgreichem Backup */
+  post_backup?: PostBackupOptions;
   /** Repository-Passwort (optional, verschlüsselt gespeichert in Config) */
   password?: string;
 }
 
+/**
+ * Änderungen an einem Backup-Job (`update_backup_job`).
+ *
+ * Nicht gesetzte Felder bleiben unverändert.
+ */
+export interface BackupJobUpdate {
+  /** Neuer Anzeigename */
+  name?: string;
+  /** Neue Quellpfade */
+  source_paths?: string[];
+  /** Neue Ausschlussmuster */
+  exclude_patterns?: string[];
+  /** Neue Tags */
+  tags?: string[];
+  /** Neuer Cron-Ausdruck (leerer String entfernt den Zeitplan) */
+  schedule?: string;
+  /** Neue Retention-Policy */
+  retention?: RetentionPolicy;
+  /** Job aktiviert/deaktiviert */
+  enabled?: boolean;
+  /** Verhalten bei verpassten geplanten Läufen */
+  catch_up?: CatchUpPolicy;
+  /** Wartungsschritte nach erfolgreichem Backup */
+  post_backup?: PostBackupOptions;
+}
+
 /**
  * Retention-Policy für Snapshot-Verwaltung.
  *
@@ -147,8 +235,142 @@ export interface RetentionPolicy {
   keep_monthly?: number;
   /** Behalte jährliche Snapshots (Jahre) */
   keep_yearly?: number;
+  /** Behalte stündliche Snapshots (Stunden) */
+  keep_hourly?: number;
+  /** Behalte alle Snapshots innerhalb dieser Dauer (z.B. "7d", "2w 3d") */
+  keep_within?: string;
+  /** Behalte Snapshots mit diesen Tag-Listen (je Eintrag kommagetrennt) */
+  keep_tags?: string[];
+  /** Behalte Snapshots mit diesen IDs */
+  keep_ids?: string[];
+  /** Gruppierung, innerhalb der die Policy angewendet wird (Standard: Host + Pfade) */
+  group_by?: RetentionGroupBy;
+}
+
+/**
+ * Gruppierungskriterien für die Retention-Policy.
+ */
+export interface RetentionGroupBy {
+  hostname: boolean;
+  paths: boolean;
+  tags: boolean;
+}
+
+/**
+ * Wartungsschritte nach einem erfolgreichen Backup eines Jobs.
+ */
+export interface PostBackupOptions {
+  /** Retention-Policy des Jobs auf dessen Snapshots anwenden */
+  apply_retention: boolean;
+  /** Prune höchstens alle N Tage ausführen (null = nie) */
+  prune_interval_days?: number | null;
 }
 
+/**
+ * Verhalten bei verpassten geplanten Läufen.
+ *
+ * Standard ist `run_once`: ein verpasster Lauf wird nach dem Start nachgeholt.
+ */
+export type CatchUpPolicy =
+  | { type: 'skip' }
+  | { type: 'run_once' }
+  | { type: 'run_if_older_than'; max_age_hours: number };
+
+/**
+ * Eintrag der Backup-Warteschlange (laufend oder wartend).
+ *
+ * Backend: scheduler/queue.rs (Event: `job-queue-changed`)
+ */
+export interface QueueEntry {
+  job_id: string;
+  /** Repository-ID (oder Pfad bei Ad-hoc-Backups) */
+  repository: string;
+  priority: 'low' | 'normal' | 'high';
+  state: 'queued' | 'running';
+  /** ISO 8601 */
+  enqueued_at: string;
+  /** ISO 8601, fehlt solange der Lauf wartet */
+  started_at?: string;
+}
+
+/**
+ * Umgang mit bereits vorhandenen Dateien beim Restore.
+ */
+export type OverwritePolicy = 'always' | 'if_changed' | 'if_newer' | 'never';
+
+/**
+ * Restore-Plan: was ein Restore im Ziel anlegt, ändert und löscht.
+ *
+ * Ergebnis eines Dry-Runs bzw. Beschreibung des ausgeführten Restores.
+ */
+export interface RestorePlanDto {
+  dry_run: boolean;
+  /** Neu anzulegende Dateien (relativ zum Ziel) */
+  files_to_create: string[];
+  /** Vorhandene Dateien, die überschrieben werden */
+  files_to_update: string[];
+  /** Wegen der Overwrite-Policy übersprungene Dateien */
+  files_skipped: string[];
+  files_unchanged: number;
+  dirs_to_create: number;
+  /** Zusätzliche Einträge, die gelöscht werden (nur im Dry-Run ermittelt) */
+  paths_to_delete: string[];
+  bytes_to_write: number;
+  bytes_unchanged: number;
+}
+
+/**
+ * Archivformat für den Restore in eine Archivdatei.
+ */
+export type ArchiveFormat = 'tar' | 'tar_gz' | 'tar_zstd' | 'zip';
+
+/**
+ * Ergebnis eines Restores in eine Archivdatei.
+ */
+export interface ArchiveResultDto {
+  output_path: string;
+  format: ArchiveFormat;
+  files: number;
+  dirs: number;
+  /** Nicht archivierbare Einträge (Geräte, FIFOs, Sockets) */
+  skipped: string[];
+  /** Geschriebene Nutzdaten (unkomprimiert) */
+  bytes: number;
+  /** Größe der Archivdatei */
+  archive_size: number;
+}
+
+/**
+ * Vorschau auf den Anfang einer Datei aus einem Snapshot.
+ */
+export interface FilePreviewDto {
+  path: string;
+  size: number;
+  /** ISO 8601 */
+  modified?: string;
+  /** Aus der Dateiendung abgeleitet */
+  mime_type?: string;
+  /** Inhalt ist als Text darstellbar */
+  is_text: boolean;
+  content_base64: string;
+  bytes_read: number;
+  /** Datei ist größer als der gelesene Ausschnitt */
+  truncated: boolean;
+}
+
+/**
+ * Ergebnis des Speicherns einer einzelnen Datei aus einem Snapshot.
+ */
+export interface ExtractedFileDto {
+  output_path: string;
+  bytes: number;
+}
+
+/**
+ * Anordnung ausgewählter Pfade im Zielverzeichnis eines Restores.
+ */
+export type RestoreLayout = 'absolute' | 'relative';
+
 /**
  * Restore-Optionen für Wiederherstellung.
  *
@@ -161,14 +383,18 @@ export interface RestoreOptionsDto {
   target_path: string;
   /** Spezifische Pfade (leer = alles) */
   paths: string[];
-  /** Existierende Dateien überschreiben */
-  overwrite: boolean;
+  /** Umgang mit vorhandenen Dateien (Standard: if_changed) */
+  overwrite: OverwritePolicy;
+  /** Zusätzliche Dateien im Ziel löschen (nur kompletter Snapshot) */
+  delete?: boolean;
   /** Dateiberechtigungen wiederherstellen */
   restore_permissions: boolean;
   /** Zeitstempel wiederherstellen */
   restore_timestamps: boolean;
   /** Dry-Run-Modus (keine Änderungen) */
   dry_run: boolean;
+  /** Anordnung der Auswahl im Ziel: Originalpfade oder relativ zur Auswahl (Standard: absolute) */
+  layout?: RestoreLayout;
 }
 
 /**
@@ -259,8 +485,20 @@ export interface FileTreeNode {
   size?: number;
   /** Letztes Änderungsdatum (ISO 8601) */
   modified?: string;
-  /** Untergeordnete Elemente (bei Verzeichnissen) */
+  /** Untergeordnete Elemente (bei Verzeichnissen; fehlt bei noch nicht geladenen Verzeichnissen) */
   children?: FileTreeNode[];
+  /** Art des Knotens */
+  node_type?: 'file' | 'dir' | 'symlink' | 'dev' | 'chardev' | 'fifo' | 'socket';
+  /** Unix-Berechtigungen */
+  mode?: number;
+  /** Besitzer (Name oder UID) */
+  owner?: string;
+  /** Gruppe (Name oder GID) */
+  group?: string;
+  /** Ziel eines symbolischen Links */
+  link_target?: string;
+  /** Gerätenummer bei Geräte-Dateien */
+  device?: number;
 }
 
 /**
@@ -291,6 +529,195 @@ export interface DiffStats {
   total_size_change: number;
 }
 
+/**
+ * Art des Suchmusters für findInSnapshots.
+ */
+export type FindPatternKind = 'glob' | 'regex';
+
+/**
+ * Suchanfrage für Dateien über mehrere Snapshots.
+ *
+ * Muster ohne "/" gelten für den Dateinamen, sonst für den Pfad im Snapshot.
+ */
+export interface FindQuery {
+  pattern: string;
+  kind?: FindPatternKind;
+  case_insensitive?: boolean;
+  min_size?: number;
+  max_size?: number;
+  /** RFC 3339 */
+  modified_after?: string;
+  /** RFC 3339 */
+  modified_before?: string;
+  /** Maximale Anzahl Treffer (Standard 10000) */
+  limit?: number;
+}
+
+/**
+ * Eine gefundene Datei in einem Snapshot.
+ */
+export interface FileMatchDto {
+  snapshot_id: string;
+  /** ISO 8601 */
+  snapshot_time: string;
+  hostname: string;
+  path: string;
+  size: number;
+  /** ISO 8601 */
+  modified?: string;
+  /** Inhaltskennung; gleiche Werte = identische Version */
+  version: string;
+}
+
+/**
+ * Ergebnis einer Suche über Snapshots.
+ */
+export interface FindResultDto {
+  /** Neueste Snapshots zuerst */
+  matches: FileMatchDto[];
+  snapshots_searched: number;
+  trees_scanned: number;
+  /** Trefferlimit erreicht */
+  truncated: boolean;
+}
+
+/**
+ * Eine Version einer Datei (aufeinanderfolgende Snapshots mit gleichem Inhalt).
+ */
+export interface FileVersionDto {
+  /** Inhaltskennung (wie FileMatchDto.version) */
+  version: string;
+  size: number;
+  /** ISO 8601, aus dem ersten Snapshot der Version */
+  modified?: string;
+  first_snapshot_id: string;
+  /** ISO 8601 */
+  first_snapshot_time: string;
+  last_snapshot_id: string;
+  /** ISO 8601 */
+  last_snapshot_time: string;
+  snapshot_count: number;
+}
+
+/**
+ * Versionsgeschichte einer Datei über alle Snapshots.
+ */
+export interface FileHistoryDto {
+  path: string;
+  /** Neueste Version zuerst */
+  versions: FileVersionDto[];
+  snapshots_searched: number;
+  snapshots_containing: number;
+}
+
+/**
+ * Sortierschlüssel für die Snapshot-Liste.
+ */
+export type SnapshotSortKey = 'time' | 'hostname' | 'size' | 'files' | 'id';
+
+/**
+ * Sortierung und Paging für die serverseitige Snapshot-Abfrage.
+ */
+export interface SnapshotQuery {
+  /** Standard: 'time' */
+  sort?: SnapshotSortKey;
+  /** Standard: absteigend (neueste zuerst) */
+  ascending?: boolean;
+  offset?: number;
+  /** Standard: 500 */
+  limit?: number;
+}
+
+/**
+ * Eine Seite der gefilterten und sortierten Snapshot-Liste.
+ */
+export interface SnapshotPageDto {
+  snapshots: SnapshotDto[];
+  /** Anzahl aller Snapshots, die dem Filter entsprechen */
+  total: number;
+  offset: number;
+  has_more: boolean;
+}
+
+/**
+ * Art eines Eintrags im detaillierten Snapshot-Diff.
+ */
+export type DiffEntryKind = 'added' | 'removed' | 'changed';
+
+/**
+ * Geänderte Eigenschaft eines Eintrags, der in beiden Snapshots existiert.
+ */
+export type DiffChangeClass = 'type' | 'content' | 'size' | 'mtime' | 'permissions' | 'owner';
+
+/**
+ * Ein Eintrag im detaillierten Snapshot-Diff.
+ */
+export interface DiffEntryDto {
+  path: string;
+  kind: DiffEntryKind;
+  /** Geänderte Eigenschaften (nur bei 'changed') */
+  changes: DiffChangeClass[];
+  is_directory: boolean;
+  size_before?: number;
+  size_after?: number;
+  size_delta: number;
+}
+
+/**
+ * Aufsummierte Änderungen unterhalb eines Verzeichnisses.
+ */
+export interface DirectoryDiffDto {
+  path: string;
+  added: number;
+  removed: number;
+  changed: number;
+  size_delta: number;
+}
+
+/**
+ * Gesamtstatistik eines detaillierten Snapshot-Diffs (über alle Seiten).
+ */
+export interface SnapshotDiffStats {
+  added: number;
+  removed: number;
+  /** Inhalt, Größe oder Typ geändert */
+  changed: number;
+  /** Nur Zeit, Rechte oder Besitzer geändert */
+  metadata_only: number;
+  bytes_added: number;
+  bytes_removed: number;
+  size_delta: number;
+}
+
+/**
+ * Optionen für den detaillierten Snapshot-Diff.
+ */
+export interface SnapshotDiffOptions {
+  /** Nur Einträge unterhalb dieses Pfades */
+  path_prefix?: string;
+  offset?: number;
+  /** Einträge pro Seite (Standard 1000) */
+  limit?: number;
+  /** Reine Metadaten-Änderungen ignorieren */
+  ignore_metadata?: boolean;
+  /** Tiefe der Verzeichnis-Rollups (Standard 2) */
+  rollup_depth?: number;
+}
+
+/**
+ * Eine Seite des detaillierten Snapshot-Diffs.
+ */
+export interface SnapshotDiffDto {
+  snapshot_a: string;
+  snapshot_b: string;
+  entries: DiffEntryDto[];
+  total_entries: number;
+  offset: number;
+  has_more: boolean;
+  stats: SnapshotDiffStats;
+  directories: DirectoryDiffDto[];
+}
+
 /**
  * Check-Ergebnis für Repository-Integritätsprüfung.
  *
@@ -303,6 +730,162 @@ export interface CheckResultDto {
   warnings: string[];
   /** Gesamt-Status: true = keine kritischen Fehler */
   is_ok: boolean;
+  /** Strukturierte Befunde (höchstens 1000, siehe `findings_truncated`) */
+  findings: CheckFinding[];
+  /** Weitere Befunde wurden nur gezählt, nicht aufgelistet */
+  findings_truncated: boolean;
+  /** Anzahl Befunde pro Kategorie */
+  summary: CheckSummary;
+  /** Anzahl geprüfter Pack-Dateien (Index gegen Backend) */
+  packs_checked: number;
+  /** Anzahl geprüfter Snapshots */
+  snapshots_checked: number;
+  /** Anzahl gelesener Trees */
+  trees_checked: number;
+  /** Anzahl vollständig gelesener Pack-Dateien (read-data) */
+  packs_read: number;
+  /** Gelesene Bytes (read-data) */
+  bytes_read: number;
+}
+
+/**
+ * Kategorie eines Befunds der Repository-Prüfung.
+ */
+export type CheckCategory =
+  | 'missing_pack'
+  | 'unreferenced_pack'
+  | 'index_mismatch'
+  | 'snapshot_error'
+  | 'tree_error'
+  | 'damaged_blob';
+
+/**
+ * Ein Befund der Repository-Prüfung.
+ */
+export interface CheckFinding {
+  category: CheckCategory;
+  /** 'error' = Datenverlust/Inkonsistenz, 'warning' = unkritisch */
+  severity: 'error' | 'warning';
+  /** Betroffene Pack-, Blob- oder Tree-ID */
+  id: string | null;
+  /** Betroffener Snapshot (nur bei Tree-Fehlern) */
+  snapshot_id: string | null;
+  message: string;
+}
+
+/**
+ * Anzahl Befunde pro Kategorie.
+ */
+export interface CheckSummary {
+  missing_packs: number;
+  unreferenced_packs: number;
+  index_mismatches: number;
+  snapshot_errors: number;
+  tree_errors: number;
+  damaged_blobs: number;
+}
+
+/**
+ * Teilmenge der Pack-Dateien, die bei read-data (zufällig ausgewählt) gelesen wird.
+ */
+export type ReadDataSubset =
+  | { type: 'all' }
+  | { type: 'percentage'; percent: number }
+  | { type: 'bytes'; max_bytes: number };
+
+/**
+ * Fortschritt der Repository-Prüfung (Event `check-progress`).
+ */
+export interface CheckProgress extends ProgressInfo {
+  /** Aktuelle Phase der Prüfung */
+  phase: 'index' | 'packs' | 'trees' | 'read_data';
+}
+
+/**
+ * Plan (und Ergebnis) der Index-Reparatur.
+ *
+ * Ausgeführt wird nur mit der bestätigten `plan_id` eines vorherigen Dry-Runs.
+ */
+export interface RepairIndexPlanDto {
+  /** Kennung des Plans; muss beim Ausführen bestätigt werden */
+  plan_id: string;
+  /** Ob nur geplant wurde (keine Änderungen am Repository) */
+  dry_run: boolean;
+  /** Alle Pack-Header neu lesen (Index komplett neu aufbauen) */
+  read_all: boolean;
+  /** Anzahl Index-Dateien */
+  index_files: number;
+  /** Anzahl Index-Dateien, die ersetzt werden */
+  index_files_changed: number;
+  /** Packs im Index, die im Backend fehlen */
+  packs_missing: number;
+  /** Packs, die in mehreren Index-Dateien stehen */
+  packs_duplicate: number;
+  /** Packs, deren Größe nicht zum Index passt */
+  packs_size_mismatch: number;
+  /** Packs im Backend, die in keinem Index stehen */
+  packs_unindexed: number;
+  /** Packs, deren Header gelesen werden */
+  packs_to_read: number;
+}
+
+/**
+ * Optionen der Snapshot-Reparatur.
+ */
+export interface RepairSnapshotsOptions {
+  /** Zu prüfende Snapshots (leer = alle) */
+  snapshot_ids?: string[];
+  /** Beschädigte Originale und nicht reparierbare Snapshots löschen */
+  delete?: boolean;
+  /** Suffix für Dateien und Verzeichnisse mit fehlendem Inhalt (Standard: ".repaired") */
+  suffix?: string;
+  /** Tags für reparierte Snapshots (Standard: ["repaired"]) */
+  tags?: string[];
+}
+
+/**
+ * Befund der Snapshot-Reparatur für einen Snapshot.
+ */
+export interface SnapshotRepairDto {
+  snapshot_id: string;
+  time: string;
+  hostname: string;
+  paths: string[];
+  /**
+   * 'repair' = reparierte Kopie, 'remove' = Root-Tree nicht lesbar (wird entfernt),
+   * 'unrepairable' = Root-Tree nicht lesbar (bleibt ohne `delete` unverändert)
+   */
+  action: 'keep' | 'repair' | 'remove' | 'unrepairable';
+  /** Nicht lesbare Trees */
+  damaged_trees: number;
+  /** Dateien mit fehlenden Blobs */
+  damaged_files: number;
+  /** Dateien, deren Größe korrigiert wird */
+  corrected_sizes: number;
+}
+
+/**
+ * Plan (und Ergebnis) der Snapshot-Reparatur.
+ */
+export interface RepairSnapshotsPlanDto {
+  /** Kennung des Plans; muss beim Ausführen bestätigt werden */
+  plan_id: string;
+  /** Ob nur geplant wurde (keine Änderungen am Repository) */
+  dry_run: boolean;
+  /** Anzahl geprüfter Snapshots */
+  snapshots_checked: number;
+  /** Snapshots, die repariert oder entfernt werden */
+  snapshots: SnapshotRepairDto[];
+  /** Anzahl Snapshots, die gelöscht werden (nur mit `delete`) */
+  snapshots_removed: number;
+}
+
+/**
+ * Fortschritt einer Reparatur (Event `repair-progress`).
+ */
+export interface RepairProgress extends ProgressInfo {
+  /** 'planning' = Dry-Run/Planung, 'applying' = Ausführung */
+  stage: 'planning' | 'applying';
 }
 
 /**